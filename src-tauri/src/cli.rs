@@ -0,0 +1,121 @@
+//! Command-line subcommands that run without starting the tray app:
+//!
+//! ```text
+//! quotabar export --dataset cost|history --output <path> [--format csv|jsonl]
+//!                 [--source claude|codex|cursor|all] [--range <preset>]
+//!                 [--since YYYY-MM-DD] [--until YYYY-MM-DD] [--timezone <tz>]
+//! ```
+
+use crate::services::cost_range::CostRangeRequest;
+use crate::services::export::{self, ExportRequest};
+
+const EXPORT_USAGE: &str = "Usage: quotabar export --dataset cost|history --output <path> \
+[--format csv|jsonl] [--source claude|codex|cursor|all] [--range <preset>] \
+[--since YYYY-MM-DD] [--until YYYY-MM-DD] [--timezone <tz>]";
+
+fn parse_export_args(args: &[String]) -> Result<ExportRequest, String> {
+    let mut request = ExportRequest::default();
+    let mut preset = None;
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{flag} needs a value"))
+        };
+        match flag.as_str() {
+            "--dataset" => request.dataset = value()?,
+            "--output" | "-o" => request.path = value()?,
+            "--format" => request.format = Some(value()?),
+            "--source" => request.source = Some(value()?),
+            "--range" => preset = Some(value()?),
+            "--since" => request.since = Some(value()?),
+            "--until" => request.until = Some(value()?),
+            "--timezone" => request.timezone = Some(value()?),
+            other => return Err(format!("Unknown option '{other}'")),
+        }
+    }
+    if request.dataset.is_empty() || request.path.is_empty() {
+        return Err("--dataset and --output are required".to_string());
+    }
+    // For cost exports `--since`/`--until` describe a custom range.
+    if request.dataset.trim().eq_ignore_ascii_case("cost")
+        && (preset.is_some() || request.since.is_some())
+    {
+        request.range = Some(CostRangeRequest {
+            preset,
+            since: request.since.clone(),
+            until: request.until.clone(),
+            compare: false,
+        });
+    }
+    Ok(request)
+}
+
+/// Run a subcommand and return its exit code, or `None` when `args` (without
+/// the program name) do not name one and the app should start normally.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    match command.as_str() {
+        "export" => {
+            if rest.iter().any(|arg| arg == "--help" || arg == "-h") {
+                println!("{EXPORT_USAGE}");
+                return Some(0);
+            }
+            let request = match parse_export_args(rest) {
+                Ok(request) => request,
+                Err(error) => {
+                    eprintln!("{error}\n{EXPORT_USAGE}");
+                    return Some(2);
+                }
+            };
+            match export::write_export(&request, false) {
+                Ok(summary) => {
+                    println!(
+                        "Wrote {} {} rows to {} ({})",
+                        summary.rows, summary.dataset, summary.path, summary.format
+                    );
+                    Some(0)
+                }
+                Err(error) => {
+                    eprintln!("Export failed: {error}");
+                    Some(1)
+                }
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_cost_export_with_custom_range() {
+        let request = parse_export_args(&args(&[
+            "--dataset",
+            "cost",
+            "-o",
+            "/tmp/cost.csv",
+            "--since",
+            "2026-05-01",
+            "--source",
+            "codex",
+        ]))
+        .unwrap();
+        assert_eq!(request.path, "/tmp/cost.csv");
+        assert_eq!(request.source.as_deref(), Some("codex"));
+        let range = request.range.unwrap();
+        assert_eq!(range.since.as_deref(), Some("2026-05-01"));
+        assert!(range.preset.is_none());
+
+        assert!(parse_export_args(&args(&["--dataset", "cost"])).is_err());
+        assert!(parse_export_args(&args(&["--dataset"])).is_err());
+        assert_eq!(run(&args(&["--minimized"])), None);
+    }
+}
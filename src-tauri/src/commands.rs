@@ -2,9 +2,14 @@ use tauri::{AppHandle, State};
 
 use crate::{
     domain::models::{
-        AntigravityData, CodexData, CodexRateLimits, CodexStats, CursorData, QuotaData,
+        AntigravityData, ClaudeStats, CodexData, CodexRateLimits, CodexStats, CursorData,
+        CustomProviderData, QuotaData, WindsurfData,
+    },
+    services::{
+        anomaly, antigravity, cache_efficiency, calibration, claude, claude_blocks, claude_stats,
+        codex, cost, cost_range::CostRangeRequest, cost_series, cursor, custom_provider, export,
+        history, link, sessions, subscription, tray, window, windsurf,
     },
-    services::{antigravity, claude, codex, cost, cursor, link, tray, window},
 };
 
 #[tauri::command]
@@ -12,6 +17,19 @@ pub async fn get_quota() -> Result<QuotaData, String> {
     Ok(claude::fetch_quota().await)
 }
 
+#[tauri::command]
+pub async fn get_claude_stats() -> Result<ClaudeStats, String> {
+    Ok(claude_stats::fetch_claude_stats().await)
+}
+
+#[tauri::command]
+pub async fn get_claude_blocks(
+    hours: Option<u32>,
+    force: Option<bool>,
+) -> Result<claude_blocks::ClaudeBlocksReport, String> {
+    claude_blocks::get_claude_blocks(hours, force.unwrap_or(false)).await
+}
+
 #[tauri::command]
 pub async fn get_codex_info() -> Result<CodexData, String> {
     Ok(codex::fetch_codex_info().await)
@@ -32,19 +50,113 @@ pub async fn get_cursor_info() -> Result<CursorData, String> {
     Ok(cursor::fetch_cursor_info().await)
 }
 
+#[tauri::command]
+pub async fn get_windsurf_info() -> Result<WindsurfData, String> {
+    Ok(windsurf::fetch_windsurf_info().await)
+}
+
 #[tauri::command]
 pub async fn get_antigravity_info() -> Result<AntigravityData, String> {
     Ok(antigravity::fetch_antigravity_info().await)
 }
 
+#[tauri::command]
+pub async fn get_custom_providers(
+    app: AppHandle,
+    force: Option<bool>,
+) -> Result<Vec<CustomProviderData>, String> {
+    custom_provider::get_custom_providers(app, force.unwrap_or(false)).await
+}
+
 #[tauri::command]
 pub async fn get_cost_overview(
+    app: AppHandle,
     source: String,
     currency: Option<String>,
     timezone: Option<String>,
+    range: Option<CostRangeRequest>,
     force: Option<bool>,
 ) -> Result<cost::CostOverview, String> {
-    cost::get_cost_overview(source, currency, timezone, force).await
+    cost::get_cost_overview(app, source, currency, timezone, range, force).await
+}
+
+#[tauri::command]
+pub async fn get_cost_series(
+    source: String,
+    since: String,
+    until: Option<String>,
+    bucket: Option<String>,
+    timezone: Option<String>,
+    force: Option<bool>,
+) -> Result<cost_series::CostSeries, String> {
+    cost_series::get_cost_series(
+        source,
+        since,
+        until,
+        bucket,
+        timezone,
+        force.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn get_subscription_value(
+    months: Option<u32>,
+    force: Option<bool>,
+) -> Result<subscription::SubscriptionValueReport, String> {
+    subscription::get_subscription_value(months, force.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn get_quota_calibration(
+    days: Option<u32>,
+    force: Option<bool>,
+) -> Result<calibration::QuotaCalibrationReport, String> {
+    calibration::get_quota_calibration(days, force.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn get_cache_efficiency(
+    source: String,
+    since: Option<String>,
+    until: Option<String>,
+    timezone: Option<String>,
+    force: Option<bool>,
+) -> Result<cache_efficiency::CacheEfficiencyReport, String> {
+    cache_efficiency::get_cache_efficiency(source, since, until, timezone, force.unwrap_or(false))
+        .await
+}
+
+#[tauri::command]
+pub async fn list_sessions(
+    source: String,
+    since: Option<String>,
+    until: Option<String>,
+    query: Option<sessions::SessionQuery>,
+    force: Option<bool>,
+) -> Result<sessions::SessionList, String> {
+    sessions::list_sessions(
+        source,
+        since,
+        until,
+        query.unwrap_or_default(),
+        force.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn get_cost_anomalies(
+    since: Option<String>,
+    until: Option<String>,
+) -> Result<Vec<history::CostAnomaly>, String> {
+    anomaly::get_cost_anomalies(since, until).await
+}
+
+#[tauri::command]
+pub async fn export_data(request: export::ExportRequest) -> Result<export::ExportSummary, String> {
+    export::export_data(request).await
 }
 
 #[tauri::command]
@@ -62,6 +174,11 @@ pub fn open_cursor_dashboard() -> Result<(), String> {
     link::open_cursor_dashboard()
 }
 
+#[tauri::command]
+pub fn open_windsurf_dashboard() -> Result<(), String> {
+    link::open_windsurf_dashboard()
+}
+
 #[tauri::command]
 pub fn open_antigravity_dashboard() -> Result<(), String> {
     link::open_antigravity_dashboard()
@@ -83,9 +200,10 @@ pub async fn update_tray_icon(
     tray_state: State<'_, tray::TrayState>,
     service: tray::TrayService,
     percentage: Option<u8>,
+    windows: Option<tray::TrayWindows>,
     visible: bool,
 ) -> Result<(), String> {
-    tray::update_tray_icon(app, tray_state, service, percentage, visible).await
+    tray::update_tray_icon(app, tray_state, service, percentage, windows, visible).await
 }
 
 #[tauri::command]
@@ -102,6 +102,51 @@ impl CodexStats {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClaudeNamedCount {
+    pub name: String,
+    pub total: u32,
+    pub today: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClaudeStats {
+    #[serde(rename = "totalSessions")]
+    pub total_sessions: u32,
+    #[serde(rename = "todaySessions")]
+    pub today_sessions: u32,
+    #[serde(rename = "totalProjects")]
+    pub total_projects: u32,
+    #[serde(rename = "activeProjects")]
+    pub active_projects: u32,
+    #[serde(rename = "lastActivity")]
+    pub last_activity: Option<String>,
+    #[serde(rename = "toolCallsTotal")]
+    pub tool_calls_total: u32,
+    #[serde(rename = "toolCallsToday")]
+    pub tool_calls_today: u32,
+    #[serde(rename = "toolCalls")]
+    pub tool_calls: Vec<ClaudeNamedCount>,
+    #[serde(rename = "messagesByModel")]
+    pub messages_by_model: Vec<ClaudeNamedCount>,
+}
+
+impl ClaudeStats {
+    pub fn empty() -> Self {
+        Self {
+            total_sessions: 0,
+            today_sessions: 0,
+            total_projects: 0,
+            active_projects: 0,
+            last_activity: None,
+            tool_calls_total: 0,
+            tool_calls_today: 0,
+            tool_calls: Vec::new(),
+            messages_by_model: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CodexRateLimitWindow {
     #[serde(rename = "usedPercent")]
@@ -128,6 +173,11 @@ pub struct CodexRateLimits {
     pub primary: Option<CodexRateLimitWindow>,
     pub secondary: Option<CodexRateLimitWindow>,
     pub credits: Option<CodexCredits>,
+    /// `"api"` for a live `wham/usage` response, `"local"` for a snapshot
+    /// read from Codex session rollout files.
+    pub source: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<String>,
     pub error: Option<String>,
 }
 
@@ -139,6 +189,8 @@ impl CodexRateLimits {
             primary: None,
             secondary: None,
             credits: None,
+            source: None,
+            updated_at: None,
             error: Some(error.into()),
         }
     }
@@ -178,6 +230,43 @@ impl CursorData {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WindsurfData {
+    pub connected: bool,
+    #[serde(rename = "planType")]
+    pub plan_type: Option<String>,
+    pub email: Option<String>,
+    #[serde(rename = "promptUsed")]
+    pub prompt_used: Option<f64>,
+    #[serde(rename = "promptLimit")]
+    pub prompt_limit: Option<f64>,
+    #[serde(rename = "flowUsed")]
+    pub flow_used: Option<f64>,
+    #[serde(rename = "flowLimit")]
+    pub flow_limit: Option<f64>,
+    pub percentage: Option<f64>,
+    #[serde(rename = "resetAt")]
+    pub reset_at: Option<String>,
+    pub error: Option<String>,
+}
+
+impl WindsurfData {
+    pub fn disconnected(error: impl Into<String>) -> Self {
+        Self {
+            connected: false,
+            plan_type: None,
+            email: None,
+            prompt_used: None,
+            prompt_limit: None,
+            flow_used: None,
+            flow_limit: None,
+            percentage: None,
+            reset_at: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AntigravityData {
     pub connected: bool,
@@ -194,3 +283,59 @@ impl AntigravityData {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomProviderWindow {
+    pub id: Option<String>,
+    pub label: String,
+    pub used: Option<f64>,
+    pub limit: Option<f64>,
+    pub percentage: Option<f64>,
+    #[serde(rename = "resetTime")]
+    pub reset_time: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomProviderCost {
+    pub amount: f64,
+    pub currency: String,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomProviderData {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub connected: bool,
+    #[serde(rename = "planType")]
+    pub plan_type: Option<String>,
+    pub windows: Vec<CustomProviderWindow>,
+    pub cost: Option<CustomProviderCost>,
+    pub percentage: Option<f64>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<String>,
+    pub error: Option<String>,
+}
+
+impl CustomProviderData {
+    pub fn disconnected(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        kind: impl Into<String>,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            kind: kind.into(),
+            connected: false,
+            plan_type: None,
+            windows: Vec::new(),
+            cost: None,
+            percentage: None,
+            updated_at: None,
+            error: Some(error.into()),
+        }
+    }
+}
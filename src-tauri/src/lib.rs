@@ -1,3 +1,4 @@
+mod cli;
 mod commands;
 mod domain;
 mod services;
@@ -29,24 +30,43 @@ fn raise_fd_limit() {
 #[cfg(not(unix))]
 fn raise_fd_limit() {}
 
+/// Run a command-line subcommand such as `export`. Returns `None` when the
+/// arguments name none and the app should start.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    cli::run(args)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     raise_fd_limit();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(TrayState::default())
         .invoke_handler(tauri::generate_handler![
             commands::get_quota,
+            commands::get_claude_stats,
+            commands::get_claude_blocks,
             commands::get_codex_info,
             commands::get_codex_stats,
             commands::get_codex_rate_limits,
             commands::get_cursor_info,
+            commands::get_windsurf_info,
             commands::get_antigravity_info,
+            commands::get_custom_providers,
             commands::get_cost_overview,
+            commands::get_cost_series,
+            commands::get_subscription_value,
+            commands::get_quota_calibration,
+            commands::get_cost_anomalies,
+            commands::get_cache_efficiency,
+            commands::list_sessions,
+            commands::export_data,
             commands::open_claude_dashboard,
             commands::open_codex_dashboard,
             commands::open_cursor_dashboard,
+            commands::open_windsurf_dashboard,
             commands::open_antigravity_dashboard,
             commands::resize_window,
             commands::set_dock_visibility,
@@ -62,6 +82,9 @@ pub fn run() {
             }
 
             services::tray::setup_tray(app.handle())?;
+            services::custom_provider::start_custom_provider_poller(app.handle().clone());
+            services::cost_index::start_cost_index_watcher();
+            services::anomaly::start_anomaly_monitor(app.handle().clone());
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -0,0 +1,449 @@
+//! Spending anomalies: Claude Code and Codex hours or sessions whose
+//! API-equivalent cost far exceeds the user's own rolling baseline, e.g. a
+//! runaway agent burning a day's worth of tokens in an hour.
+//!
+//! Every few minutes the current and previous hour are compared with the
+//! active hours of the last week, and recently active sessions with the other
+//! sessions of the last month. The baseline is the median with its median
+//! absolute deviation, so earlier spikes barely move it. New anomalies are
+//! recorded in the history, emitted as [`COST_ANOMALY_EVENT`] and shown as a
+//! native notification, naming the project and model that spent the most.
+
+use crate::services::cost_index::{self, BucketKey, BucketTotals, IndexedSource};
+use crate::services::history::{self, CostAnomaly};
+use crate::services::notification;
+use crate::services::pricing::PriceTable;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub const COST_ANOMALY_EVENT: &str = "cost-anomaly";
+
+pub const ANOMALY_KIND_HOUR: &str = "hour";
+pub const ANOMALY_KIND_SESSION: &str = "session";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(300);
+const HOUR_BASELINE_DAYS: i64 = 7;
+const SESSION_BASELINE_DAYS: i64 = 30;
+/// Sessions active this recently are checked; older ones form the baseline.
+const RECENT_SESSION_HOURS: i64 = 2;
+const MIN_BASELINE_SAMPLES: usize = 8;
+/// A flagged hour or session costs at least this many times the median...
+const MIN_RATIO: f64 = 4.0;
+/// ...and this many (scaled) median absolute deviations above it...
+const MAD_FACTOR: f64 = 6.0;
+/// ...and at least this much in USD, so idle baselines do not flag pennies.
+const MIN_HOUR_COST_USD: f64 = 2.0;
+const MIN_SESSION_COST_USD: f64 = 10.0;
+
+/// Keys of anomalies already raised, seeded from the recorded history.
+static RAISED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn raised() -> &'static Mutex<HashSet<String>> {
+    RAISED.get_or_init(|| {
+        let since = Utc::now() - ChronoDuration::days(SESSION_BASELINE_DAYS);
+        let keys = history::load_anomalies(Some(since), None)
+            .unwrap_or_default()
+            .iter()
+            .map(anomaly_key)
+            .collect();
+        Mutex::new(keys)
+    })
+}
+
+fn anomaly_key(anomaly: &CostAnomaly) -> String {
+    match &anomaly.session_id {
+        Some(session) => format!("{}|{}|{session}", anomaly.kind, anomaly.source),
+        None => format!("{}|{}|{}", anomaly.kind, anomaly.source, anomaly.start),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Baseline {
+    median: f64,
+    mad: f64,
+}
+
+impl Baseline {
+    fn from_costs(mut costs: Vec<f64>) -> Option<Self> {
+        if costs.len() < MIN_BASELINE_SAMPLES {
+            return None;
+        }
+        let median = median_of(&mut costs);
+        let mut deviations: Vec<f64> = costs.iter().map(|cost| (cost - median).abs()).collect();
+        let mad = median_of(&mut deviations);
+        Some(Self { median, mad })
+    }
+
+    /// Cost at or above which a period is flagged.
+    fn threshold(&self, min_cost: f64) -> f64 {
+        // 1.4826 scales the MAD to a standard deviation for normal data.
+        (self.median * MIN_RATIO)
+            .max(self.median + MAD_FACTOR * 1.4826 * self.mad)
+            .max(min_cost)
+    }
+}
+
+fn median_of(values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+/// Spend of one hour or session, split by project and model.
+#[derive(Debug, Clone, Default)]
+struct Spend {
+    cost: f64,
+    first_at: Option<DateTime<Utc>>,
+    last_at: Option<DateTime<Utc>>,
+    by_culprit: HashMap<(String, String), f64>,
+}
+
+impl Spend {
+    fn add(
+        &mut self,
+        project: &str,
+        model: &str,
+        cost: f64,
+        first_at: DateTime<Utc>,
+        last_at: DateTime<Utc>,
+    ) {
+        self.cost += cost;
+        self.first_at = Some(self.first_at.map_or(first_at, |first| first.min(first_at)));
+        self.last_at = Some(self.last_at.map_or(last_at, |last| last.max(last_at)));
+        *self
+            .by_culprit
+            .entry((project.to_string(), model.to_string()))
+            .or_default() += cost;
+    }
+
+    fn culprit(&self) -> (String, String) {
+        self.by_culprit
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(key, _)| key.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Indexed spend of a source, per hour and per session.
+#[derive(Debug, Default)]
+struct SpendIndex {
+    hours: BTreeMap<i64, Spend>,
+    sessions: HashMap<String, Spend>,
+}
+
+impl SpendIndex {
+    fn add(&mut self, key: &BucketKey, totals: &BucketTotals, prices: &PriceTable) {
+        let Some(cost) = prices
+            .cost_usd(&key.model, &totals.tokens)
+            .filter(|cost| *cost > 0.0)
+        else {
+            return;
+        };
+        let first_at = totals.first_at.unwrap_or_else(|| key.start());
+        let last_at = totals.last_at.unwrap_or(first_at);
+        self.hours.entry(key.hour).or_default().add(
+            &key.project,
+            &key.model,
+            cost,
+            first_at,
+            last_at,
+        );
+        if !key.session_id.is_empty() {
+            self.sessions
+                .entry(key.session_id.clone())
+                .or_default()
+                .add(&key.project, &key.model, cost, first_at, last_at);
+        }
+    }
+}
+
+fn anomaly(
+    kind: &str,
+    source: IndexedSource,
+    spend: &Spend,
+    session_id: Option<&str>,
+    (start, end): (DateTime<Utc>, DateTime<Utc>),
+    baseline: &Baseline,
+    now: DateTime<Utc>,
+) -> CostAnomaly {
+    let (project, model) = spend.culprit();
+    CostAnomaly {
+        detected_at: now.to_rfc3339(),
+        kind: kind.to_string(),
+        source: source.as_str().to_string(),
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        session_id: session_id.map(ToString::to_string),
+        cost_usd: spend.cost,
+        baseline_usd: baseline.median,
+        ratio: if baseline.median > 0.0 {
+            spend.cost / baseline.median
+        } else {
+            0.0
+        },
+        project,
+        model,
+    }
+}
+
+/// Anomalous hours among the current and previous hour, and anomalous
+/// recently active sessions.
+fn detect(source: IndexedSource, index: &SpendIndex, now: DateTime<Utc>) -> Vec<CostAnomaly> {
+    let mut anomalies = Vec::new();
+    let current_hour = now.timestamp().div_euclid(3600);
+    let checked_from = current_hour - 1;
+
+    let baseline_from = current_hour - HOUR_BASELINE_DAYS * 24;
+    let hour_costs = index
+        .hours
+        .range(baseline_from..checked_from)
+        .map(|(_, spend)| spend.cost)
+        .collect();
+    if let Some(baseline) = Baseline::from_costs(hour_costs) {
+        let threshold = baseline.threshold(MIN_HOUR_COST_USD);
+        for (hour, spend) in index.hours.range(checked_from..=current_hour) {
+            if spend.cost >= threshold {
+                let start = DateTime::from_timestamp(hour * 3600, 0).unwrap_or_default();
+                let end = start + ChronoDuration::hours(1);
+                anomalies.push(anomaly(
+                    ANOMALY_KIND_HOUR,
+                    source,
+                    spend,
+                    None,
+                    (start, end),
+                    &baseline,
+                    now,
+                ));
+            }
+        }
+    }
+
+    let recent_after = now - ChronoDuration::hours(RECENT_SESSION_HOURS);
+    let is_recent = |spend: &Spend| spend.last_at.is_some_and(|last| last >= recent_after);
+    let session_costs = index
+        .sessions
+        .values()
+        .filter(|spend| !is_recent(spend))
+        .map(|spend| spend.cost)
+        .collect();
+    if let Some(baseline) = Baseline::from_costs(session_costs) {
+        let threshold = baseline.threshold(MIN_SESSION_COST_USD);
+        for (session_id, spend) in &index.sessions {
+            if is_recent(spend) && spend.cost >= threshold {
+                anomalies.push(anomaly(
+                    ANOMALY_KIND_SESSION,
+                    source,
+                    spend,
+                    Some(session_id),
+                    (spend.first_at.unwrap_or(now), spend.last_at.unwrap_or(now)),
+                    &baseline,
+                    now,
+                ));
+            }
+        }
+    }
+    anomalies
+}
+
+fn load_spend(
+    source: IndexedSource,
+    now: DateTime<Utc>,
+    prices: &PriceTable,
+) -> Result<SpendIndex, String> {
+    let since = now - ChronoDuration::days(SESSION_BASELINE_DAYS);
+    let mut index = SpendIndex::default();
+    cost_index::for_each_bucket(source, since, now, false, |key, totals| {
+        index.add(key, totals, prices);
+    })?;
+    Ok(index)
+}
+
+/// Detect anomalies in every indexed source and return the ones not raised
+/// before, after recording them.
+pub fn check_anomalies() -> Vec<CostAnomaly> {
+    let now = Utc::now();
+    let prices = PriceTable::load();
+    let mut fresh = Vec::new();
+    for source in IndexedSource::ALL {
+        let index = match load_spend(source, now, &prices) {
+            Ok(index) => index,
+            Err(error) => {
+                eprintln!(
+                    "[Anomaly] failed to read {} spend: {error}",
+                    source.as_str()
+                );
+                continue;
+            }
+        };
+        for anomaly in detect(source, &index, now) {
+            let first_time = raised()
+                .lock()
+                .map(|mut raised| raised.insert(anomaly_key(&anomaly)))
+                .unwrap_or(false);
+            if first_time {
+                history::record_anomaly(&anomaly);
+                fresh.push(anomaly);
+            }
+        }
+    }
+    fresh
+}
+
+fn notify(app: &AppHandle, anomaly: &CostAnomaly) {
+    let scope = if anomaly.kind == ANOMALY_KIND_SESSION {
+        "session"
+    } else {
+        "hour"
+    };
+    notification::notify(
+        app,
+        &format!("Unusual {} spend", anomaly.source),
+        &format!(
+            "${:.2} in one {scope} ({:.1}x usual) · {} · {}",
+            anomaly.cost_usd, anomaly.ratio, anomaly.project, anomaly.model
+        ),
+    );
+}
+
+/// Check for anomalies in the background, then notify about and emit each
+/// new one.
+pub fn start_anomaly_monitor(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        for anomaly in check_anomalies() {
+            eprintln!(
+                "[Anomaly] {} {} spent ${:.2} ({:.1}x baseline) in {} with {}",
+                anomaly.source,
+                anomaly.kind,
+                anomaly.cost_usd,
+                anomaly.ratio,
+                anomaly.project,
+                anomaly.model
+            );
+            notify(&app, &anomaly);
+            let _ = app.emit(COST_ANOMALY_EVENT, anomaly);
+        }
+    });
+}
+
+pub async fn get_cost_anomalies(
+    since: Option<String>,
+    until: Option<String>,
+) -> Result<Vec<CostAnomaly>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let bound = |value: Option<String>| {
+            value
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| {
+                    DateTime::parse_from_rfc3339(value.trim())
+                        .map(|at| at.with_timezone(&Utc))
+                        .map_err(|_| format!("Invalid timestamp '{value}', expected RFC 3339"))
+                })
+                .transpose()
+        };
+        history::load_anomalies(bound(since)?, bound(until)?)
+    })
+    .await
+    .map_err(|err| format!("Anomaly task failed: {err}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::cost::CostTokenBreakdown;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn add(index: &mut SpendIndex, when: &str, project: &str, session: &str, output: i64) {
+        let when = at(when);
+        let key = BucketKey {
+            hour: when.timestamp().div_euclid(3600),
+            model: "claude-sonnet-4".to_string(),
+            project: project.to_string(),
+            session_id: session.to_string(),
+        };
+        let totals = BucketTotals {
+            tokens: CostTokenBreakdown {
+                output_tokens: output,
+                total_tokens: output,
+                ..Default::default()
+            },
+            entries: 1,
+            first_at: Some(when),
+            last_at: Some(when),
+        };
+        index.add(&key, &totals, &PriceTable::default());
+    }
+
+    #[test]
+    fn flags_hours_and_sessions_far_above_the_baseline() {
+        let mut index = SpendIndex::default();
+        // Ten ordinary days: one session with two $1.50 hours each.
+        for day in 1..=10 {
+            for hour in [10, 14] {
+                let when = format!("2026-05-{day:02}T{hour}:00:00Z");
+                add(&mut index, &when, "/repo/app", &format!("s{day}"), 100_000);
+            }
+        }
+        // A runaway agent in the current hour: $30 in one session.
+        add(
+            &mut index,
+            "2026-05-12T09:10:00Z",
+            "/repo/app",
+            "s-ok",
+            50_000,
+        );
+        add(
+            &mut index,
+            "2026-05-12T09:20:00Z",
+            "/repo/agent",
+            "s-run",
+            2_000_000,
+        );
+
+        let now = at("2026-05-12T09:30:00Z");
+        let anomalies = detect(IndexedSource::Claude, &index, now);
+        let kinds: Vec<(&str, &str)> = anomalies
+            .iter()
+            .map(|a| (a.kind.as_str(), a.project.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ANOMALY_KIND_HOUR, "/repo/agent"),
+                (ANOMALY_KIND_SESSION, "/repo/agent")
+            ]
+        );
+        assert_eq!(anomalies[1].session_id.as_deref(), Some("s-run"));
+        assert!(anomalies[0].ratio > MIN_RATIO);
+    }
+
+    #[test]
+    fn needs_enough_baseline_samples() {
+        assert!(Baseline::from_costs(vec![1.0; MIN_BASELINE_SAMPLES - 1]).is_none());
+        let baseline = Baseline::from_costs(vec![1.0, 1.0, 2.0, 2.0, 2.0, 3.0, 3.0, 10.0]).unwrap();
+        assert_eq!(
+            baseline,
+            Baseline {
+                median: 2.0,
+                mad: 1.0
+            }
+        );
+        assert_eq!(baseline.threshold(0.5), 2.0 + MAD_FACTOR * 1.4826);
+    }
+}
@@ -0,0 +1,346 @@
+//! Spending budgets from the `budgets` config section, evaluated against the
+//! Today / This Week / This Month ranges of every computed cost overview.
+//!
+//! Budgets are in USD and compared with `CostRangeSummary::cost_usd`, so the
+//! display currency does not change when a warning fires. Budgets for `all`
+//! are only evaluated on the combined overview. Warnings are shown as native
+//! notifications; overviews restored from disk and custom ranges only get
+//! their statuses, so a stale total or a custom view never raises one.
+
+use crate::services::config::{self, BudgetConfig};
+use crate::services::cost::CostOverview;
+use crate::services::cost_combined::ALL_COST_SOURCE;
+use crate::services::cost_range::BILLING_CYCLE_RANGE;
+use crate::services::{notification, tray};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+
+pub const BUDGET_WARNING_EVENT: &str = "cost-budget-warning";
+
+pub const BUDGET_LEVEL_OK: &str = "ok";
+pub const BUDGET_LEVEL_WARNING: &str = "warning";
+pub const BUDGET_LEVEL_EXCEEDED: &str = "exceeded";
+
+/// `budget key|period start|threshold` of every warning already raised.
+static RAISED_WARNINGS: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn raised_warnings() -> &'static Mutex<HashSet<String>> {
+    RAISED_WARNINGS.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Ids of the budget trays currently shown.
+static BUDGET_TRAYS: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn budget_trays() -> &'static Mutex<HashSet<String>> {
+    BUDGET_TRAYS.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    pub source: String,
+    pub period: String,
+    /// Key of the range the budget was evaluated against.
+    pub range: String,
+    pub since: Option<String>,
+    pub amount: f64,
+    pub spent: f64,
+    pub percent: f64,
+    /// Highest configured warning percentage reached, if any.
+    pub threshold: Option<u8>,
+    /// `ok`, `warning` or `exceeded`.
+    pub level: String,
+    pub tray: bool,
+}
+
+impl BudgetStatus {
+    fn key(&self) -> String {
+        format!("{}-{}", self.source, self.period)
+    }
+
+    fn tray_id(&self) -> String {
+        format!("budget-{}", self.key())
+    }
+}
+
+fn budget_period(budget: &BudgetConfig) -> String {
+    budget.period.trim().to_ascii_lowercase()
+}
+
+fn range_for_period(period: &str) -> Option<&'static str> {
+    match period.trim().to_ascii_lowercase().as_str() {
+        "daily" | "day" => Some("today"),
+        "weekly" | "week" => Some("week"),
+        "monthly" | "month" => Some("month"),
+        "cycle" | "billingcycle" => Some(BILLING_CYCLE_RANGE),
+        _ => None,
+    }
+}
+
+fn budget_source(budget: &BudgetConfig) -> String {
+    let source = budget.source.trim().to_ascii_lowercase();
+    match source.as_str() {
+        "" | "overall" => ALL_COST_SOURCE.to_string(),
+        _ => source,
+    }
+}
+
+/// Statuses of the budgets for the overview's source whose period matches
+/// one of its ranges.
+pub fn evaluate(overview: &CostOverview, budgets: &[BudgetConfig]) -> Vec<BudgetStatus> {
+    budgets
+        .iter()
+        .filter(|budget| budget.amount > 0.0 && budget_source(budget) == overview.source)
+        .filter_map(|budget| {
+            let range_key = range_for_period(&budget.period)?;
+            let range = overview.ranges.iter().find(|r| r.range == range_key)?;
+            let spent = range.cost_usd.unwrap_or(0.0);
+            let percent = spent / budget.amount * 100.0;
+            let threshold = budget
+                .warn_at
+                .iter()
+                .copied()
+                .filter(|threshold| percent >= f64::from(*threshold))
+                .max();
+            let level = if percent >= 100.0 {
+                BUDGET_LEVEL_EXCEEDED
+            } else if threshold.is_some() {
+                BUDGET_LEVEL_WARNING
+            } else {
+                BUDGET_LEVEL_OK
+            };
+            Some(BudgetStatus {
+                source: budget_source(budget),
+                period: budget_period(budget),
+                range: range.range.clone(),
+                since: range.since.clone(),
+                amount: budget.amount,
+                spent,
+                percent,
+                threshold,
+                level: level.to_string(),
+                tray: budget.tray,
+            })
+        })
+        .collect()
+}
+
+/// Attach budget statuses without warnings or tray updates, for overviews
+/// restored from disk that are about to be recomputed.
+pub fn annotate(mut overview: CostOverview) -> CostOverview {
+    overview.budgets = evaluate(&overview, &config::load_config().budgets);
+    overview
+}
+
+/// Trays in `shown` whose budget was removed from the config or no longer
+/// asks for a tray. A budget without a status in one overview (e.g. a custom
+/// range) keeps its tray.
+fn orphaned_trays(shown: &HashSet<String>, budgets: &[BudgetConfig]) -> Vec<String> {
+    let configured: HashSet<String> = budgets
+        .iter()
+        .filter(|budget| budget.tray && budget.amount > 0.0)
+        .map(|budget| format!("budget-{}-{}", budget_source(budget), budget_period(budget)))
+        .collect();
+    let mut orphans: Vec<String> = shown
+        .iter()
+        .filter(|id| !configured.contains(*id))
+        .cloned()
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+fn notify_warning(app: &AppHandle, status: &BudgetStatus) {
+    let title = if status.level == BUDGET_LEVEL_EXCEEDED {
+        format!("{} {} budget exceeded", status.source, status.period)
+    } else {
+        format!("{} {} budget warning", status.source, status.period)
+    };
+    notification::notify(
+        app,
+        &title,
+        &format!(
+            "{:.0}% used · ${:.2} of ${:.2}",
+            status.percent, status.spent, status.amount
+        ),
+    );
+}
+
+/// Evaluate the configured budgets, raise warnings crossed since the last
+/// evaluation and refresh budget trays, hiding the ones no longer configured.
+pub fn apply(app: &AppHandle, mut overview: CostOverview) -> CostOverview {
+    let budgets = config::load_config().budgets;
+    overview.budgets = evaluate(&overview, &budgets);
+    for status in &overview.budgets {
+        if let Some(threshold) = status.threshold {
+            let warning_key = format!(
+                "{}|{}|{threshold}",
+                status.key(),
+                status.since.as_deref().unwrap_or_default()
+            );
+            let first_time = raised_warnings()
+                .lock()
+                .map(|mut raised| raised.insert(warning_key))
+                .unwrap_or(false);
+            if first_time {
+                eprintln!(
+                    "[Budget] {} {} budget at {:.0}% (${:.2} of ${:.2})",
+                    status.source, status.period, status.percent, status.spent, status.amount
+                );
+                notify_warning(app, status);
+                let _ = app.emit(BUDGET_WARNING_EVENT, status.clone());
+            }
+        }
+    }
+    sync_trays(app, &overview.budgets, &budgets);
+    overview
+}
+
+fn sync_trays(app: &AppHandle, statuses: &[BudgetStatus], budgets: &[BudgetConfig]) {
+    let Ok(mut shown) = budget_trays().lock() else {
+        return;
+    };
+    for status in statuses.iter().filter(|status| status.tray) {
+        let label = format!("Budget · {} {}", status.source, status.period);
+        let percentage = status.percent.round().clamp(0.0, 100.0) as u8;
+        let id = status.tray_id();
+        match tray::update_custom_tray(app, &id, &label, Some(percentage), true) {
+            Ok(()) => {
+                shown.insert(id);
+            }
+            Err(error) => eprintln!("[Budget] tray update failed: {error}"),
+        }
+    }
+    for id in orphaned_trays(&shown, budgets) {
+        let _ = tray::update_custom_tray(app, &id, &id, None, false);
+        shown.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::cost::{CostRangeSummary, CostTokenBreakdown};
+
+    fn budget(source: &str, period: &str, amount: f64) -> BudgetConfig {
+        BudgetConfig {
+            source: source.to_string(),
+            period: period.to_string(),
+            amount,
+            warn_at: vec![50, 80, 100],
+            tray: false,
+        }
+    }
+
+    fn overview(source: &str, today: f64, month: f64) -> CostOverview {
+        let range = |key: &str, cost: f64| CostRangeSummary {
+            range: key.to_string(),
+            label: key.to_string(),
+            since: Some("2026-05-01".to_string()),
+            until: None,
+            currency: "USD".to_string(),
+            cost: Some(cost),
+            cost_usd: Some(cost),
+            tokens: CostTokenBreakdown::default(),
+            models: Vec::new(),
+            projects: Vec::new(),
+            tags: Vec::new(),
+            sources: Vec::new(),
+            valid_entries: 1,
+            skipped_entries: 0,
+            elapsed_ms: 0.0,
+            comparison: None,
+        };
+        CostOverview {
+            source: source.to_string(),
+            display_name: source.to_string(),
+            currency: "USD".to_string(),
+            generated_at: String::new(),
+            cached: false,
+            ranges: vec![range("today", today), range("month", month)],
+            budgets: Vec::new(),
+            unpriced_models: Vec::new(),
+            exchange_rate: None,
+            truncated: false,
+            view_key: None,
+        }
+    }
+
+    #[test]
+    fn reports_highest_threshold_and_level() {
+        let budgets = [
+            budget("claude", "daily", 10.0),
+            budget("claude", "monthly", 100.0),
+            budget("codex", "daily", 10.0),
+            // No week range in this overview.
+            budget("claude", "weekly", 50.0),
+        ];
+        let statuses = evaluate(&overview("claude", 8.5, 120.0), &budgets);
+        let summary: Vec<(&str, Option<u8>, &str)> = statuses
+            .iter()
+            .map(|s| (s.period.as_str(), s.threshold, s.level.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("daily", Some(80), BUDGET_LEVEL_WARNING),
+                ("monthly", Some(100), BUDGET_LEVEL_EXCEEDED),
+            ]
+        );
+    }
+
+    #[test]
+    fn overall_budgets_apply_to_the_combined_overview() {
+        let budgets = [budget("overall", "daily", 20.0)];
+        assert!(evaluate(&overview("claude", 5.0, 5.0), &budgets).is_empty());
+        let statuses = evaluate(&overview(ALL_COST_SOURCE, 5.0, 5.0), &budgets);
+        assert_eq!(statuses[0].percent, 25.0);
+        assert_eq!(statuses[0].threshold, None);
+        assert_eq!(statuses[0].level, BUDGET_LEVEL_OK);
+    }
+
+    #[test]
+    fn finds_trays_of_removed_budgets_only() {
+        let tray = |source: &str, period: &str| BudgetConfig {
+            tray: true,
+            ..budget(source, period, 10.0)
+        };
+        let budgets = [
+            tray("claude", "daily"),
+            tray("claude", "weekly"),
+            tray("all", "daily"),
+            budget("codex", "daily", 10.0),
+        ];
+        let shown: HashSet<String> = [
+            "budget-claude-daily",
+            "budget-claude-weekly",
+            "budget-claude-monthly",
+            "budget-all-daily",
+            "budget-codex-daily",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        assert_eq!(
+            orphaned_trays(&shown, &budgets),
+            vec!["budget-claude-monthly", "budget-codex-daily"]
+        );
+    }
+
+    #[test]
+    fn ranged_overviews_orphan_no_trays() {
+        let budgets = [BudgetConfig {
+            tray: true,
+            ..budget("claude", "daily", 10.0)
+        }];
+        let mut ranged = overview("claude", 5.0, 5.0);
+        for range in &mut ranged.ranges {
+            range.range = "custom".to_string();
+        }
+        assert!(evaluate(&ranged, &budgets).is_empty());
+        let shown: HashSet<String> = HashSet::from(["budget-claude-daily".to_string()]);
+        assert!(orphaned_trays(&shown, &budgets).is_empty());
+    }
+}
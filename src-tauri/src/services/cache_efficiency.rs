@@ -0,0 +1,315 @@
+//! Prompt-cache efficiency of Claude Code and Codex usage, per model, per
+//! project and per day, from the incremental cost index.
+//!
+//! Input tokens are split into uncached input, cache writes and cache reads
+//! (Codex's `cached_input_tokens` are reads). Savings compare the prompt cost
+//! with what the same prompt tokens would cost at the plain input price:
+//! reads save `input - cache_read` per token and writes cost an extra
+//! `cache_write - input`. A write pays for itself once it is read
+//! `break_even_reads` times.
+
+use crate::services::cost::CostTokenBreakdown;
+use crate::services::cost_index::{self, IndexedSource};
+use crate::services::cost_range::RangeTimezone;
+use crate::services::cost_series::parse_bound;
+use crate::services::pricing::{ModelPricing, PriceTable};
+use crate::services::projects;
+use chrono::{Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+const DEFAULT_DAYS: i64 = 30;
+const MAX_DAYS: i64 = 366;
+const PER_MILLION: f64 = 1_000_000.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEfficiencyReport {
+    pub source: String,
+    pub since: String,
+    pub until: String,
+    pub generated_at: String,
+    pub total: CacheMetrics,
+    pub models: Vec<CacheGroup>,
+    pub projects: Vec<CacheGroup>,
+    /// One point per local day, oldest first; days without prompts are left
+    /// out.
+    pub trend: Vec<CacheTrendPoint>,
+    /// Models without a price; their tokens count towards the ratios only.
+    pub unpriced_models: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheGroup {
+    /// Model id or repository root.
+    pub name: String,
+    #[serde(flatten)]
+    pub metrics: CacheMetrics,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheTrendPoint {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    #[serde(flatten)]
+    pub metrics: CacheMetrics,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheMetrics {
+    /// Uncached input plus cache writes and reads.
+    pub prompt_tokens: i64,
+    pub input_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    /// Cache reads as a share of prompt tokens, `0..=1`.
+    pub hit_ratio: Option<f64>,
+    /// Prompt cost as billed, with cache prices.
+    pub prompt_cost_usd: f64,
+    /// The same prompt tokens at the plain input price.
+    pub uncached_cost_usd: f64,
+    /// `uncached_cost_usd - prompt_cost_usd`; negative when writes are not
+    /// read back often enough.
+    pub savings_usd: f64,
+    /// Extra cost of cache writes over plain input.
+    pub write_premium_usd: f64,
+    /// Discount of cache reads under plain input.
+    pub read_savings_usd: f64,
+    /// Cache reads per cache-written token.
+    pub reads_per_write: Option<f64>,
+    /// Reads per written token needed to recover the write premium.
+    pub break_even_reads: Option<f64>,
+}
+
+/// Running sums; ratios are derived in [`CacheTotals::metrics`].
+#[derive(Debug, Clone, Default)]
+struct CacheTotals {
+    tokens: CostTokenBreakdown,
+    prompt_cost: f64,
+    uncached_cost: f64,
+    write_premium: f64,
+    read_savings: f64,
+}
+
+impl CacheTotals {
+    fn add(&mut self, tokens: &CostTokenBreakdown, pricing: Option<&ModelPricing>) {
+        self.tokens.accumulate(tokens);
+        let Some(pricing) = pricing else {
+            return;
+        };
+        let per_token = |count: i64, rate: f64| count as f64 * rate / PER_MILLION;
+        let input = per_token(tokens.input_tokens, pricing.input);
+        let writes = per_token(tokens.cache_creation_tokens, pricing.cache_write);
+        let reads = per_token(tokens.cache_read_tokens, pricing.cache_read);
+        let writes_uncached = per_token(tokens.cache_creation_tokens, pricing.input);
+        let reads_uncached = per_token(tokens.cache_read_tokens, pricing.input);
+        self.prompt_cost += input + writes + reads;
+        self.uncached_cost += input + writes_uncached + reads_uncached;
+        self.write_premium += writes - writes_uncached;
+        self.read_savings += reads_uncached - reads;
+    }
+
+    fn metrics(&self) -> CacheMetrics {
+        let tokens = &self.tokens;
+        let prompt_tokens =
+            tokens.input_tokens + tokens.cache_creation_tokens + tokens.cache_read_tokens;
+        let writes = tokens.cache_creation_tokens as f64;
+        let reads = tokens.cache_read_tokens as f64;
+        // Per written token: the premium paid once and the saving per read.
+        let premium_per_write = (writes > 0.0).then(|| self.write_premium / writes);
+        let saving_per_read = (reads > 0.0).then(|| self.read_savings / reads);
+        CacheMetrics {
+            prompt_tokens,
+            input_tokens: tokens.input_tokens,
+            cache_creation_tokens: tokens.cache_creation_tokens,
+            cache_read_tokens: tokens.cache_read_tokens,
+            hit_ratio: (prompt_tokens > 0).then(|| reads / prompt_tokens as f64),
+            prompt_cost_usd: self.prompt_cost,
+            uncached_cost_usd: self.uncached_cost,
+            savings_usd: self.uncached_cost - self.prompt_cost,
+            write_premium_usd: self.write_premium,
+            read_savings_usd: self.read_savings,
+            reads_per_write: (writes > 0.0).then(|| reads / writes),
+            break_even_reads: premium_per_write
+                .zip(saving_per_read)
+                .filter(|(_, saving)| *saving > 0.0)
+                .map(|(premium, saving)| premium.max(0.0) / saving),
+        }
+    }
+}
+
+fn sorted_groups(groups: HashMap<String, CacheTotals>) -> Vec<CacheGroup> {
+    let mut groups: Vec<CacheGroup> = groups
+        .into_iter()
+        .map(|(name, totals)| CacheGroup {
+            name,
+            metrics: totals.metrics(),
+        })
+        .filter(|group| group.metrics.prompt_tokens > 0)
+        .collect();
+    groups.sort_by(|a, b| {
+        b.metrics
+            .prompt_tokens
+            .cmp(&a.metrics.prompt_tokens)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    groups
+}
+
+pub fn build_cache_efficiency(
+    source: &str,
+    since: Option<&str>,
+    until: Option<&str>,
+    timezone: Option<&str>,
+    force: bool,
+) -> Result<CacheEfficiencyReport, String> {
+    let indexed = IndexedSource::from_name(source).ok_or_else(|| {
+        format!("Cache metrics are only available for Claude Code and Codex, not '{source}'")
+    })?;
+    let zone = RangeTimezone::parse(timezone).ok_or_else(|| {
+        format!(
+            "Unsupported timezone '{}'; use local, UTC or an offset like +02:00",
+            timezone.unwrap_or_default()
+        )
+    })?;
+    let now = Utc::now();
+    let bound = |value: Option<&str>, end_of_day: bool| {
+        value
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| parse_bound(value, &zone, end_of_day))
+            .transpose()
+    };
+    let until = bound(until, true)?.unwrap_or(now);
+    let since = bound(since, false)?.unwrap_or_else(|| {
+        zone.start_of_day(zone.date_of(now) - ChronoDuration::days(DEFAULT_DAYS - 1))
+    });
+    if until <= since {
+        return Err("Range end must be after its start".to_string());
+    }
+    if until - since > ChronoDuration::days(MAX_DAYS) {
+        return Err(format!("Range is too long (max {MAX_DAYS} days)"));
+    }
+
+    let table = PriceTable::load();
+    let mut prices: HashMap<String, Option<ModelPricing>> = HashMap::new();
+    let mut roots: HashMap<String, String> = HashMap::new();
+    let mut total = CacheTotals::default();
+    let mut models: HashMap<String, CacheTotals> = HashMap::new();
+    let mut project_totals: HashMap<String, CacheTotals> = HashMap::new();
+    let mut trend: BTreeMap<String, CacheTotals> = BTreeMap::new();
+    cost_index::for_each_bucket(indexed, since, until, force, |key, totals| {
+        let pricing = prices
+            .entry(key.model.clone())
+            .or_insert_with(|| table.lookup(&key.model))
+            .as_ref();
+        let root = roots
+            .entry(key.project.clone())
+            .or_insert_with(|| projects::repository_root(&key.project));
+        let day = zone.date_of(key.start()).format("%Y-%m-%d").to_string();
+        total.add(&totals.tokens, pricing);
+        models
+            .entry(key.model.clone())
+            .or_default()
+            .add(&totals.tokens, pricing);
+        project_totals
+            .entry(root.clone())
+            .or_default()
+            .add(&totals.tokens, pricing);
+        trend.entry(day).or_default().add(&totals.tokens, pricing);
+    })?;
+
+    let mut unpriced_models: Vec<String> = prices
+        .into_iter()
+        .filter(|(_, pricing)| pricing.is_none())
+        .map(|(model, _)| model)
+        .collect();
+    unpriced_models.sort();
+
+    Ok(CacheEfficiencyReport {
+        source: indexed.as_str().to_string(),
+        since: since.to_rfc3339(),
+        until: until.to_rfc3339(),
+        generated_at: now.to_rfc3339(),
+        total: total.metrics(),
+        models: sorted_groups(models),
+        projects: sorted_groups(project_totals),
+        trend: trend
+            .into_iter()
+            .map(|(date, totals)| CacheTrendPoint {
+                date,
+                metrics: totals.metrics(),
+            })
+            .filter(|point| point.metrics.prompt_tokens > 0)
+            .collect(),
+        unpriced_models,
+    })
+}
+
+pub async fn get_cache_efficiency(
+    source: String,
+    since: Option<String>,
+    until: Option<String>,
+    timezone: Option<String>,
+    force: bool,
+) -> Result<CacheEfficiencyReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        build_cache_efficiency(
+            &source,
+            since.as_deref(),
+            until.as_deref(),
+            timezone.as_deref(),
+            force,
+        )
+    })
+    .await
+    .map_err(|err| format!("Cache metrics task failed: {err}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_hit_ratio_savings_and_break_even() {
+        // Sonnet-style prices: input 3, write 3.75, read 0.3 per million.
+        let pricing = ModelPricing {
+            input: 3.0,
+            output: 15.0,
+            cache_write: 3.75,
+            cache_read: 0.3,
+            reasoning: 0.0,
+        };
+        let tokens = CostTokenBreakdown {
+            input_tokens: 100_000,
+            cache_creation_tokens: 1_000_000,
+            cache_read_tokens: 8_900_000,
+            output_tokens: 50_000,
+            ..Default::default()
+        };
+        let mut totals = CacheTotals::default();
+        totals.add(&tokens, Some(&pricing));
+        let metrics = totals.metrics();
+
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        assert_eq!(metrics.prompt_tokens, 10_000_000);
+        assert!(close(metrics.hit_ratio.unwrap(), 0.89));
+        assert!(close(metrics.uncached_cost_usd, 30.0));
+        // 0.3 input + 3.75 writes + 2.67 reads.
+        assert!(close(metrics.prompt_cost_usd, 6.72));
+        assert!(close(metrics.savings_usd, 23.28));
+        assert!(close(metrics.write_premium_usd, 0.75));
+        assert!(close(metrics.reads_per_write.unwrap(), 8.9));
+        // A 0.75 premium per written token, recovered at 2.7 per read.
+        assert!(close(metrics.break_even_reads.unwrap(), 0.75 / 2.7));
+
+        // Unpriced models count towards ratios but not cost.
+        let mut unpriced = CacheTotals::default();
+        unpriced.add(&tokens, None);
+        let metrics = unpriced.metrics();
+        assert!(close(metrics.hit_ratio.unwrap(), 0.89));
+        assert_eq!(metrics.savings_usd, 0.0);
+    }
+}
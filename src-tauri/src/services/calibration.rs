@@ -0,0 +1,477 @@
+//! Quota calibration: how many local tokens (and API-equivalent dollars) one
+//! percent of a Claude or Codex quota window has cost so far, and what is left
+//! of the current window at that rate.
+//!
+//! The recorded quota history is split into one segment per window instance
+//! (a reset or a new `resets_at` starts the next one). Each segment's rise in
+//! percentage is paired with the indexed tokens logged over the same interval.
+//! Hourly buckets are pro-rated by how much of their activity falls inside the
+//! interval.
+//!
+//! Usage on other machines or on the web raises the percentage without local
+//! tokens, so the estimates assume this machine is the main consumer.
+
+use crate::services::cost::CostTokenBreakdown;
+use crate::services::cost_index::{self, IndexedSource};
+use crate::services::history::{self, QuotaSnapshot};
+use crate::services::pricing::PriceTable;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const DEFAULT_DAYS: u32 = 28;
+const MAX_DAYS: u32 = 90;
+/// Segments that rose less than this are dominated by the API's rounding.
+const MIN_SAMPLE_PERCENT: f64 = 3.0;
+/// A `resets_at` that moves by more than this starts a new window instance.
+const RESET_TOLERANCE_SECS: i64 = 300;
+/// With fewer samples the spread is meaningless; a ±50% band is reported.
+const MIN_SPREAD_SAMPLES: usize = 3;
+
+pub const CONFIDENCE_NONE: &str = "none";
+pub const CONFIDENCE_LOW: &str = "low";
+pub const CONFIDENCE_MEDIUM: &str = "medium";
+pub const CONFIDENCE_HIGH: &str = "high";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaCalibrationReport {
+    pub generated_at: String,
+    pub days: u32,
+    pub windows: Vec<WindowCalibration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateBand {
+    pub estimate: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl EstimateBand {
+    fn scaled(&self, factor: f64) -> Self {
+        Self {
+            estimate: self.estimate * factor,
+            low: self.low * factor,
+            high: self.high * factor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowCalibration {
+    pub source: String,
+    pub window: String,
+    /// Window instances used for the estimate.
+    pub samples: usize,
+    /// Sum of the percentage rises of those instances.
+    pub observed_percent: f64,
+    pub tokens_per_percent: Option<EstimateBand>,
+    pub cost_usd_per_percent: Option<EstimateBand>,
+    /// Latest recorded percentage; `0` once that window has reset.
+    pub current_percentage: Option<f64>,
+    pub resets_at: Option<String>,
+    pub remaining_tokens: Option<EstimateBand>,
+    pub remaining_cost_usd: Option<EstimateBand>,
+    /// `none`, `low`, `medium` or `high`, from the number of samples.
+    pub confidence: String,
+}
+
+/// One indexed bucket, kept with its activity span for pro-rating.
+struct Bucket {
+    model: String,
+    tokens: CostTokenBreakdown,
+    /// Zero for unpriced models.
+    cost_usd: f64,
+    first_at: DateTime<Utc>,
+    last_at: DateTime<Utc>,
+}
+
+impl Bucket {
+    /// Share of this bucket's activity inside `[since, until)`.
+    fn share(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> f64 {
+        if self.last_at <= self.first_at {
+            return if self.first_at >= since && self.first_at < until {
+                1.0
+            } else {
+                0.0
+            };
+        }
+        let start = self.first_at.max(since);
+        let end = self.last_at.min(until);
+        if end <= start {
+            return 0.0;
+        }
+        (end - start).num_milliseconds() as f64
+            / (self.last_at - self.first_at).num_milliseconds() as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Sample {
+    percent: f64,
+    tokens: f64,
+    cost_usd: f64,
+}
+
+/// Per-model windows only count their model family.
+fn counts_model(window: &str, model: &str) -> bool {
+    let model = model.to_ascii_lowercase();
+    match window {
+        "weeklyOpus" => model.contains("opus"),
+        "weeklySonnet" => model.contains("sonnet"),
+        _ => true,
+    }
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// One window instance: its first and last snapshot.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    start: DateTime<Utc>,
+    start_percent: f64,
+    end: DateTime<Utc>,
+    end_percent: f64,
+    resets_at: Option<DateTime<Utc>>,
+}
+
+impl Segment {
+    fn rise(&self) -> f64 {
+        self.end_percent - self.start_percent
+    }
+}
+
+/// Split one window's snapshots (oldest first) into window instances.
+fn segments(snapshots: &[&QuotaSnapshot]) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    for snapshot in snapshots {
+        let Some(at) = parse_time(&snapshot.recorded_at) else {
+            continue;
+        };
+        let resets_at = snapshot.resets_at.as_deref().and_then(parse_time);
+        let current = segments.last_mut().filter(|segment| {
+            let same_reset = match (segment.resets_at, resets_at) {
+                (Some(a), Some(b)) => (a - b).num_seconds().abs() <= RESET_TOLERANCE_SECS,
+                _ => true,
+            };
+            same_reset && snapshot.percentage >= segment.end_percent
+        });
+        match current {
+            Some(segment) => {
+                segment.end = at;
+                segment.end_percent = snapshot.percentage;
+            }
+            None => segments.push(Segment {
+                start: at,
+                start_percent: snapshot.percentage,
+                end: at,
+                end_percent: snapshot.percentage,
+                resets_at,
+            }),
+        }
+    }
+    segments
+}
+
+fn window_samples(window: &str, snapshots: &[&QuotaSnapshot], buckets: &[Bucket]) -> Vec<Sample> {
+    segments(snapshots)
+        .into_iter()
+        .filter(|segment| segment.rise() >= MIN_SAMPLE_PERCENT)
+        .filter_map(|segment| {
+            let mut tokens = 0.0;
+            let mut cost_usd = 0.0;
+            for bucket in buckets
+                .iter()
+                .filter(|bucket| counts_model(window, &bucket.model))
+            {
+                let share = bucket.share(segment.start, segment.end);
+                if share <= 0.0 {
+                    continue;
+                }
+                tokens += bucket.tokens.total_tokens as f64 * share;
+                cost_usd += bucket.cost_usd * share;
+            }
+            // A rise without local tokens came from elsewhere.
+            (tokens > 0.0).then_some(Sample {
+                percent: segment.rise(),
+                tokens,
+                cost_usd,
+            })
+        })
+        .collect()
+}
+
+/// Percent-weighted rate across samples, with a one-standard-deviation band
+/// (±50% with too few samples to measure the spread).
+fn rate_band(samples: &[Sample], value: impl Fn(&Sample) -> f64) -> Option<EstimateBand> {
+    let percent: f64 = samples.iter().map(|sample| sample.percent).sum();
+    if samples.is_empty() || percent <= 0.0 {
+        return None;
+    }
+    let estimate = samples.iter().map(&value).sum::<f64>() / percent;
+    let spread = if samples.len() < MIN_SPREAD_SAMPLES {
+        estimate * 0.5
+    } else {
+        let variance = samples
+            .iter()
+            .map(|sample| {
+                let rate = value(sample) / sample.percent;
+                sample.percent * (rate - estimate).powi(2)
+            })
+            .sum::<f64>()
+            / percent;
+        variance.sqrt()
+    };
+    Some(EstimateBand {
+        estimate,
+        low: (estimate - spread).max(0.0),
+        high: estimate + spread,
+    })
+}
+
+fn confidence(samples: usize) -> &'static str {
+    match samples {
+        0 => CONFIDENCE_NONE,
+        1..=2 => CONFIDENCE_LOW,
+        3..=7 => CONFIDENCE_MEDIUM,
+        _ => CONFIDENCE_HIGH,
+    }
+}
+
+fn calibrate_window(
+    source: &str,
+    window: &str,
+    snapshots: &[&QuotaSnapshot],
+    buckets: &[Bucket],
+    now: DateTime<Utc>,
+) -> WindowCalibration {
+    let samples = window_samples(window, snapshots, buckets);
+    let tokens_per_percent = rate_band(&samples, |sample| sample.tokens);
+    let cost_usd_per_percent = rate_band(&samples, |sample| sample.cost_usd);
+
+    let latest = snapshots.last();
+    let resets_at = latest.and_then(|snapshot| snapshot.resets_at.clone());
+    let has_reset = resets_at
+        .as_deref()
+        .and_then(parse_time)
+        .is_some_and(|reset| reset <= now);
+    let current_percentage =
+        latest.map(|snapshot| if has_reset { 0.0 } else { snapshot.percentage });
+    let remaining = current_percentage.map(|percentage| (100.0 - percentage).max(0.0));
+
+    WindowCalibration {
+        source: source.to_string(),
+        window: window.to_string(),
+        samples: samples.len(),
+        observed_percent: samples.iter().map(|sample| sample.percent).sum(),
+        remaining_tokens: tokens_per_percent
+            .zip(remaining)
+            .map(|(band, left)| band.scaled(left)),
+        remaining_cost_usd: cost_usd_per_percent
+            .zip(remaining)
+            .map(|(band, left)| band.scaled(left)),
+        tokens_per_percent,
+        cost_usd_per_percent,
+        current_percentage,
+        resets_at: if has_reset { None } else { resets_at },
+        confidence: confidence(samples.len()).to_string(),
+    }
+}
+
+fn load_buckets(
+    source: IndexedSource,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    prices: &PriceTable,
+    force: bool,
+) -> Result<Vec<Bucket>, String> {
+    let mut buckets = Vec::new();
+    cost_index::for_each_bucket(source, since, until, force, |key, totals| {
+        let start = key.start();
+        buckets.push(Bucket {
+            model: key.model.clone(),
+            tokens: totals.tokens.clone(),
+            cost_usd: prices.cost_usd(&key.model, &totals.tokens).unwrap_or(0.0),
+            first_at: totals.first_at.unwrap_or(start),
+            last_at: totals.last_at.unwrap_or(start),
+        });
+    })?;
+    Ok(buckets)
+}
+
+pub fn build_calibration(days: Option<u32>, force: bool) -> Result<QuotaCalibrationReport, String> {
+    let days = days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+    let now = Utc::now();
+    let since = now - ChronoDuration::days(i64::from(days));
+    let snapshots = history::load_history(Some(since), None)?;
+
+    let mut by_window: BTreeMap<(String, String), Vec<&QuotaSnapshot>> = BTreeMap::new();
+    for snapshot in &snapshots {
+        by_window
+            .entry((snapshot.source.clone(), snapshot.window.clone()))
+            .or_default()
+            .push(snapshot);
+    }
+
+    let prices = PriceTable::load();
+    let mut windows = Vec::new();
+    for source in IndexedSource::ALL {
+        let source_windows: Vec<_> = by_window
+            .iter()
+            .filter(|((name, _), _)| name == source.as_str())
+            .collect();
+        if source_windows.is_empty() {
+            continue;
+        }
+        let buckets = match load_buckets(source, since, now, &prices, force) {
+            Ok(buckets) => buckets,
+            Err(error) => {
+                eprintln!(
+                    "[Calibration] failed to read {} buckets: {error}",
+                    source.as_str()
+                );
+                Vec::new()
+            }
+        };
+        for ((_, window), snapshots) in source_windows {
+            windows.push(calibrate_window(
+                source.as_str(),
+                window,
+                snapshots,
+                &buckets,
+                now,
+            ));
+        }
+    }
+
+    Ok(QuotaCalibrationReport {
+        generated_at: now.to_rfc3339(),
+        days,
+        windows,
+    })
+}
+
+pub async fn get_quota_calibration(
+    days: Option<u32>,
+    force: bool,
+) -> Result<QuotaCalibrationReport, String> {
+    tauri::async_runtime::spawn_blocking(move || build_calibration(days, force))
+        .await
+        .map_err(|err| format!("Calibration task failed: {err}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(recorded_at: &str, percentage: f64, resets_at: &str) -> QuotaSnapshot {
+        QuotaSnapshot {
+            recorded_at: recorded_at.to_string(),
+            source: "claude".to_string(),
+            window: "session".to_string(),
+            percentage,
+            used: None,
+            limit: None,
+            resets_at: Some(resets_at.to_string()),
+        }
+    }
+
+    fn bucket(model: &str, first_at: &str, last_at: &str, tokens: i64) -> Bucket {
+        Bucket {
+            model: model.to_string(),
+            tokens: CostTokenBreakdown {
+                total_tokens: tokens,
+                ..Default::default()
+            },
+            cost_usd: 0.0,
+            first_at: parse_time(first_at).unwrap(),
+            last_at: parse_time(last_at).unwrap(),
+        }
+    }
+
+    #[test]
+    fn pairs_window_instances_with_prorated_tokens() {
+        let history = [
+            snapshot("2026-05-01T10:00:00Z", 0.0, "2026-05-01T15:00:00Z"),
+            snapshot("2026-05-01T12:00:00Z", 10.0, "2026-05-01T15:00:00Z"),
+            // The window reset: a new instance starts.
+            snapshot("2026-05-01T16:00:00Z", 2.0, "2026-05-01T21:00:00Z"),
+            snapshot("2026-05-01T17:00:00Z", 22.0, "2026-05-01T21:00:30Z"),
+        ];
+        let refs: Vec<&QuotaSnapshot> = history.iter().collect();
+        let buckets = [
+            bucket(
+                "claude-x",
+                "2026-05-01T10:00:00Z",
+                "2026-05-01T11:00:00Z",
+                1000,
+            ),
+            // Half of this bucket's activity falls inside the first instance.
+            bucket(
+                "claude-x",
+                "2026-05-01T11:30:00Z",
+                "2026-05-01T12:30:00Z",
+                2000,
+            ),
+            bucket(
+                "claude-x",
+                "2026-05-01T16:30:00Z",
+                "2026-05-01T16:30:00Z",
+                4000,
+            ),
+            bucket(
+                "claude-opus-4",
+                "2026-05-01T16:40:00Z",
+                "2026-05-01T16:40:00Z",
+                100,
+            ),
+        ];
+
+        let samples = window_samples("session", &refs, &buckets);
+        let points: Vec<(f64, f64)> = samples.iter().map(|s| (s.percent, s.tokens)).collect();
+        assert_eq!(points, vec![(10.0, 2000.0), (20.0, 4100.0)]);
+
+        let opus = window_samples("weeklyOpus", &refs, &buckets);
+        assert_eq!(opus.len(), 1);
+        assert_eq!(opus[0].tokens, 100.0);
+    }
+
+    #[test]
+    fn estimates_remaining_budget_with_a_band() {
+        let samples = [
+            Sample {
+                percent: 10.0,
+                tokens: 1000.0,
+                cost_usd: 1.0,
+            },
+            Sample {
+                percent: 10.0,
+                tokens: 3000.0,
+                cost_usd: 3.0,
+            },
+        ];
+        let band = rate_band(&samples, |s| s.tokens).unwrap();
+        assert_eq!(
+            band,
+            EstimateBand {
+                estimate: 200.0,
+                low: 100.0,
+                high: 300.0
+            }
+        );
+
+        let more = [samples[0], samples[1], samples[0], samples[1]];
+        let band = rate_band(&more, |s| s.tokens).unwrap();
+        assert_eq!((band.estimate, band.low, band.high), (200.0, 100.0, 300.0));
+        assert_eq!(band.scaled(40.0).estimate, 8000.0);
+        assert_eq!(confidence(more.len()), CONFIDENCE_MEDIUM);
+        assert!(rate_band(&[], |s| s.tokens).is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::domain::models::{QuotaData, UsageInfo};
+use crate::services::history;
 use crate::services::http::{is_transient_os_error, shared_http_client};
-use std::fs::OpenOptions;
-use std::io::Write as IoWrite;
+use crate::services::logging::append_log;
 #[cfg(target_os = "macos")]
 use std::process::Command;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -29,31 +29,7 @@ fn last_request_time() -> &'static Mutex<Option<Instant>> {
 }
 
 fn log_msg(msg: &str) {
-    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-    let line = format!("[{timestamp}] {msg}\n");
-
-    print!("{line}");
-
-    let log_dir = dirs::home_dir()
-        .unwrap_or_default()
-        .join("Library/Logs/quotabar");
-    if let Err(e) = std::fs::create_dir_all(&log_dir) {
-        eprintln!("[log] failed to create log dir: {e}");
-        return;
-    }
-
-    match OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(log_dir.join("claude.log"))
-    {
-        Ok(mut file) => {
-            if let Err(e) = file.write_all(line.as_bytes()) {
-                eprintln!("[log] failed to write log: {e}");
-            }
-        }
-        Err(e) => eprintln!("[log] failed to open log file: {e}"),
-    }
+    append_log("claude.log", msg);
 }
 
 fn log_response_headers(response: &reqwest::Response) {
@@ -100,6 +76,7 @@ struct CachedCredentials {
     access_token: String,
     cached_at: Instant,
     expires_at_ms: Option<u64>,
+    plan: Option<String>,
 }
 
 static CREDENTIALS_CACHE: OnceLock<Mutex<Option<CachedCredentials>>> = OnceLock::new();
@@ -130,6 +107,25 @@ struct KeychainCredentials {
     access_token: String,
     expires_at_ms: Option<u64>,
     cred_name: String,
+    plan: Option<String>,
+}
+
+/// Plan id from the OAuth credentials: `pro`, `max_5x`, `max_20x`, `team`,
+/// `enterprise`, … The rate-limit tier is more specific than the
+/// subscription type for Max plans.
+#[cfg(target_os = "macos")]
+fn plan_from_oauth(oauth: &serde_json::Value) -> Option<String> {
+    let tier = oauth["rateLimitTier"].as_str().unwrap_or_default();
+    for plan in ["max_20x", "max_5x"] {
+        if tier.contains(plan) {
+            return Some(plan.to_string());
+        }
+    }
+    oauth["subscriptionType"]
+        .as_str()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_ascii_lowercase)
 }
 
 #[cfg(target_os = "macos")]
@@ -161,6 +157,7 @@ fn read_credentials_from_system() -> Result<KeychainCredentials, String> {
                             access_token: access_token.to_string(),
                             expires_at_ms,
                             cred_name: cred_name.to_string(),
+                            plan: plan_from_oauth(oauth),
                         });
                     }
                 }
@@ -229,6 +226,7 @@ fn get_oauth_token(force_refresh: bool) -> Result<String, String> {
                 access_token: token.clone(),
                 cached_at: Instant::now(),
                 expires_at_ms: None,
+                plan: None,
             });
         }
         return Ok(token);
@@ -248,11 +246,23 @@ fn get_oauth_token(force_refresh: bool) -> Result<String, String> {
             access_token: keychain.access_token.clone(),
             cached_at: Instant::now(),
             expires_at_ms: keychain.expires_at_ms,
+            plan: keychain.plan,
         });
     }
     Ok(keychain.access_token)
 }
 
+/// The signed-in Claude plan, when the credentials record one. Tokens from
+/// `CLAUDE_CODE_OAUTH_TOKEN` carry no plan.
+pub fn claude_plan() -> Option<String> {
+    get_oauth_token(false).ok()?;
+    credentials_cache()
+        .lock()
+        .ok()?
+        .as_ref()
+        .and_then(|creds| creds.plan.clone())
+}
+
 async fn request_quota(access_token: &str) -> Result<reqwest::Response, String> {
     let (count, gap) = track_request();
     log_msg(&format!(
@@ -342,6 +352,16 @@ fn get_stale_cached_quota() -> Option<QuotaData> {
     }
 }
 
+/// Last successful quota response regardless of TTL, without touching the API.
+/// Local analytics use it to align with the server's windows.
+pub fn last_known_quota() -> Option<QuotaData> {
+    let guard = quota_cache().lock().ok()?;
+    guard
+        .as_ref()
+        .filter(|cached| cached.data.connected)
+        .map(|cached| cached.data.clone())
+}
+
 fn save_quota_cache(data: &QuotaData) {
     if let Ok(mut guard) = quota_cache().lock() {
         *guard = Some(CachedQuota {
@@ -484,5 +504,6 @@ pub async fn fetch_quota() -> QuotaData {
     );
 
     save_quota_cache(&result);
+    history::record_claude(&result);
     result
 }
@@ -0,0 +1,449 @@
+//! Five-hour billing blocks reconstructed from local Claude transcripts.
+//!
+//! A block opens at the hour floor of its first entry and lasts five hours; the
+//! next entry at or after the block end, or after a five-hour gap, opens a new
+//! one. When the API's `five_hour.resets_at` is known, the current block is
+//! pinned to `[reset - 5h, reset)` instead, so local token and project totals
+//! line up with the utilization the server reports.
+
+use crate::services::claude;
+use crate::services::claude_usage::{load_usage_entries, ClaudeUsageEntry};
+use crate::services::cost::CostTokenBreakdown;
+use chrono::{DateTime, Duration as ChronoDuration, DurationRound, Utc};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const BLOCK_HOURS: i64 = 5;
+/// Gaps longer than this between entries do not count as active time.
+const IDLE_GAP_MINUTES: i64 = 10;
+const DEFAULT_LOOKBACK_HOURS: u32 = 24;
+const MAX_LOOKBACK_HOURS: u32 = 24 * 7;
+const BLOCKS_CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeBlocksReport {
+    pub generated_at: String,
+    pub session_reset_time: Option<String>,
+    pub session_percentage: Option<f64>,
+    pub blocks: Vec<ClaudeBlock>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeBlock {
+    pub start_time: String,
+    pub end_time: String,
+    pub first_activity: String,
+    pub last_activity: String,
+    pub active_minutes: u32,
+    pub is_active: bool,
+    /// Pinned to the API's five-hour window rather than inferred locally.
+    pub anchored: bool,
+    pub entries: u32,
+    pub tokens: CostTokenBreakdown,
+    pub cost_usd: f64,
+    pub models: Vec<String>,
+    pub unpriced_models: Vec<String>,
+    pub projects: Vec<ClaudeBlockProject>,
+    pub projected_cost_usd: Option<f64>,
+    pub projected_tokens: Option<i64>,
+    /// When the session quota reaches 100% at the current pace, if that is
+    /// before the block ends.
+    pub projected_limit_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeBlockProject {
+    pub project: String,
+    pub entries: u32,
+    pub total_tokens: i64,
+    pub cost_usd: f64,
+}
+
+struct BlockBuilder {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    anchored: bool,
+    first: DateTime<Utc>,
+    last: DateTime<Utc>,
+    active: ChronoDuration,
+    entries: u32,
+    tokens: CostTokenBreakdown,
+    cost_usd: f64,
+    models: BTreeSet<String>,
+    unpriced_models: BTreeSet<String>,
+    projects: HashMap<String, ClaudeBlockProject>,
+}
+
+impl BlockBuilder {
+    fn new(start: DateTime<Utc>, end: DateTime<Utc>, anchored: bool, first: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            end,
+            anchored,
+            first,
+            last: first,
+            active: ChronoDuration::zero(),
+            entries: 0,
+            tokens: CostTokenBreakdown::default(),
+            cost_usd: 0.0,
+            models: BTreeSet::new(),
+            unpriced_models: BTreeSet::new(),
+            projects: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, entry: &ClaudeUsageEntry) {
+        let gap = entry.timestamp - self.last;
+        if self.entries > 0 && gap <= ChronoDuration::minutes(IDLE_GAP_MINUTES) {
+            self.active += gap;
+        }
+        self.last = self.last.max(entry.timestamp);
+        self.entries += 1;
+        self.tokens.accumulate(&entry.tokens);
+        self.models.insert(entry.model.clone());
+
+        let cost = entry.cost_usd.unwrap_or_else(|| {
+            self.unpriced_models.insert(entry.model.clone());
+            0.0
+        });
+        self.cost_usd += cost;
+
+        let project = self
+            .projects
+            .entry(entry.project.clone())
+            .or_insert_with(|| ClaudeBlockProject {
+                project: entry.project.clone(),
+                entries: 0,
+                total_tokens: 0,
+                cost_usd: 0.0,
+            });
+        project.entries += 1;
+        project.total_tokens += entry.tokens.total_tokens;
+        project.cost_usd += cost;
+    }
+
+    fn finish(self, now: DateTime<Utc>, session_percentage: Option<f64>) -> ClaudeBlock {
+        let is_active = now < self.end && now - self.last < ChronoDuration::hours(BLOCK_HOURS);
+
+        let (projected_cost_usd, projected_tokens) = if is_active {
+            project_totals(
+                self.first,
+                self.last,
+                self.end,
+                self.cost_usd,
+                self.tokens.total_tokens,
+            )
+        } else {
+            (None, None)
+        };
+        let projected_limit_at = if is_active && self.anchored {
+            session_percentage.and_then(|pct| project_limit(self.start, self.end, now, pct))
+        } else {
+            None
+        };
+
+        let mut projects: Vec<ClaudeBlockProject> = self.projects.into_values().collect();
+        projects.sort_by(|a, b| {
+            b.cost_usd
+                .total_cmp(&a.cost_usd)
+                .then_with(|| b.total_tokens.cmp(&a.total_tokens))
+        });
+
+        ClaudeBlock {
+            start_time: self.start.to_rfc3339(),
+            end_time: self.end.to_rfc3339(),
+            first_activity: self.first.to_rfc3339(),
+            last_activity: self.last.to_rfc3339(),
+            active_minutes: self.active.num_minutes().max(0) as u32,
+            is_active,
+            anchored: self.anchored,
+            entries: self.entries,
+            tokens: self.tokens,
+            cost_usd: self.cost_usd,
+            models: self.models.into_iter().collect(),
+            unpriced_models: self.unpriced_models.into_iter().collect(),
+            projects,
+            projected_cost_usd,
+            projected_tokens,
+            projected_limit_at,
+        }
+    }
+}
+
+/// Extrapolate the burn rate between the first and last entry to the block end.
+fn project_totals(
+    first: DateTime<Utc>,
+    last: DateTime<Utc>,
+    end: DateTime<Utc>,
+    cost_usd: f64,
+    tokens: i64,
+) -> (Option<f64>, Option<i64>) {
+    let elapsed_minutes = (last - first).num_seconds() as f64 / 60.0;
+    if elapsed_minutes < 1.0 {
+        return (None, None);
+    }
+    let remaining_minutes = ((end - last).num_seconds() as f64 / 60.0).max(0.0);
+    let factor = 1.0 + remaining_minutes / elapsed_minutes;
+    (
+        Some(cost_usd * factor),
+        Some((tokens as f64 * factor).round() as i64),
+    )
+}
+
+/// At `percentage` used after `now - start`, when does the window hit 100%?
+fn project_limit(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    now: DateTime<Utc>,
+    percentage: f64,
+) -> Option<String> {
+    if percentage.is_nan() || percentage <= 0.0 || percentage >= 100.0 {
+        return None;
+    }
+    let elapsed_secs = (now - start).num_seconds() as f64;
+    if elapsed_secs <= 0.0 {
+        return None;
+    }
+    let total_secs = elapsed_secs * 100.0 / percentage;
+    let limit_at = start + ChronoDuration::seconds(total_secs as i64);
+    (limit_at < end).then(|| limit_at.to_rfc3339())
+}
+
+fn floor_to_hour(ts: DateTime<Utc>) -> DateTime<Utc> {
+    ts.duration_trunc(ChronoDuration::hours(1)).unwrap_or(ts)
+}
+
+/// Group entries (sorted by time) into blocks. `anchor` pins the block that
+/// contains `now` to the API's five-hour window.
+fn build_blocks(
+    entries: &[ClaudeUsageEntry],
+    anchor: Option<(DateTime<Utc>, f64)>,
+    now: DateTime<Utc>,
+) -> Vec<ClaudeBlock> {
+    let block_len = ChronoDuration::hours(BLOCK_HOURS);
+    let anchor = anchor.filter(|(reset, _)| *reset > now && *reset - block_len <= now);
+    let anchor_start = anchor.map(|(reset, _)| reset - block_len);
+    let session_percentage = anchor.map(|(_, pct)| pct);
+
+    let mut blocks = Vec::new();
+    let mut current: Option<BlockBuilder> = None;
+
+    for entry in entries {
+        let ts = entry.timestamp;
+        let pinned = anchor_start.is_some_and(|start| ts >= start);
+
+        let starts_new = match current.as_ref() {
+            None => true,
+            Some(block) if pinned && !block.anchored => true,
+            Some(block) => !block.anchored && (ts >= block.end || ts - block.last >= block_len),
+        };
+        if starts_new {
+            if let Some(done) = current.take() {
+                blocks.push(done.finish(now, session_percentage));
+            }
+            current = Some(match (pinned, anchor) {
+                (true, Some((reset, _))) => BlockBuilder::new(reset - block_len, reset, true, ts),
+                _ => {
+                    let start = floor_to_hour(ts);
+                    let mut end = start + block_len;
+                    // Inferred blocks never overlap the anchored window.
+                    if let Some(anchor_start) = anchor_start {
+                        if start < anchor_start && end > anchor_start {
+                            end = anchor_start;
+                        }
+                    }
+                    BlockBuilder::new(start, end, false, ts)
+                }
+            });
+        }
+        if let Some(block) = current.as_mut() {
+            block.push(entry);
+        }
+    }
+
+    if let Some(done) = current.take() {
+        blocks.push(done.finish(now, session_percentage));
+    }
+    blocks
+}
+
+struct CachedBlocks {
+    key: String,
+    report: ClaudeBlocksReport,
+    cached_at: Instant,
+}
+
+static BLOCKS_CACHE: OnceLock<Mutex<Option<CachedBlocks>>> = OnceLock::new();
+
+fn blocks_cache() -> &'static Mutex<Option<CachedBlocks>> {
+    BLOCKS_CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn session_anchor() -> (Option<String>, Option<(DateTime<Utc>, f64)>) {
+    let Some(session) = claude::last_known_quota().and_then(|quota| quota.session) else {
+        return (None, None);
+    };
+    let anchor = session
+        .reset_time
+        .as_deref()
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        .map(|reset| (reset.with_timezone(&Utc), session.percentage));
+    (session.reset_time, anchor)
+}
+
+fn compute_blocks_report(hours: u32, force: bool) -> ClaudeBlocksReport {
+    let (reset_time, anchor) = session_anchor();
+    let key = format!("{hours}|{}", reset_time.as_deref().unwrap_or(""));
+
+    if !force {
+        if let Ok(guard) = blocks_cache().lock() {
+            if let Some(cached) = guard.as_ref() {
+                if cached.key == key && cached.cached_at.elapsed() < BLOCKS_CACHE_TTL {
+                    return cached.report.clone();
+                }
+            }
+        }
+    }
+
+    let now = Utc::now();
+    // Look back one extra block so the oldest block in range starts complete.
+    let since = now - ChronoDuration::hours(i64::from(hours) + BLOCK_HOURS);
+    let entries = load_usage_entries(since);
+    let range_start = now - ChronoDuration::hours(i64::from(hours));
+
+    let blocks: Vec<ClaudeBlock> = build_blocks(&entries, anchor, now)
+        .into_iter()
+        .filter(|block| {
+            DateTime::parse_from_rfc3339(&block.end_time)
+                .map(|end| end.with_timezone(&Utc) > range_start)
+                .unwrap_or(true)
+        })
+        .collect();
+
+    let report = ClaudeBlocksReport {
+        generated_at: now.to_rfc3339(),
+        session_reset_time: reset_time,
+        session_percentage: anchor.map(|(_, pct)| pct),
+        blocks,
+    };
+
+    if let Ok(mut guard) = blocks_cache().lock() {
+        *guard = Some(CachedBlocks {
+            key,
+            report: report.clone(),
+            cached_at: Instant::now(),
+        });
+    }
+    report
+}
+
+pub async fn get_claude_blocks(
+    hours: Option<u32>,
+    force: bool,
+) -> Result<ClaudeBlocksReport, String> {
+    let hours = hours
+        .unwrap_or(DEFAULT_LOOKBACK_HOURS)
+        .clamp(BLOCK_HOURS as u32, MAX_LOOKBACK_HOURS);
+    tauri::async_runtime::spawn_blocking(move || compute_blocks_report(hours, force))
+        .await
+        .map_err(|err| format!("Claude blocks task failed: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn entry(ts: &str, project: &str, tokens: i64, cost: Option<f64>) -> ClaudeUsageEntry {
+        ClaudeUsageEntry {
+            timestamp: at(ts),
+            session_id: "s1".to_string(),
+            project: project.to_string(),
+            model: if cost.is_some() {
+                "claude-sonnet-4-5".to_string()
+            } else {
+                "mystery".to_string()
+            },
+            tokens: CostTokenBreakdown {
+                input_tokens: tokens,
+                total_tokens: tokens,
+                ..Default::default()
+            },
+            cost_usd: cost,
+        }
+    }
+
+    #[test]
+    fn groups_entries_into_hour_floored_blocks() {
+        let entries = vec![
+            entry("2026-05-01T09:20:00Z", "/app", 100, Some(1.0)),
+            entry("2026-05-01T09:25:00Z", "/lib", 50, Some(0.5)),
+            entry("2026-05-01T13:59:00Z", "/app", 10, None),
+            entry("2026-05-01T14:05:00Z", "/app", 10, Some(0.1)),
+        ];
+        let blocks = build_blocks(&entries, None, at("2026-05-02T00:00:00Z"));
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].start_time, "2026-05-01T09:00:00+00:00");
+        assert_eq!(blocks[0].end_time, "2026-05-01T14:00:00+00:00");
+        assert_eq!(blocks[0].entries, 3);
+        assert_eq!(blocks[0].active_minutes, 5);
+        assert_eq!(blocks[0].unpriced_models, vec!["mystery".to_string()]);
+        assert_eq!(blocks[0].projects[0].project, "/app");
+        assert!((blocks[0].cost_usd - 1.5).abs() < 1e-9);
+        assert_eq!(blocks[1].start_time, "2026-05-01T14:00:00+00:00");
+        assert!(!blocks[1].is_active);
+    }
+
+    #[test]
+    fn pins_current_block_to_api_reset_and_projects() {
+        let entries = vec![
+            entry("2026-05-01T06:10:00Z", "/old", 10, Some(0.1)),
+            entry("2026-05-01T09:40:00Z", "/app", 100, Some(1.0)),
+            entry("2026-05-01T10:40:00Z", "/app", 100, Some(1.0)),
+        ];
+        let now = at("2026-05-01T10:45:00Z");
+        let reset = at("2026-05-01T14:30:00Z");
+        let blocks = build_blocks(&entries, Some((reset, 50.0)), now);
+        assert_eq!(blocks.len(), 2);
+
+        let previous = &blocks[0];
+        assert_eq!(previous.start_time, "2026-05-01T06:00:00+00:00");
+        assert_eq!(previous.end_time, "2026-05-01T09:30:00+00:00");
+
+        let current = &blocks[1];
+        assert!(current.anchored);
+        assert!(current.is_active);
+        assert_eq!(current.start_time, "2026-05-01T09:30:00+00:00");
+        assert_eq!(current.end_time, "2026-05-01T14:30:00+00:00");
+        // 2 USD over 60 minutes, 230 more minutes to go.
+        let projected = current.projected_cost_usd.unwrap();
+        assert!((projected - 2.0 * (1.0 + 230.0 / 60.0)).abs() < 1e-9);
+        // 50% after 75 minutes -> 100% after 150 minutes.
+        assert_eq!(
+            current.projected_limit_at.as_deref(),
+            Some("2026-05-01T12:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn stale_reset_is_ignored() {
+        let entries = vec![entry("2026-05-01T09:40:00Z", "/app", 100, Some(1.0))];
+        let blocks = build_blocks(
+            &entries,
+            Some((at("2026-05-01T08:00:00Z"), 90.0)),
+            at("2026-05-01T10:00:00Z"),
+        );
+        assert!(!blocks[0].anchored);
+        assert_eq!(blocks[0].start_time, "2026-05-01T09:00:00+00:00");
+    }
+}
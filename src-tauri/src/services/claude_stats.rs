@@ -0,0 +1,490 @@
+//! Local Claude Code session analytics from `~/.claude/projects/**/*.jsonl`.
+//!
+//! Transcripts are append-only, so each file is scanned incrementally from the
+//! end of its last complete line, the same offset approach as
+//! `codex::fetch_codex_stats`. Per-file tallies are cached for the current UTC
+//! day and merged on every call; a new day drops the cache so the "today"
+//! counters come from a full rescan.
+
+use crate::domain::models::{ClaudeNamedCount, ClaudeStats};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+const CLAUDE_CONFIG_DIR_ENV_KEY: &str = "CLAUDE_CONFIG_DIR";
+/// Model name Claude Code records for locally generated assistant messages.
+const SYNTHETIC_MODEL: &str = "<synthetic>";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct DayCount {
+    total: u32,
+    today: u32,
+}
+
+impl DayCount {
+    fn add(&mut self, is_today: bool) {
+        self.total = self.total.saturating_add(1);
+        if is_today {
+            self.today = self.today.saturating_add(1);
+        }
+    }
+
+    fn merge(&mut self, other: DayCount) {
+        self.total = self.total.saturating_add(other.total);
+        self.today = self.today.saturating_add(other.today);
+    }
+}
+
+#[derive(Default)]
+struct TranscriptStats {
+    sessions: HashSet<String>,
+    today_sessions: HashSet<String>,
+    active_today: bool,
+    last_ts: Option<i64>,
+    tools: HashMap<String, DayCount>,
+    models: HashMap<String, DayCount>,
+    /// Claude Code writes one line per content block, repeating the message
+    /// id, so consecutive lines with the same id are one message.
+    last_message_id: Option<String>,
+}
+
+struct CachedTranscript {
+    project: String,
+    file_size: u64,
+    modified_at: Option<SystemTime>,
+    offset: u64,
+    stats: TranscriptStats,
+}
+
+impl CachedTranscript {
+    fn new(project: String) -> Self {
+        Self {
+            project,
+            file_size: 0,
+            modified_at: None,
+            offset: 0,
+            stats: TranscriptStats::default(),
+        }
+    }
+}
+
+struct ClaudeStatsCache {
+    day: NaiveDate,
+    files: HashMap<PathBuf, CachedTranscript>,
+}
+
+static CLAUDE_STATS_CACHE: OnceLock<Mutex<Option<ClaudeStatsCache>>> = OnceLock::new();
+
+fn claude_stats_cache() -> &'static Mutex<Option<ClaudeStatsCache>> {
+    CLAUDE_STATS_CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// Transcript roots: each `CLAUDE_CONFIG_DIR` entry (comma separated) when set,
+/// otherwise `~/.config/claude` and `~/.claude`.
+pub(crate) fn claude_projects_dirs() -> Vec<PathBuf> {
+    let bases: Vec<PathBuf> = match std::env::var(CLAUDE_CONFIG_DIR_ENV_KEY) {
+        Ok(value) if !value.trim().is_empty() => value
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .collect(),
+        _ => dirs::home_dir()
+            .map(|home| vec![home.join(".config/claude"), home.join(".claude")])
+            .unwrap_or_default(),
+    };
+    bases
+        .into_iter()
+        .map(|base| base.join("projects"))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Recursively collect `*.jsonl` files. Symlinks are skipped to avoid loops.
+pub(crate) fn collect_jsonl_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_jsonl_files(&path, out);
+        } else if file_type.is_file() && path.extension().is_some_and(|ext| ext == "jsonl") {
+            out.push(path);
+        }
+    }
+}
+
+/// The project is the first directory under the projects root, which Claude
+/// Code derives from the session's working directory.
+pub(crate) fn project_key(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .ok()
+        .and_then(|rel| {
+            let mut components = rel.components();
+            let first = components.next()?;
+            components.next()?;
+            Some(first.as_os_str().to_string_lossy().into_owned())
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn parse_entry_timestamp(entry: &serde_json::Value) -> Option<DateTime<Utc>> {
+    entry["timestamp"]
+        .as_str()
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn record_entry(entry: &serde_json::Value, today: NaiveDate, stats: &mut TranscriptStats) {
+    let timestamp = parse_entry_timestamp(entry);
+    let is_today = timestamp.is_some_and(|dt| dt.date_naive() == today);
+    if let Some(dt) = timestamp {
+        let ts = dt.timestamp();
+        if stats.last_ts.is_none_or(|current| ts > current) {
+            stats.last_ts = Some(ts);
+        }
+        stats.active_today |= is_today;
+    }
+
+    if let Some(session_id) = entry["sessionId"].as_str().filter(|v| !v.is_empty()) {
+        if !stats.sessions.contains(session_id) {
+            stats.sessions.insert(session_id.to_string());
+        }
+        if is_today && !stats.today_sessions.contains(session_id) {
+            stats.today_sessions.insert(session_id.to_string());
+        }
+    }
+
+    if entry["type"].as_str() != Some("assistant") {
+        return;
+    }
+
+    let message = &entry["message"];
+    let message_id = message["id"].as_str();
+    let is_new_message = message_id.is_none_or(|id| stats.last_message_id.as_deref() != Some(id));
+    if is_new_message {
+        if let Some(model) = message["model"]
+            .as_str()
+            .filter(|model| !model.is_empty() && *model != SYNTHETIC_MODEL)
+        {
+            stats
+                .models
+                .entry(model.to_string())
+                .or_default()
+                .add(is_today);
+        }
+    }
+    stats.last_message_id = message_id.map(ToString::to_string);
+
+    if let Some(content) = message["content"].as_array() {
+        for item in content {
+            if item["type"].as_str() != Some("tool_use") {
+                continue;
+            }
+            if let Some(name) = item["name"].as_str().filter(|v| !v.is_empty()) {
+                stats
+                    .tools
+                    .entry(name.to_string())
+                    .or_default()
+                    .add(is_today);
+            }
+        }
+    }
+}
+
+/// Consume complete lines and return how many bytes were read. A trailing line
+/// without a newline is still being written and is left for the next scan.
+fn update_stats_from_reader<R: BufRead>(
+    mut reader: R,
+    today: NaiveDate,
+    stats: &mut TranscriptStats,
+) -> u64 {
+    let mut consumed = 0u64;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => {
+                if line.last() != Some(&b'\n') {
+                    break;
+                }
+                consumed += read as u64;
+                if let Ok(entry) = serde_json::from_slice::<serde_json::Value>(&line) {
+                    record_entry(&entry, today, stats);
+                }
+            }
+        }
+    }
+    consumed
+}
+
+fn scan_transcript(
+    path: &Path,
+    project: String,
+    metadata: &fs::Metadata,
+    today: NaiveDate,
+    previous: Option<CachedTranscript>,
+) -> CachedTranscript {
+    let file_size = metadata.len();
+    let modified_at = metadata.modified().ok();
+
+    let mut cached = match previous {
+        Some(prev) if prev.file_size == file_size && prev.modified_at == modified_at => {
+            return prev;
+        }
+        Some(prev) => {
+            let file_grew = file_size >= prev.offset;
+            let modified_is_not_older = match (modified_at, prev.modified_at) {
+                (Some(now), Some(cached)) => now >= cached,
+                _ => true,
+            };
+            if file_grew && modified_is_not_older {
+                prev
+            } else {
+                CachedTranscript::new(project)
+            }
+        }
+        None => CachedTranscript::new(project),
+    };
+
+    let Ok(mut file) = fs::File::open(path) else {
+        // Leave size/mtime untouched so the next call retries the read.
+        return cached;
+    };
+    if file.seek(SeekFrom::Start(cached.offset)).is_err() {
+        cached = CachedTranscript::new(cached.project);
+        if file.seek(SeekFrom::Start(0)).is_err() {
+            return cached;
+        }
+    }
+
+    let consumed = update_stats_from_reader(BufReader::new(file), today, &mut cached.stats);
+    cached.offset += consumed;
+    cached.file_size = file_size;
+    cached.modified_at = modified_at;
+    cached
+}
+
+fn sorted_counts(counts: HashMap<&str, DayCount>) -> Vec<ClaudeNamedCount> {
+    let mut out: Vec<ClaudeNamedCount> = counts
+        .into_iter()
+        .map(|(name, count)| ClaudeNamedCount {
+            name: name.to_string(),
+            total: count.total,
+            today: count.today,
+        })
+        .collect();
+    out.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+    out
+}
+
+fn build_claude_stats<'a>(files: impl IntoIterator<Item = &'a CachedTranscript>) -> ClaudeStats {
+    let mut sessions: HashSet<&str> = HashSet::new();
+    let mut today_sessions: HashSet<&str> = HashSet::new();
+    let mut projects: HashSet<&str> = HashSet::new();
+    let mut active_projects: HashSet<&str> = HashSet::new();
+    let mut last_ts: Option<i64> = None;
+    let mut tools: HashMap<&str, DayCount> = HashMap::new();
+    let mut models: HashMap<&str, DayCount> = HashMap::new();
+
+    for file in files {
+        let stats = &file.stats;
+        if stats.sessions.is_empty() {
+            continue;
+        }
+        sessions.extend(stats.sessions.iter().map(String::as_str));
+        today_sessions.extend(stats.today_sessions.iter().map(String::as_str));
+        projects.insert(&file.project);
+        if stats.active_today {
+            active_projects.insert(&file.project);
+        }
+        if let Some(ts) = stats.last_ts {
+            if last_ts.is_none_or(|current| ts > current) {
+                last_ts = Some(ts);
+            }
+        }
+        for (name, count) in &stats.tools {
+            tools.entry(name).or_default().merge(*count);
+        }
+        for (name, count) in &stats.models {
+            models.entry(name).or_default().merge(*count);
+        }
+    }
+
+    let tool_calls_total = tools
+        .values()
+        .fold(0u32, |acc, count| acc.saturating_add(count.total));
+    let tool_calls_today = tools
+        .values()
+        .fold(0u32, |acc, count| acc.saturating_add(count.today));
+    let last_activity = last_ts
+        .and_then(|ts| DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string());
+
+    ClaudeStats {
+        total_sessions: sessions.len() as u32,
+        today_sessions: today_sessions.len() as u32,
+        total_projects: projects.len() as u32,
+        active_projects: active_projects.len() as u32,
+        last_activity,
+        tool_calls_total,
+        tool_calls_today,
+        tool_calls: sorted_counts(tools),
+        messages_by_model: sorted_counts(models),
+    }
+}
+
+fn compute_claude_stats() -> ClaudeStats {
+    let roots = claude_projects_dirs();
+    let Ok(mut guard) = claude_stats_cache().lock() else {
+        return ClaudeStats::empty();
+    };
+    if roots.is_empty() {
+        *guard = None;
+        return ClaudeStats::empty();
+    }
+
+    let today = Utc::now().date_naive();
+    let mut previous = match guard.take() {
+        Some(cache) if cache.day == today => cache.files,
+        _ => HashMap::new(),
+    };
+
+    let mut files = HashMap::new();
+    for root in &roots {
+        let mut paths = Vec::new();
+        collect_jsonl_files(root, &mut paths);
+        for path in paths {
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            let project = project_key(root, &path);
+            let cached = previous.remove(&path);
+            let scanned = scan_transcript(&path, project, &metadata, today, cached);
+            files.insert(path, scanned);
+        }
+    }
+
+    let stats = build_claude_stats(files.values());
+    *guard = Some(ClaudeStatsCache { day: today, files });
+    stats
+}
+
+pub async fn fetch_claude_stats() -> ClaudeStats {
+    tauri::async_runtime::spawn_blocking(compute_claude_stats)
+        .await
+        .unwrap_or_else(|_| ClaudeStats::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const TRANSCRIPT: &str = concat!(
+        r#"{"type":"user","sessionId":"s1","timestamp":"2026-05-01T09:00:00Z","message":{"role":"user","content":"hi"}}"#,
+        "\n",
+        r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-05-01T09:00:05Z","message":{"id":"msg_1","model":"claude-sonnet-4-5","content":[{"type":"text","text":"ok"}]}}"#,
+        "\n",
+        r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-05-01T09:00:06Z","message":{"id":"msg_1","model":"claude-sonnet-4-5","content":[{"type":"tool_use","name":"Bash","input":{}}]}}"#,
+        "\n",
+        r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-04-30T22:00:00Z","message":{"id":"msg_2","model":"claude-opus-4-1","content":[{"type":"tool_use","name":"Read","input":{}},{"type":"tool_use","name":"Bash","input":{}}]}}"#,
+        "\n",
+        r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-05-01T09:01:00Z","message":{"id":"msg_3","model":"<synthetic>","content":[]}}"#,
+        "\n",
+    );
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 5, 1).unwrap()
+    }
+
+    #[test]
+    fn counts_messages_once_per_id_and_tools_per_block() {
+        let mut stats = TranscriptStats::default();
+        let consumed = update_stats_from_reader(Cursor::new(TRANSCRIPT), today(), &mut stats);
+        assert_eq!(consumed, TRANSCRIPT.len() as u64);
+        assert_eq!(
+            stats.models.get("claude-sonnet-4-5"),
+            Some(&DayCount { total: 1, today: 1 })
+        );
+        assert_eq!(
+            stats.models.get("claude-opus-4-1"),
+            Some(&DayCount { total: 1, today: 0 })
+        );
+        assert!(!stats.models.contains_key(SYNTHETIC_MODEL));
+        assert_eq!(
+            stats.tools.get("Bash"),
+            Some(&DayCount { total: 2, today: 1 })
+        );
+        assert_eq!(
+            stats.tools.get("Read"),
+            Some(&DayCount { total: 1, today: 0 })
+        );
+        assert!(stats.active_today);
+        assert!(stats.today_sessions.contains("s1"));
+    }
+
+    #[test]
+    fn leaves_partial_trailing_line_for_next_scan() {
+        let complete = r#"{"type":"user","sessionId":"s1","timestamp":"2026-05-01T09:00:00Z"}"#;
+        let input = format!("{complete}\n{{\"type\":\"assis");
+        let mut stats = TranscriptStats::default();
+        let consumed = update_stats_from_reader(Cursor::new(input), today(), &mut stats);
+        assert_eq!(consumed, complete.len() as u64 + 1);
+    }
+
+    #[test]
+    fn merges_sessions_and_projects_across_files() {
+        let mut main = CachedTranscript::new("-Users-dev-app".to_string());
+        update_stats_from_reader(Cursor::new(TRANSCRIPT), today(), &mut main.stats);
+        let subagent_line = r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-05-01T10:00:00Z","message":{"id":"msg_9","model":"claude-sonnet-4-5","content":[{"type":"tool_use","name":"Grep","input":{}}]}}"#;
+        let mut subagent = CachedTranscript::new("-Users-dev-app".to_string());
+        update_stats_from_reader(
+            Cursor::new(format!("{subagent_line}\n")),
+            today(),
+            &mut subagent.stats,
+        );
+        let old_line = r#"{"type":"user","sessionId":"s0","timestamp":"2026-04-01T10:00:00Z"}"#;
+        let mut old = CachedTranscript::new("-Users-dev-lib".to_string());
+        update_stats_from_reader(
+            Cursor::new(format!("{old_line}\n")),
+            today(),
+            &mut old.stats,
+        );
+
+        let stats = build_claude_stats([&main, &subagent, &old]);
+        assert_eq!(stats.total_sessions, 2);
+        assert_eq!(stats.today_sessions, 1);
+        assert_eq!(stats.total_projects, 2);
+        assert_eq!(stats.active_projects, 1);
+        assert_eq!(stats.tool_calls_total, 4);
+        assert_eq!(stats.tool_calls_today, 2);
+        assert_eq!(stats.tool_calls[0].name, "Bash");
+        assert_eq!(stats.messages_by_model[0].name, "claude-sonnet-4-5");
+        assert_eq!(stats.messages_by_model[0].total, 2);
+        assert_eq!(stats.last_activity.as_deref(), Some("2026-05-01 10:00"));
+    }
+
+    #[test]
+    fn project_key_uses_first_directory() {
+        let root = Path::new("/home/dev/.claude/projects");
+        assert_eq!(
+            project_key(root, &root.join("-Users-dev-app/session.jsonl")),
+            "-Users-dev-app"
+        );
+        assert_eq!(
+            project_key(root, &root.join("-Users-dev-app/s1/subagents/agent.jsonl")),
+            "-Users-dev-app"
+        );
+        assert_eq!(project_key(root, &root.join("stray.jsonl")), "");
+    }
+}
@@ -0,0 +1,208 @@
+//! Priced token usage entries read from Claude Code transcripts.
+//!
+//! Claude Code writes one transcript line per content block and repeats the
+//! message's `usage` on each, and resumed sessions copy earlier messages into
+//! the new file. Entries are therefore de-duplicated by `message.id` plus
+//! `requestId` across all files before anything is summed.
+
+use crate::services::claude_stats::{
+    claude_projects_dirs, collect_jsonl_files, parse_entry_timestamp, project_key,
+};
+use crate::services::cost::CostTokenBreakdown;
+use crate::services::pricing::PriceTable;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone)]
+pub struct ClaudeUsageEntry {
+    pub timestamp: DateTime<Utc>,
+    pub session_id: String,
+    /// Working directory recorded on the entry, falling back to the encoded
+    /// project directory name.
+    pub project: String,
+    pub model: String,
+    pub tokens: CostTokenBreakdown,
+    /// `None` when the model is not in the pricing table, and until
+    /// [`load_usage_entries`] prices the entry.
+    pub cost_usd: Option<f64>,
+}
+
+fn token_count(value: &serde_json::Value) -> i64 {
+    value.as_i64().unwrap_or(0).max(0)
+}
+
+/// Returns the de-duplication key (if any) and the entry for assistant lines
+/// that carry usage.
+pub(crate) fn parse_usage_entry(
+    entry: &serde_json::Value,
+    fallback_project: &str,
+) -> Option<(Option<String>, ClaudeUsageEntry)> {
+    if entry["type"].as_str() != Some("assistant") {
+        return None;
+    }
+    let message = &entry["message"];
+    let usage = &message["usage"];
+    if !usage.is_object() {
+        return None;
+    }
+    let timestamp = parse_entry_timestamp(entry)?;
+
+    let mut tokens = CostTokenBreakdown {
+        input_tokens: token_count(&usage["input_tokens"]),
+        output_tokens: token_count(&usage["output_tokens"]),
+        cache_creation_tokens: token_count(&usage["cache_creation_input_tokens"]),
+        cache_read_tokens: token_count(&usage["cache_read_input_tokens"]),
+        ..Default::default()
+    };
+    tokens.total_tokens = tokens.input_tokens
+        + tokens.output_tokens
+        + tokens.cache_creation_tokens
+        + tokens.cache_read_tokens;
+    if tokens.total_tokens == 0 {
+        return None;
+    }
+
+    let model = message["model"]
+        .as_str()
+        .filter(|model| !model.is_empty())
+        .unwrap_or("unknown")
+        .to_string();
+    let dedupe_key = match (message["id"].as_str(), entry["requestId"].as_str()) {
+        (Some(id), Some(request)) => Some(format!("{id}:{request}")),
+        (Some(id), None) => Some(id.to_string()),
+        _ => None,
+    };
+    let project = entry["cwd"]
+        .as_str()
+        .filter(|cwd| !cwd.is_empty())
+        .unwrap_or(fallback_project)
+        .to_string();
+
+    Some((
+        dedupe_key,
+        ClaudeUsageEntry {
+            timestamp,
+            session_id: entry["sessionId"].as_str().unwrap_or_default().to_string(),
+            project,
+            cost_usd: None,
+            model,
+            tokens,
+        },
+    ))
+}
+
+fn read_file_entries(
+    path: &Path,
+    project: &str,
+    since: DateTime<Utc>,
+    seen: &mut HashSet<String>,
+    out: &mut Vec<ClaudeUsageEntry>,
+) {
+    let Ok(file) = fs::File::open(path) else {
+        return;
+    };
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        // Cheap pre-filter: only assistant lines with usage are interesting.
+        if !line.contains("\"usage\"") {
+            continue;
+        }
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        let Some((key, entry)) = parse_usage_entry(&value, project) else {
+            continue;
+        };
+        if entry.timestamp < since {
+            continue;
+        }
+        if let Some(key) = key {
+            if !seen.insert(key) {
+                continue;
+            }
+        }
+        out.push(entry);
+    }
+}
+
+/// All usage entries at or after `since`, sorted by timestamp and priced with
+/// one snapshot of the pricing table. Files last modified before `since` are
+/// skipped without being opened.
+pub fn load_usage_entries(since: DateTime<Utc>) -> Vec<ClaudeUsageEntry> {
+    let since_system = SystemTime::from(since);
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+
+    for root in claude_projects_dirs() {
+        let mut paths = Vec::new();
+        collect_jsonl_files(&root, &mut paths);
+        for path in paths {
+            let recent = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .map(|modified| modified >= since_system)
+                .unwrap_or(true);
+            if !recent {
+                continue;
+            }
+            let project = project_key(&root, &path);
+            read_file_entries(&path, &project, since, &mut seen, &mut entries);
+        }
+    }
+
+    let prices = PriceTable::load();
+    for entry in &mut entries {
+        entry.cost_usd = prices.cost_usd(&entry.model, &entry.tokens);
+    }
+    entries.sort_by_key(|entry| entry.timestamp);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_usage_with_dedupe_key() {
+        let line = json!({
+            "type": "assistant",
+            "sessionId": "s1",
+            "requestId": "req_1",
+            "cwd": "/Users/dev/app",
+            "timestamp": "2026-05-01T09:00:00.000Z",
+            "message": {
+                "id": "msg_1",
+                "model": "claude-sonnet-4-5-20250929",
+                "usage": {
+                    "input_tokens": 1000,
+                    "output_tokens": 200,
+                    "cache_creation_input_tokens": 0,
+                    "cache_read_input_tokens": 5000
+                }
+            }
+        });
+        let (key, entry) = parse_usage_entry(&line, "-Users-dev-app").expect("usage entry");
+        assert_eq!(key.as_deref(), Some("msg_1:req_1"));
+        assert_eq!(entry.project, "/Users/dev/app");
+        assert_eq!(entry.tokens.total_tokens, 6200);
+        let cost = PriceTable::default()
+            .cost_usd(&entry.model, &entry.tokens)
+            .expect("sonnet is priced");
+        assert!((cost - (0.003 + 0.003 + 0.0015)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ignores_user_lines_and_empty_usage() {
+        let user = json!({ "type": "user", "timestamp": "2026-05-01T09:00:00Z" });
+        assert!(parse_usage_entry(&user, "p").is_none());
+        let empty = json!({
+            "type": "assistant",
+            "timestamp": "2026-05-01T09:00:00Z",
+            "message": { "model": "<synthetic>", "usage": { "input_tokens": 0, "output_tokens": 0 } }
+        });
+        assert!(parse_usage_entry(&empty, "p").is_none());
+    }
+}
@@ -1,15 +1,21 @@
 use crate::domain::models::{
     CodexCredits, CodexData, CodexRateLimitWindow, CodexRateLimits, CodexStats,
 };
+use crate::services::history;
 use crate::services::http::{is_transient_os_error, shared_http_client};
 use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine as _};
 use chrono::{DateTime, NaiveDate, Utc};
 use std::fs;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 use std::time::SystemTime;
 
+pub const RATE_LIMITS_SOURCE_API: &str = "api";
+pub const RATE_LIMITS_SOURCE_LOCAL: &str = "local";
+/// Only the newest rollouts are read when looking for a local snapshot.
+const MAX_ROLLOUTS_SCANNED: usize = 8;
+
 #[derive(Clone)]
 struct HistoryStatsCache {
     history_file: PathBuf,
@@ -32,6 +38,26 @@ fn history_stats_cache() -> &'static Mutex<Option<HistoryStatsCache>> {
 static LAST_GOOD_INFO: OnceLock<Mutex<Option<CodexData>>> = OnceLock::new();
 static LAST_GOOD_LIMITS: OnceLock<Mutex<Option<CodexRateLimits>>> = OnceLock::new();
 
+#[derive(Clone)]
+struct CachedRolloutSnapshot {
+    path: PathBuf,
+    file_size: u64,
+    modified_at: Option<SystemTime>,
+    snapshot: Option<RolloutSnapshot>,
+}
+
+#[derive(Clone)]
+struct RolloutSnapshot {
+    timestamp: DateTime<Utc>,
+    rate_limits: serde_json::Value,
+}
+
+static ROLLOUT_SNAPSHOT_CACHE: OnceLock<Mutex<Option<CachedRolloutSnapshot>>> = OnceLock::new();
+
+fn rollout_snapshot_cache() -> &'static Mutex<Option<CachedRolloutSnapshot>> {
+    ROLLOUT_SNAPSHOT_CACHE.get_or_init(|| Mutex::new(None))
+}
+
 fn last_good_info() -> &'static Mutex<Option<CodexData>> {
     LAST_GOOD_INFO.get_or_init(|| Mutex::new(None))
 }
@@ -294,7 +320,216 @@ pub async fn fetch_codex_stats() -> CodexStats {
     build_codex_stats(total_sessions, today_sessions, last_ts)
 }
 
-fn fallback_or_disconnected_limits(error: String) -> CodexRateLimits {
+/// `~/.codex/sessions`, where Codex writes one rollout file per session.
+pub(crate) fn codex_sessions_dir() -> Option<PathBuf> {
+    get_codex_home().map(|home| home.join("sessions"))
+}
+
+pub(crate) fn is_rollout_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("rollout-") && name.ends_with(".jsonl"))
+}
+
+pub(crate) fn collect_rollout_files(dir: &Path, out: &mut Vec<(PathBuf, SystemTime)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_rollout_files(&path, out);
+            continue;
+        }
+        if is_rollout_file(&path) {
+            if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+                out.push((path, modified));
+            }
+        }
+    }
+}
+
+/// The last `token_count` event carrying `rate_limits` in a rollout file.
+fn read_rollout_snapshot<R: BufRead>(reader: R) -> Option<RolloutSnapshot> {
+    let mut latest = None;
+    for line in reader.lines().map_while(Result::ok) {
+        if !line.contains("\"rate_limits\"") {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        let payload = &entry["payload"];
+        if payload["type"].as_str() != Some("token_count") || !payload["rate_limits"].is_object() {
+            continue;
+        }
+        let Some(timestamp) = entry["timestamp"]
+            .as_str()
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+        else {
+            continue;
+        };
+        latest = Some(RolloutSnapshot {
+            timestamp,
+            rate_limits: payload["rate_limits"].clone(),
+        });
+    }
+    latest
+}
+
+fn cached_rollout_snapshot(path: &Path) -> Option<RolloutSnapshot> {
+    let metadata = fs::metadata(path).ok()?;
+    let file_size = metadata.len();
+    let modified_at = metadata.modified().ok();
+
+    if let Ok(guard) = rollout_snapshot_cache().lock() {
+        if let Some(cached) = guard.as_ref() {
+            if cached.path == path
+                && cached.file_size == file_size
+                && cached.modified_at == modified_at
+            {
+                return cached.snapshot.clone();
+            }
+        }
+    }
+
+    let file = fs::File::open(path).ok()?;
+    let snapshot = read_rollout_snapshot(BufReader::new(file));
+    if let Ok(mut guard) = rollout_snapshot_cache().lock() {
+        *guard = Some(CachedRolloutSnapshot {
+            path: path.to_path_buf(),
+            file_size,
+            modified_at,
+            snapshot: snapshot.clone(),
+        });
+    }
+    snapshot
+}
+
+/// Codex has written both `{ primary: { used_percent, window_minutes,
+/// resets_at | resets_in_seconds } }` and the older flat
+/// `primary_used_percent` / `primary_window_minutes` shapes.
+fn parse_snapshot_window(
+    rate_limits: &serde_json::Value,
+    name: &str,
+    observed_at: DateTime<Utc>,
+) -> Option<CodexRateLimitWindow> {
+    let nested = &rate_limits[name];
+    let (used, window_minutes, resets_at, resets_in) = if nested.is_object() {
+        (
+            nested["used_percent"].as_f64(),
+            nested["window_minutes"].as_i64(),
+            nested["resets_at"].as_i64(),
+            nested["resets_in_seconds"].as_i64(),
+        )
+    } else {
+        (
+            rate_limits[format!("{name}_used_percent")].as_f64(),
+            rate_limits[format!("{name}_window_minutes")].as_i64(),
+            None,
+            rate_limits[format!("{name}_resets_in_seconds")].as_i64(),
+        )
+    };
+
+    let resets_at =
+        resets_at.or_else(|| resets_in.map(|secs| observed_at.timestamp().saturating_add(secs)));
+    Some(CodexRateLimitWindow {
+        used_percent: used?.clamp(0.0, 100.0),
+        window_minutes,
+        resets_at,
+    })
+}
+
+fn rate_limits_from_snapshot(
+    snapshot: &RolloutSnapshot,
+    now: DateTime<Utc>,
+) -> Option<CodexRateLimits> {
+    // A window whose reset already passed no longer says anything about usage.
+    let current = |window: CodexRateLimitWindow| {
+        window
+            .resets_at
+            .is_none_or(|reset| reset > now.timestamp())
+            .then_some(window)
+    };
+    let rate_limits = &snapshot.rate_limits;
+    let primary =
+        parse_snapshot_window(rate_limits, "primary", snapshot.timestamp).and_then(current);
+    let secondary =
+        parse_snapshot_window(rate_limits, "secondary", snapshot.timestamp).and_then(current);
+    if primary.is_none() && secondary.is_none() {
+        return None;
+    }
+
+    let credits = rate_limits["credits"]
+        .as_object()
+        .map(|credits| CodexCredits {
+            has_credits: credits
+                .get("has_credits")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            unlimited: credits
+                .get("unlimited")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            balance: credits
+                .get("balance")
+                .and_then(|v| v.as_str())
+                .map(ToString::to_string),
+        });
+
+    Some(CodexRateLimits {
+        connected: true,
+        plan_type: rate_limits["plan_type"].as_str().map(ToString::to_string),
+        primary,
+        secondary,
+        credits,
+        source: Some(RATE_LIMITS_SOURCE_LOCAL.to_string()),
+        updated_at: Some(snapshot.timestamp.to_rfc3339()),
+        error: None,
+    })
+}
+
+/// Newest rate-limit snapshot from `~/.codex/sessions/**/rollout-*.jsonl`.
+fn local_rate_limits() -> Option<CodexRateLimits> {
+    let sessions_dir = codex_sessions_dir()?;
+    let mut rollouts = Vec::new();
+    collect_rollout_files(&sessions_dir, &mut rollouts);
+    rollouts.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+
+    let now = Utc::now();
+    rollouts
+        .iter()
+        .take(MAX_ROLLOUTS_SCANNED)
+        .find_map(|(path, _)| cached_rollout_snapshot(path))
+        .and_then(|snapshot| rate_limits_from_snapshot(&snapshot, now))
+}
+
+/// Plan type of the last successful rate-limit response or local snapshot.
+pub(crate) fn last_known_plan_type() -> Option<String> {
+    last_good_limits()
+        .lock()
+        .ok()?
+        .as_ref()
+        .and_then(|limits| limits.plan_type.clone())
+}
+
+/// `chatgpt_subscription_active_until` of the last decoded auth token.
+pub(crate) fn last_known_subscription_until() -> Option<String> {
+    last_good_info()
+        .lock()
+        .ok()?
+        .as_ref()
+        .and_then(|info| info.subscription_until.clone())
+}
+
+/// Transient OS errors keep the last live result; anything else falls back to
+/// the newest local rollout snapshot before giving up. The rollout scan walks
+/// the whole sessions tree, so it runs on the blocking pool.
+async fn fallback_or_disconnected_limits(error: String) -> CodexRateLimits {
     if is_transient_os_error(&error) {
         if let Ok(guard) = last_good_limits().lock() {
             if let Some(stale) = guard.as_ref() {
@@ -302,18 +537,27 @@ fn fallback_or_disconnected_limits(error: String) -> CodexRateLimits {
             }
         }
     }
-    CodexRateLimits::disconnected(error)
+    let local = tauri::async_runtime::spawn_blocking(local_rate_limits)
+        .await
+        .ok()
+        .flatten();
+    local.unwrap_or_else(|| CodexRateLimits::disconnected(error))
 }
 
 pub async fn fetch_codex_rate_limits() -> CodexRateLimits {
     let auth_json = match read_auth_json() {
         Ok(v) => v,
-        Err(error) => return fallback_or_disconnected_limits(error),
+        Err(error) => return fallback_or_disconnected_limits(error).await,
     };
 
     let access_token = match auth_json["tokens"]["access_token"].as_str() {
         Some(token) => token,
-        None => return CodexRateLimits::disconnected("No access_token found in auth.json"),
+        None => {
+            return fallback_or_disconnected_limits(
+                "No access_token found in auth.json".to_string(),
+            )
+            .await
+        }
     };
 
     let account_id = auth_json["tokens"]["id_token"]
@@ -338,7 +582,7 @@ pub async fn fetch_codex_rate_limits() -> CodexRateLimits {
 
     let response = match request.send().await {
         Ok(resp) => resp,
-        Err(err) => return CodexRateLimits::disconnected(format!("Network error: {err}")),
+        Err(err) => return fallback_or_disconnected_limits(format!("Network error: {err}")).await,
     };
 
     if response.status().as_u16() == 401 || response.status().as_u16() == 403 {
@@ -346,13 +590,14 @@ pub async fn fetch_codex_rate_limits() -> CodexRateLimits {
     }
 
     if !response.status().is_success() {
-        return CodexRateLimits::disconnected(format!("API error: {}", response.status()));
+        return fallback_or_disconnected_limits(format!("API error: {}", response.status())).await;
     }
 
     let data = match response.json::<serde_json::Value>().await {
         Ok(data) => data,
         Err(err) => {
-            return CodexRateLimits::disconnected(format!("Failed to parse response: {err}"))
+            return fallback_or_disconnected_limits(format!("Failed to parse response: {err}"))
+                .await
         }
     };
 
@@ -385,11 +630,83 @@ pub async fn fetch_codex_rate_limits() -> CodexRateLimits {
         primary,
         secondary,
         credits,
+        source: Some(RATE_LIMITS_SOURCE_API.to_string()),
+        updated_at: Some(Utc::now().to_rfc3339()),
         error: None,
     };
 
     if let Ok(mut guard) = last_good_limits().lock() {
         *guard = Some(limits.clone());
     }
+    history::record_codex(&limits);
     limits
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn reads_last_token_count_snapshot() {
+        let rollout = concat!(
+            r#"{"timestamp":"2026-05-01T09:00:00Z","type":"event_msg","payload":{"type":"token_count","rate_limits":{"primary":{"used_percent":10.0,"window_minutes":300,"resets_at":1777640400}}}}"#,
+            "\n",
+            r#"{"timestamp":"2026-05-01T09:05:00Z","type":"response_item","payload":{"type":"message"}}"#,
+            "\n",
+            r#"{"timestamp":"2026-05-01T09:10:00Z","type":"event_msg","payload":{"type":"token_count","rate_limits":{"primary":{"used_percent":25.5,"window_minutes":300,"resets_at":1777640400},"secondary":{"used_percent":40.0,"window_minutes":10080,"resets_in_seconds":3600}}}}"#,
+            "\n",
+        );
+        let snapshot = read_rollout_snapshot(Cursor::new(rollout)).expect("snapshot");
+        assert_eq!(snapshot.timestamp, at("2026-05-01T09:10:00Z"));
+
+        let limits = rate_limits_from_snapshot(&snapshot, at("2026-05-01T09:30:00Z"))
+            .expect("current windows");
+        assert!(limits.connected);
+        assert_eq!(limits.source.as_deref(), Some(RATE_LIMITS_SOURCE_LOCAL));
+        assert_eq!(
+            limits.updated_at.as_deref(),
+            Some("2026-05-01T09:10:00+00:00")
+        );
+        let primary = limits.primary.expect("primary");
+        assert_eq!(primary.used_percent, 25.5);
+        assert_eq!(primary.window_minutes, Some(300));
+        let secondary = limits.secondary.expect("secondary");
+        assert_eq!(
+            secondary.resets_at,
+            Some(at("2026-05-01T10:10:00Z").timestamp())
+        );
+    }
+
+    #[test]
+    fn parses_flat_rate_limit_shape() {
+        let snapshot = RolloutSnapshot {
+            timestamp: at("2026-05-01T09:00:00Z"),
+            rate_limits: serde_json::json!({
+                "primary_used_percent": 50.0,
+                "primary_window_minutes": 300,
+                "primary_resets_in_seconds": 600
+            }),
+        };
+        let limits = rate_limits_from_snapshot(&snapshot, at("2026-05-01T09:05:00Z")).unwrap();
+        assert_eq!(limits.primary.unwrap().used_percent, 50.0);
+        assert!(limits.secondary.is_none());
+    }
+
+    #[test]
+    fn drops_windows_that_already_reset() {
+        let snapshot = RolloutSnapshot {
+            timestamp: at("2026-05-01T09:00:00Z"),
+            rate_limits: serde_json::json!({
+                "primary": { "used_percent": 80.0, "window_minutes": 300, "resets_in_seconds": 600 }
+            }),
+        };
+        assert!(rate_limits_from_snapshot(&snapshot, at("2026-05-01T12:00:00Z")).is_none());
+    }
+}
@@ -0,0 +1,342 @@
+//! User configuration loaded from `<config dir>/quotabar/config.json`
+//! (`~/Library/Application Support/quotabar/config.json` on macOS).
+//!
+//! The file is optional and every section defaults to empty, so a missing or
+//! partially written config never blocks the built-in providers. The parsed
+//! value is cached and re-read only when the file's size or mtime changes.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+const CONFIG_PATH_ENV_KEY: &str = "QUOTABAR_CONFIG";
+const DEFAULT_PLUGIN_INTERVAL_SECS: u64 = 60;
+const DEFAULT_PLUGIN_TIMEOUT_SECS: u64 = 15;
+const DEFAULT_BUDGET_WARN_AT: [u8; 2] = [80, 100];
+const DEFAULT_TRAY_COLOR_ABOVE: u8 = 80;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppConfig {
+    pub plugins: Vec<PluginConfig>,
+    pub http_providers: Vec<HttpProviderConfig>,
+    pub project_tags: Vec<ProjectTagConfig>,
+    pub budgets: Vec<BudgetConfig>,
+    pub plan_prices: Vec<PlanPriceConfig>,
+    pub pricing_overrides: Vec<PricingOverrideConfig>,
+    /// Display currency for local cost when the caller does not pick one,
+    /// converted with the offline rate table.
+    pub currency: Option<String>,
+    pub billing_cycles: Vec<BillingCycleConfig>,
+    pub tray: TrayConfig,
+}
+
+/// Tray icon rendering, e.g. `{ "style": "template", "colorAbove": 80 }`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayConfig {
+    /// `color` (default) or `template` for monochrome menubar icons.
+    #[serde(default)]
+    pub style: String,
+    /// Used percent at which template icons switch to the colored icon.
+    #[serde(default = "default_tray_color_above")]
+    pub color_above: u8,
+}
+
+/// Tags local cost of every repository under `path` (`~` is expanded), e.g.
+/// `{ "path": "~/clients/acme", "tag": "client:acme" }`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProjectTagConfig {
+    pub path: String,
+    pub tag: String,
+}
+
+/// A USD spending limit for one local cost source (`claude`, `codex`,
+/// `cursor`) or for all of them (`all`), over the current day, week or month.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetConfig {
+    #[serde(default = "default_budget_source")]
+    pub source: String,
+    /// `daily`, `weekly` or `monthly`.
+    pub period: String,
+    pub amount: f64,
+    /// Percentages of `amount` that raise a warning, once per period each.
+    #[serde(default = "default_budget_warn_at")]
+    pub warn_at: Vec<u8>,
+    /// Show a tray ring with the share of the budget spent.
+    #[serde(default)]
+    pub tray: bool,
+}
+
+/// Monthly subscription price used by the subscription value report. Without
+/// `plan` the entry applies to the source whatever plan is detected.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanPriceConfig {
+    pub source: String,
+    pub plan: Option<String>,
+    /// USD per seat and month.
+    pub monthly_price: f64,
+    #[serde(default = "default_seats")]
+    pub seats: u32,
+}
+
+/// Token prices in USD per million for a model id or family prefix, e.g.
+/// `{ "model": "claude-sonnet-4", "cacheRead": 0.25 }`. Omitted token types
+/// keep the built-in price.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PricingOverrideConfig {
+    pub model: String,
+    pub input: Option<f64>,
+    pub output: Option<f64>,
+    pub cache_creation: Option<f64>,
+    pub cache_read: Option<f64>,
+    /// Charged in addition to output.
+    pub reasoning: Option<f64>,
+}
+
+/// Day of the month a source's billing cycle renews, e.g.
+/// `{ "source": "claude", "anchorDay": 14 }`. Overrides the anchor derived
+/// from Codex's subscription date or Cursor's `startOfMonth`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BillingCycleConfig {
+    pub source: String,
+    pub anchor_day: u32,
+}
+
+/// An external executable that prints the provider plugin JSON schema
+/// (see `docs/provider-plugins.md`) to stdout.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginConfig {
+    pub id: String,
+    pub name: Option<String>,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default = "default_plugin_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_plugin_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default = "default_true")]
+    pub tray: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl PluginConfig {
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.id.clone())
+    }
+}
+
+/// A REST quota endpoint mapped into windows with JSONPath expressions. Header
+/// values and the URL may reference `${env:NAME}`, `${keychain:service}`,
+/// `${keychain:service/account}` or `${file:path}` secrets.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpProviderConfig {
+    pub id: String,
+    pub name: Option<String>,
+    pub url: String,
+    #[serde(default = "default_http_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub body: Option<serde_json::Value>,
+    #[serde(default = "default_auth_failure_status")]
+    pub auth_failure_status: Vec<u16>,
+    /// JSONPath to the plan name.
+    pub plan_type: Option<String>,
+    #[serde(default)]
+    pub windows: Vec<HttpWindowMapping>,
+    #[serde(default = "default_plugin_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_plugin_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default = "default_true")]
+    pub tray: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl HttpProviderConfig {
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.id.clone())
+    }
+}
+
+/// JSONPath expressions for one quota window. `used` and `limit` (or
+/// `percentage`) are required to produce a tray value.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpWindowMapping {
+    pub id: Option<String>,
+    pub label: Option<String>,
+    pub used: Option<String>,
+    pub limit: Option<String>,
+    pub percentage: Option<String>,
+    pub reset: Option<String>,
+}
+
+fn default_budget_source() -> String {
+    "all".to_string()
+}
+
+fn default_budget_warn_at() -> Vec<u8> {
+    DEFAULT_BUDGET_WARN_AT.to_vec()
+}
+
+fn default_seats() -> u32 {
+    1
+}
+
+fn default_http_method() -> String {
+    "GET".to_string()
+}
+
+fn default_auth_failure_status() -> Vec<u16> {
+    vec![401, 403]
+}
+
+fn default_plugin_interval_secs() -> u64 {
+    DEFAULT_PLUGIN_INTERVAL_SECS
+}
+
+fn default_plugin_timeout_secs() -> u64 {
+    DEFAULT_PLUGIN_TIMEOUT_SECS
+}
+
+fn default_tray_color_above() -> u8 {
+    DEFAULT_TRAY_COLOR_ABOVE
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Clone)]
+struct CachedConfig {
+    path: PathBuf,
+    file_size: u64,
+    modified_at: Option<SystemTime>,
+    config: AppConfig,
+}
+
+static CONFIG_CACHE: OnceLock<Mutex<Option<CachedConfig>>> = OnceLock::new();
+
+fn config_cache() -> &'static Mutex<Option<CachedConfig>> {
+    CONFIG_CACHE.get_or_init(|| Mutex::new(None))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("quotabar"))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var(CONFIG_PATH_ENV_KEY)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    {
+        return Some(PathBuf::from(path));
+    }
+    config_dir().map(|dir| dir.join("config.json"))
+}
+
+/// Return the current config, re-parsing only when the file changed. A parse
+/// error keeps serving the last good config so a half-saved edit does not
+/// tear down running plugins.
+pub fn load_config() -> AppConfig {
+    let Some(path) = config_path() else {
+        return AppConfig::default();
+    };
+    let Ok(metadata) = std::fs::metadata(&path) else {
+        if let Ok(mut guard) = config_cache().lock() {
+            *guard = None;
+        }
+        return AppConfig::default();
+    };
+    let file_size = metadata.len();
+    let modified_at = metadata.modified().ok();
+
+    if let Ok(guard) = config_cache().lock() {
+        if let Some(cached) = guard.as_ref() {
+            if cached.path == path
+                && cached.file_size == file_size
+                && cached.modified_at == modified_at
+            {
+                return cached.config.clone();
+            }
+        }
+    }
+
+    let parsed = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+        .and_then(|content| {
+            serde_json::from_str::<AppConfig>(&content)
+                .map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+        });
+
+    let mut guard = match config_cache().lock() {
+        Ok(guard) => guard,
+        Err(_) => return parsed.unwrap_or_default(),
+    };
+    match parsed {
+        Ok(config) => {
+            *guard = Some(CachedConfig {
+                path,
+                file_size,
+                modified_at,
+                config: config.clone(),
+            });
+            config
+        }
+        Err(error) => {
+            eprintln!("[Config] {error}");
+            guard
+                .as_ref()
+                .map(|cached| cached.config.clone())
+                .unwrap_or_default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_sections_default_to_empty() {
+        let config: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.plugins.is_empty());
+        assert!(config.http_providers.is_empty());
+        assert!(config.project_tags.is_empty());
+        assert!(config.budgets.is_empty());
+        assert!(config.currency.is_none());
+        assert!(config.billing_cycles.is_empty());
+        assert!(config.plan_prices.is_empty());
+        assert!(config.pricing_overrides.is_empty());
+    }
+
+    #[test]
+    fn plugin_defaults_apply() {
+        let config: AppConfig = serde_json::from_str(
+            r#"{ "plugins": [{ "id": "gemini", "command": "/usr/local/bin/gemini-quota" }] }"#,
+        )
+        .unwrap();
+        let plugin = &config.plugins[0];
+        assert_eq!(plugin.display_name(), "gemini");
+        assert_eq!(plugin.interval_secs, DEFAULT_PLUGIN_INTERVAL_SECS);
+        assert_eq!(plugin.timeout_secs, DEFAULT_PLUGIN_TIMEOUT_SECS);
+        assert!(plugin.tray);
+        assert!(plugin.enabled);
+    }
+}
@@ -1,31 +1,67 @@
-//! Local cost summaries powered by the `ccstats` SDK.
+//! Local cost summaries.
+//!
+//! Claude Code and Codex ranges are answered from the incremental
+//! [`cost_index`](crate::services::cost_index) in USD. Cursor and named
+//! timezones still go through the `ccstats` SDK, which rescans the logs on
+//! every call.
+//!
+//! Every overview is computed in USD; other currencies are converted
+//! afterwards with the offline [`exchange`](crate::services::exchange) rates.
 
+use crate::services::budget::{self, BudgetStatus};
+use crate::services::config;
+use crate::services::cost_combined::{self, ALL_COST_SOURCE};
+use crate::services::cost_index::{self, IndexedSource};
+use crate::services::cost_range::{
+    self, CostPeriod, CostRangeRequest, RangeTimezone, BILLING_CYCLE_RANGE,
+};
+use crate::services::exchange::{self, ExchangeRate};
+use crate::services::pricing::PriceTable;
+use crate::services::projects::{self, ProjectTagger};
+use crate::services::{codex, cursor, openai};
 use ccstats::{
     summarize_cost, CostSummary, ModelCostSummary, SummaryOptions, TokenBreakdown, UsageRange,
     UsageSource,
 };
-use chrono::Utc;
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate, Utc};
 use once_cell::sync::Lazy;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
     str::FromStr,
-    sync::Mutex,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
     time::{Duration, Instant},
 };
+use tauri::{AppHandle, Emitter};
 
 const CACHE_TTL: Duration = Duration::from_secs(300);
+const COST_CACHE_FILE: &str = "cost-cache.json";
+/// Cache writes within this window are coalesced into one file write.
+const PERSIST_DEBOUNCE: Duration = Duration::from_secs(2);
+/// Emitted with the fresh [`CostOverview`] once a background refresh of a
+/// restored overview completes.
+pub const COST_OVERVIEW_UPDATED_EVENT: &str = "cost-overview-updated";
 
 static COST_CACHE: Lazy<Mutex<HashMap<String, CachedOverview>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
+    Lazy::new(|| Mutex::new(load_persisted_overviews()));
+static REFRESHING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+static PERSIST_PENDING: AtomicBool = AtomicBool::new(false);
 
 #[derive(Clone)]
 struct CachedOverview {
-    inserted_at: Instant,
+    /// `None` for overviews restored from disk that have not been recomputed
+    /// in this session.
+    inserted_at: Option<Instant>,
     overview: CostOverview,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CostOverview {
     pub source: String,
@@ -34,9 +70,67 @@ pub struct CostOverview {
     pub generated_at: String,
     pub cached: bool,
     pub ranges: Vec<CostRangeSummary>,
+    /// Configured budgets for this source, evaluated on every response.
+    #[serde(default)]
+    pub budgets: Vec<BudgetStatus>,
+    /// Models without a price in any range; their tokens are excluded from
+    /// the cost.
+    #[serde(default)]
+    pub unpriced_models: Vec<UnpricedModel>,
+    /// Rate used to convert from USD; `None` for USD overviews.
+    #[serde(default)]
+    pub exchange_rate: Option<ExchangeRate>,
+    /// Set when the provider had more data than was fetched, so the ranges
+    /// undercount.
+    #[serde(default)]
+    pub truncated: bool,
+    /// Source, display currency and timezone of a default-range overview, so
+    /// listeners of [`COST_OVERVIEW_UPDATED_EVENT`] can skip other views.
+    #[serde(default)]
+    pub view_key: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnpricedModel {
+    pub model: String,
+    /// Tokens in the widest range the model appears in.
+    pub total_tokens: i64,
+    /// Priced as a related model family rather than skipped; the cost is an
+    /// estimate.
+    #[serde(default)]
+    pub guessed: bool,
+}
+
+/// Unpriced models and models with guessed prices across `ranges`, most
+/// tokens first.
+pub(crate) fn unpriced_models(ranges: &[CostRangeSummary]) -> Vec<UnpricedModel> {
+    let mut unpriced: HashMap<&str, (i64, bool)> = HashMap::new();
+    for model in ranges.iter().flat_map(|range| &range.models) {
+        let skipped = model.cost_usd.is_none();
+        if (skipped || model.price_guessed) && model.tokens.total_tokens > 0 {
+            let (tokens, guessed) = unpriced.entry(model.model.as_str()).or_default();
+            *tokens = (*tokens).max(model.tokens.total_tokens);
+            *guessed = !skipped;
+        }
+    }
+    let mut models: Vec<UnpricedModel> = unpriced
+        .into_iter()
+        .map(|(model, (total_tokens, guessed))| UnpricedModel {
+            model: model.to_string(),
+            total_tokens,
+            guessed,
+        })
+        .collect();
+    models.sort_by(|a, b| {
+        b.total_tokens
+            .cmp(&a.total_tokens)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+    models
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CostRangeSummary {
     pub range: String,
@@ -48,12 +142,49 @@ pub struct CostRangeSummary {
     pub cost_usd: Option<f64>,
     pub tokens: CostTokenBreakdown,
     pub models: Vec<CostModelSummary>,
+    pub projects: Vec<CostProjectSummary>,
+    /// Project costs rolled up by the `projectTags` config section.
+    #[serde(default)]
+    pub tags: Vec<CostTagSummary>,
+    /// Per-source subtotals of the combined `all` overview.
+    #[serde(default)]
+    pub sources: Vec<CostSourceSubtotal>,
     pub valid_entries: i64,
     pub skipped_entries: i64,
     pub elapsed_ms: f64,
+    /// The preceding period, when the overview was requested with `compare`.
+    #[serde(default)]
+    pub comparison: Option<CostRangeComparison>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostRangeComparison {
+    pub since: String,
+    pub until: String,
+    pub cost: Option<f64>,
+    pub cost_usd: Option<f64>,
+    pub tokens: CostTokenBreakdown,
+    /// Current minus previous cost.
+    pub delta_cost: Option<f64>,
+    /// `None` when the previous period cost nothing.
+    pub delta_percent: Option<f64>,
+    pub models: Vec<CostModelDelta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostModelDelta {
+    pub model: String,
+    pub cost: Option<f64>,
+    pub previous_cost: Option<f64>,
+    pub delta_cost: Option<f64>,
+    pub delta_percent: Option<f64>,
+    pub total_tokens: i64,
+    pub previous_total_tokens: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CostTokenBreakdown {
     pub input_tokens: i64,
@@ -64,30 +195,187 @@ pub struct CostTokenBreakdown {
     pub total_tokens: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CostModelSummary {
     pub model: String,
     pub cost: Option<f64>,
     pub cost_usd: Option<f64>,
     pub tokens: CostTokenBreakdown,
+    /// Priced as a related model family; see
+    /// [`PriceMatch::guessed`](crate::services::pricing::PriceMatch::guessed).
+    #[serde(default)]
+    pub price_guessed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostProjectSummary {
+    pub project: String,
+    #[serde(default)]
+    pub tag: Option<String>,
+    pub cost: Option<f64>,
+    pub cost_usd: Option<f64>,
+    pub tokens: CostTokenBreakdown,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostSourceSubtotal {
+    pub source: String,
+    pub display_name: String,
+    pub cost: Option<f64>,
+    pub cost_usd: Option<f64>,
+    pub tokens: CostTokenBreakdown,
+    /// Set when this source could not be summarized.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostTagSummary {
+    pub tag: String,
+    pub cost: Option<f64>,
+    pub cost_usd: Option<f64>,
+    pub tokens: CostTokenBreakdown,
+    pub projects: Vec<String>,
+}
+
+/// Serve the overview for `source`. On the first request after launch an
+/// overview persisted by a previous run is returned immediately with
+/// `cached: true` while a background task recomputes it and emits
+/// [`COST_OVERVIEW_UPDATED_EVENT`]. Only the default ranges are cached; custom
+/// and comparative ranges are always computed from the index.
 pub async fn get_cost_overview(
+    app: AppHandle,
     source: String,
     currency: Option<String>,
     timezone: Option<String>,
+    range: Option<CostRangeRequest>,
     force: Option<bool>,
 ) -> Result<CostOverview, String> {
+    let force = force.unwrap_or(false);
+    let range = range.unwrap_or_default();
+    let currency = normalize_optional(currency).or_else(|| config::load_config().currency);
+    if !range.is_default() {
+        let overview = tauri::async_runtime::spawn_blocking(move || {
+            build_ranged_overview(source, currency, timezone, range, force)
+        })
+        .await
+        .map_err(|err| format!("Cost summary task failed: {err}"))??;
+        // Budgets track the default ranges; a custom view neither warns nor
+        // touches their trays.
+        return Ok(budget::annotate(overview));
+    }
+    let view_key = view_key(&source, currency.clone(), timezone.as_deref())?;
+    if !force {
+        // Overviews are cached in USD and converted on the way out.
+        let cache_key = overview_cache_key(&source, timezone.as_deref())?;
+        if let Some(restored) = restored_overview(&cache_key) {
+            let mut restored = match foreign_currency(currency.clone()) {
+                Some(code) => convert_overview(restored, &code)?,
+                None => restored,
+            };
+            restored.view_key = Some(view_key.clone());
+            refresh_in_background(app.clone(), cache_key, view_key, source, currency, timezone);
+            return Ok(budget::annotate(restored));
+        }
+    }
+    let mut overview = compute_cost_overview(source, currency, timezone, force).await?;
+    overview.view_key = Some(view_key);
+    Ok(budget::apply(&app, overview))
+}
+
+async fn compute_cost_overview(
+    source: String,
+    currency: Option<String>,
+    timezone: Option<String>,
+    force: bool,
+) -> Result<CostOverview, String> {
+    if source
+        .trim()
+        .eq_ignore_ascii_case(openai::OPENAI_COST_SOURCE)
+    {
+        let overview = openai::get_cost_overview(force).await?;
+        return match foreign_currency(currency) {
+            Some(code) => convert_overview(overview, &code),
+            None => Ok(overview),
+        };
+    }
+
     tauri::async_runtime::spawn_blocking(move || {
-        let overview = build_cost_overview(source, currency, timezone, force.unwrap_or(false));
-        relieve_allocator_pressure();
-        overview
+        build_cost_overview(source, currency, timezone, force)
     })
     .await
     .map_err(|err| format!("Cost summary task failed: {err}"))?
 }
 
+fn refresh_in_background(
+    app: AppHandle,
+    cache_key: String,
+    view_key: String,
+    source: String,
+    currency: Option<String>,
+    timezone: Option<String>,
+) {
+    match REFRESHING.lock() {
+        Ok(mut refreshing) => {
+            if !refreshing.insert(cache_key.clone()) {
+                return;
+            }
+        }
+        Err(_) => return,
+    }
+
+    tauri::async_runtime::spawn(async move {
+        // Not forced: the stale entry itself is never served from the cache,
+        // while the fresh parts of a combined overview still are.
+        match compute_cost_overview(source, currency, timezone, false).await {
+            Ok(mut overview) => {
+                overview.view_key = Some(view_key);
+                let overview = budget::apply(&app, overview);
+                let _ = app.emit(COST_OVERVIEW_UPDATED_EVENT, overview);
+            }
+            Err(error) => eprintln!("[Cost] background refresh of {cache_key} failed: {error}"),
+        }
+        if let Ok(mut refreshing) = REFRESHING.lock() {
+            refreshing.remove(&cache_key);
+        }
+    });
+}
+
+/// The cache key with the display currency in place of USD.
+fn view_key(
+    source: &str,
+    currency: Option<String>,
+    timezone: Option<&str>,
+) -> Result<String, String> {
+    let cache_key = overview_cache_key(source, timezone)?;
+    Ok(match foreign_currency(currency) {
+        Some(code) => cache_key.replacen("|USD|", &format!("|{code}|"), 1),
+        None => cache_key,
+    })
+}
+
+/// Cache key shared by the in-memory cache and the persisted file.
+fn overview_cache_key(source: &str, timezone: Option<&str>) -> Result<String, String> {
+    if source
+        .trim()
+        .eq_ignore_ascii_case(openai::OPENAI_COST_SOURCE)
+    {
+        return Ok(openai::cost_cache_key());
+    }
+    let source = if cost_combined::is_all_source(source) {
+        ALL_COST_SOURCE
+    } else {
+        UsageSource::from_str(source)
+            .map_err(|err| err.to_string())?
+            .as_str()
+    };
+    let timezone = timezone.map(str::trim).filter(|value| !value.is_empty());
+    Ok(format!("{}|USD|{}", source, timezone.unwrap_or("local")))
+}
+
 #[cfg(target_os = "macos")]
 fn relieve_allocator_pressure() {
     unsafe extern "C" {
@@ -105,34 +393,128 @@ fn relieve_allocator_pressure() {
 #[cfg(not(target_os = "macos"))]
 fn relieve_allocator_pressure() {}
 
-fn build_cost_overview(
+pub(crate) fn build_cost_overview(
     source: String,
     currency: Option<String>,
     timezone: Option<String>,
     force: bool,
 ) -> Result<CostOverview, String> {
-    let source = UsageSource::from_str(&source).map_err(|err| err.to_string())?;
-    let currency = normalize_optional(currency);
-    let timezone = normalize_optional(timezone);
-    let cache_key = format!(
-        "{}|{}|{}",
-        source.as_str(),
-        currency.as_deref().unwrap_or("USD"),
-        timezone.as_deref().unwrap_or("local")
-    );
-
+    if let Some(code) = foreign_currency(currency) {
+        let overview = build_cost_overview(source, None, timezone, force)?;
+        return convert_overview(overview, &code);
+    }
+    let cache_key = overview_cache_key(&source, timezone.as_deref())?;
+    // The index watcher marks overviews stale as soon as new usage arrives,
+    // so every source can be served from the cache within the TTL.
     if !force {
         if let Some(cached) = get_cached_overview(&cache_key)? {
             return Ok(cached);
         }
     }
+    if cost_combined::is_all_source(&source) {
+        // Parts are served from their own caches while those are fresh.
+        let overview = cost_combined::build_combined_overview(timezone, None, force)?;
+        set_cached_overview(cache_key, overview.clone())?;
+        return Ok(overview);
+    }
+    let source = UsageSource::from_str(&source).map_err(|err| err.to_string())?;
+    let timezone = normalize_optional(timezone);
+
+    if let Some(indexed) = IndexedSource::from_name(source.as_str()) {
+        if let Some(zone) = RangeTimezone::parse(timezone.as_deref()) {
+            let today = zone.date_of(Utc::now());
+            let mut periods = cost_range::default_periods(today);
+            if let Some(anchor_day) = billing_anchor_day(source) {
+                periods.push(cost_range::billing_cycle_period(today, anchor_day));
+            }
+            let overview = build_indexed_overview(indexed, &zone, &periods, false, force)?;
+            set_cached_overview(cache_key, overview.clone())?;
+            return Ok(overview);
+        }
+    }
 
+    let overview = build_ccstats_overview(source, timezone);
+    relieve_allocator_pressure();
+    let overview = overview?;
+    set_cached_overview(cache_key, overview.clone())?;
+    Ok(overview)
+}
+
+/// Custom, rolling and comparative ranges. These need per-entry data, so they
+/// are only available for the indexed sources.
+pub(crate) fn build_ranged_overview(
+    source: String,
+    currency: Option<String>,
+    timezone: Option<String>,
+    range: CostRangeRequest,
+    force: bool,
+) -> Result<CostOverview, String> {
+    if let Some(code) = foreign_currency(currency) {
+        let overview = build_ranged_overview(source, None, timezone, range, force)?;
+        return convert_overview(overview, &code);
+    }
+    if cost_combined::is_all_source(&source) {
+        return cost_combined::build_combined_overview(timezone, Some(range), force);
+    }
+    let indexed = IndexedSource::from_name(&source).ok_or_else(|| {
+        format!("Custom cost ranges are only available for Claude Code and Codex, not '{source}'")
+    })?;
+    let timezone = normalize_optional(timezone);
+    let zone = RangeTimezone::parse(timezone.as_deref()).ok_or_else(|| {
+        format!(
+            "Unsupported timezone '{}' for custom ranges; use local, UTC or an offset like +02:00",
+            timezone.as_deref().unwrap_or_default()
+        )
+    })?;
+
+    let today = zone.date_of(Utc::now());
+    let periods = cost_range::resolve_periods(&range, today)?;
+    build_indexed_overview(indexed, &zone, &periods, range.compare, force)
+}
+
+/// Day of the month the billing cycle of `source` renews: the configured
+/// `billingCycles` anchor, else the day of Codex's `subscription_until` or
+/// Cursor's `startOfMonth` from the last quota response.
+fn billing_anchor_day(source: UsageSource) -> Option<u32> {
+    let configured = config::load_config()
+        .billing_cycles
+        .iter()
+        .find(|cycle| cycle.source.trim().eq_ignore_ascii_case(source.as_str()))
+        .map(|cycle| cycle.anchor_day)
+        .filter(|day| (1..=31).contains(day));
+    if configured.is_some() {
+        return configured;
+    }
+    let derived = match source {
+        UsageSource::Claude => None,
+        UsageSource::Codex => codex::last_known_subscription_until(),
+        UsageSource::Cursor => cursor::last_known_cycle_start(),
+    }?;
+    // The date as written, so a UTC midnight does not shift to the day before.
+    NaiveDate::parse_from_str(derived.get(..10)?, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.day())
+}
+
+fn source_display_name(source: UsageSource) -> String {
+    match source {
+        UsageSource::Claude => "Claude Code".to_string(),
+        UsageSource::Codex => "Codex".to_string(),
+        UsageSource::Cursor => "Cursor".to_string(),
+    }
+}
+
+fn build_ccstats_overview(
+    source: UsageSource,
+    timezone: Option<String>,
+) -> Result<CostOverview, String> {
     let range_specs = [
         ("today", "Today", UsageRange::Today),
         ("week", "This Week", UsageRange::ThisWeek),
         ("month", "This Month", UsageRange::ThisMonth),
     ];
 
+    let prices = PriceTable::load();
     let mut ranges = Vec::with_capacity(range_specs.len());
     for (range_key, label, range) in range_specs {
         let summary = summarize_cost(SummaryOptions {
@@ -141,32 +523,385 @@ fn build_cost_overview(
             timezone: timezone.clone(),
             offline: true,
             strict_pricing: false,
-            currency: currency.clone(),
+            currency: None,
         })
         .map_err(|err| err.to_string())?;
-        ranges.push(CostRangeSummary::from_summary(range_key, label, summary));
+        ranges.push(CostRangeSummary::from_summary(
+            range_key, label, summary, &prices,
+        ));
+    }
+    // A cycle renewing on the 1st is the calendar month; any other is
+    // summarized from its first day through today as `ccstats` sees it, which
+    // also covers named timezones.
+    let today = ranges
+        .iter()
+        .find(|range| range.range == "today")
+        .and_then(|range| range.since.as_deref())
+        .and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok());
+    if let (Some(anchor_day), Some(today)) = (billing_anchor_day(source), today) {
+        let cycle = if anchor_day == 1 {
+            ranges
+                .iter()
+                .find(|range| range.range == "month")
+                .map(|month| CostRangeSummary {
+                    range: BILLING_CYCLE_RANGE.to_string(),
+                    label: "Billing Cycle".to_string(),
+                    ..month.clone()
+                })
+        } else {
+            let period = cost_range::billing_cycle_period(today, anchor_day);
+            let summary = summarize_cost(SummaryOptions {
+                source,
+                range: UsageRange::DateRange {
+                    since: Some(period.since),
+                    until: Some(period.until),
+                },
+                timezone: timezone.clone(),
+                offline: true,
+                strict_pricing: false,
+                currency: None,
+            })
+            .map_err(|err| err.to_string())?;
+            Some(CostRangeSummary::from_summary(
+                &period.key,
+                &period.label,
+                summary,
+                &prices,
+            ))
+        };
+        ranges.extend(cycle);
+    }
+
+    Ok(CostOverview {
+        source: source.as_str().to_string(),
+        display_name: source_display_name(source),
+        currency: "USD".to_string(),
+        generated_at: Utc::now().to_rfc3339(),
+        cached: false,
+        unpriced_models: unpriced_models(&ranges),
+        ranges,
+        budgets: Vec::new(),
+        exchange_rate: None,
+        truncated: false,
+        view_key: None,
+    })
+}
+
+/// Sum the indexed buckets of the inclusive `(since, until)` days into a range
+/// summary priced in USD. Entries for models missing from the pricing table
+/// keep their tokens but are counted as skipped.
+pub(crate) fn summarize_indexed_range(
+    source: IndexedSource,
+    range: &str,
+    label: &str,
+    (since, until): (NaiveDate, NaiveDate),
+    zone: &RangeTimezone,
+    prices: &PriceTable,
+    force: bool,
+) -> Result<CostRangeSummary, String> {
+    let started = Instant::now();
+    let start = zone.start_of_day(since);
+    let end = zone.start_of_day(until + ChronoDuration::days(1));
+
+    let mut by_model: HashMap<String, (CostTokenBreakdown, i64)> = HashMap::new();
+    let mut by_project: HashMap<(String, String), CostTokenBreakdown> = HashMap::new();
+    cost_index::for_each_bucket(source, start, end, force, |key, totals| {
+        let (tokens, entries) = by_model.entry(key.model.clone()).or_default();
+        tokens.accumulate(&totals.tokens);
+        *entries += totals.entries;
+        by_project
+            .entry((key.project.clone(), key.model.clone()))
+            .or_default()
+            .accumulate(&totals.tokens);
+    })?;
+    let (projects, tags) = summarize_projects(by_project, &ProjectTagger::from_config(), prices);
+
+    let mut tokens = CostTokenBreakdown::default();
+    let mut cost_usd = 0.0;
+    let mut valid_entries = 0;
+    let mut skipped_entries = 0;
+    let mut models: Vec<CostModelSummary> = by_model
+        .into_iter()
+        .map(|(model, (model_tokens, entries))| {
+            let price = prices.resolve(&model);
+            let model_cost = price.map(|found| found.pricing.cost_usd(&model_tokens));
+            tokens.accumulate(&model_tokens);
+            match model_cost {
+                Some(cost) => {
+                    cost_usd += cost;
+                    valid_entries += entries;
+                }
+                None => skipped_entries += entries,
+            }
+            CostModelSummary {
+                model,
+                cost: model_cost,
+                cost_usd: model_cost,
+                tokens: model_tokens,
+                price_guessed: price.is_some_and(|found| found.guessed),
+            }
+        })
+        .collect();
+    models.sort_by(|a, b| {
+        b.cost_usd
+            .unwrap_or(0.0)
+            .total_cmp(&a.cost_usd.unwrap_or(0.0))
+            .then_with(|| b.tokens.total_tokens.cmp(&a.tokens.total_tokens))
+    });
+
+    Ok(CostRangeSummary {
+        range: range.to_string(),
+        label: label.to_string(),
+        since: Some(since.to_string()),
+        until: Some(until.to_string()),
+        currency: "USD".to_string(),
+        cost: Some(cost_usd),
+        cost_usd: Some(cost_usd),
+        tokens,
+        models,
+        projects,
+        tags,
+        sources: Vec::new(),
+        valid_entries,
+        skipped_entries,
+        elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+        comparison: None,
+    })
+}
+
+/// Collapse `(working directory, model)` token totals into repository roots,
+/// priced per model, and roll tagged projects up into tag totals. Both lists
+/// are ordered by cost.
+fn summarize_projects(
+    by_project: HashMap<(String, String), CostTokenBreakdown>,
+    tagger: &ProjectTagger,
+    prices: &PriceTable,
+) -> (Vec<CostProjectSummary>, Vec<CostTagSummary>) {
+    let mut roots: HashMap<String, (f64, CostTokenBreakdown)> = HashMap::new();
+    for ((cwd, model), tokens) in by_project {
+        let cost = prices.cost_usd(&model, &tokens).unwrap_or(0.0);
+        let (root_cost, root_tokens) = roots.entry(projects::repository_root(&cwd)).or_default();
+        *root_cost += cost;
+        root_tokens.accumulate(&tokens);
+    }
+
+    let mut tags: HashMap<String, CostTagSummary> = HashMap::new();
+    let mut summaries: Vec<CostProjectSummary> = roots
+        .into_iter()
+        .map(|(project, (cost, tokens))| {
+            let tag = tagger.tag_for(&project);
+            if let Some(tag) = &tag {
+                let summary = tags.entry(tag.clone()).or_insert_with(|| CostTagSummary {
+                    tag: tag.clone(),
+                    cost: Some(0.0),
+                    cost_usd: Some(0.0),
+                    tokens: CostTokenBreakdown::default(),
+                    projects: Vec::new(),
+                });
+                let total = summary.cost.unwrap_or(0.0) + cost;
+                summary.cost = Some(total);
+                summary.cost_usd = Some(total);
+                summary.tokens.accumulate(&tokens);
+                summary.projects.push(project.clone());
+            }
+            CostProjectSummary {
+                project,
+                tag,
+                cost: Some(cost),
+                cost_usd: Some(cost),
+                tokens,
+            }
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        b.cost
+            .unwrap_or(0.0)
+            .total_cmp(&a.cost.unwrap_or(0.0))
+            .then_with(|| a.project.cmp(&b.project))
+    });
+
+    let mut tags: Vec<CostTagSummary> = tags.into_values().collect();
+    for tag in &mut tags {
+        tag.projects.sort();
+    }
+    tags.sort_by(|a, b| {
+        b.cost
+            .unwrap_or(0.0)
+            .total_cmp(&a.cost.unwrap_or(0.0))
+            .then_with(|| a.tag.cmp(&b.tag))
+    });
+    (summaries, tags)
+}
+
+/// Pair a range with its preceding period. Models are ordered by the size of
+/// their change so the biggest movers come first.
+pub(crate) fn compare_ranges(
+    current: &CostRangeSummary,
+    previous: CostRangeSummary,
+) -> CostRangeComparison {
+    fn delta(current: Option<f64>, previous: Option<f64>) -> (Option<f64>, Option<f64>) {
+        let current = current.unwrap_or(0.0);
+        let previous = previous.unwrap_or(0.0);
+        let percent = (previous > 0.0).then(|| (current - previous) / previous * 100.0);
+        (Some(current - previous), percent)
+    }
+
+    let mut by_model: HashMap<&str, (Option<&CostModelSummary>, Option<&CostModelSummary>)> =
+        HashMap::new();
+    for model in &current.models {
+        by_model.entry(&model.model).or_default().0 = Some(model);
+    }
+    for model in &previous.models {
+        by_model.entry(&model.model).or_default().1 = Some(model);
+    }
+    let mut models: Vec<CostModelDelta> = by_model
+        .into_iter()
+        .map(|(model, (now, before))| {
+            let cost = now.and_then(|m| m.cost);
+            let previous_cost = before.and_then(|m| m.cost);
+            let (delta_cost, delta_percent) = if cost.is_none() && previous_cost.is_none() {
+                (None, None)
+            } else {
+                delta(cost, previous_cost)
+            };
+            CostModelDelta {
+                model: model.to_string(),
+                cost,
+                previous_cost,
+                delta_cost,
+                delta_percent,
+                total_tokens: now.map_or(0, |m| m.tokens.total_tokens),
+                previous_total_tokens: before.map_or(0, |m| m.tokens.total_tokens),
+            }
+        })
+        .collect();
+    models.sort_by(|a, b| {
+        b.delta_cost
+            .unwrap_or(0.0)
+            .abs()
+            .total_cmp(&a.delta_cost.unwrap_or(0.0).abs())
+            .then_with(|| a.model.cmp(&b.model))
+    });
+
+    let (delta_cost, delta_percent) = delta(current.cost, previous.cost);
+    CostRangeComparison {
+        since: previous.since.unwrap_or_default(),
+        until: previous.until.unwrap_or_default(),
+        cost: previous.cost,
+        cost_usd: previous.cost_usd,
+        tokens: previous.tokens,
+        delta_cost,
+        delta_percent,
+        models,
+    }
+}
+
+fn build_indexed_overview(
+    source: IndexedSource,
+    zone: &RangeTimezone,
+    periods: &[CostPeriod],
+    compare: bool,
+    force: bool,
+) -> Result<CostOverview, String> {
+    let prices = PriceTable::load();
+    let mut ranges = Vec::with_capacity(periods.len());
+    for period in periods {
+        // Only the first query needs a forced refresh; the rest share it.
+        let force = force && ranges.is_empty();
+        let mut summary = summarize_indexed_range(
+            source,
+            &period.key,
+            &period.label,
+            (period.since, period.until),
+            zone,
+            &prices,
+            force,
+        )?;
+        if compare {
+            let previous = summarize_indexed_range(
+                source,
+                &period.key,
+                "",
+                period.previous(),
+                zone,
+                &prices,
+                false,
+            )?;
+            summary.comparison = Some(compare_ranges(&summary, previous));
+        }
+        ranges.push(summary);
     }
 
     let display_name = match source {
-        UsageSource::Claude => "Claude Code".to_string(),
-        UsageSource::Codex => "Codex".to_string(),
-        UsageSource::Cursor => "Cursor".to_string(),
+        IndexedSource::Claude => source_display_name(UsageSource::Claude),
+        IndexedSource::Codex => source_display_name(UsageSource::Codex),
     };
-    let currency = ranges
-        .first()
-        .map(|range| range.currency.clone())
-        .unwrap_or_else(|| currency.unwrap_or_else(|| "USD".to_string()));
-    let overview = CostOverview {
+    Ok(CostOverview {
         source: source.as_str().to_string(),
         display_name,
-        currency,
+        currency: "USD".to_string(),
         generated_at: Utc::now().to_rfc3339(),
         cached: false,
+        unpriced_models: unpriced_models(&ranges),
         ranges,
-    };
+        budgets: Vec::new(),
+        exchange_rate: None,
+        truncated: false,
+        view_key: None,
+    })
+}
 
-    set_cached_overview(cache_key, overview.clone())?;
-    Ok(overview)
+/// The requested currency when it is not USD.
+fn foreign_currency(currency: Option<String>) -> Option<String> {
+    normalize_optional(currency)
+        .map(|code| code.to_ascii_uppercase())
+        .filter(|code| code != "USD")
+}
+
+/// Convert a USD overview into `currency` with the offline rate table.
+/// `cost_usd` fields stay in USD, so budgets keep comparing dollars.
+pub(crate) fn convert_overview(
+    overview: CostOverview,
+    currency: &str,
+) -> Result<CostOverview, String> {
+    Ok(apply_rate(overview, exchange::rate_for(currency)?))
+}
+
+/// Convert a USD overview with an already resolved `rate`.
+pub(crate) fn apply_rate(mut overview: CostOverview, rate: ExchangeRate) -> CostOverview {
+    let convert = |value: &mut Option<f64>| {
+        if let Some(value) = value {
+            *value *= rate.rate;
+        }
+    };
+    for range in &mut overview.ranges {
+        range.currency = rate.currency.clone();
+        convert(&mut range.cost);
+        for model in &mut range.models {
+            convert(&mut model.cost);
+        }
+        for project in &mut range.projects {
+            convert(&mut project.cost);
+        }
+        for tag in &mut range.tags {
+            convert(&mut tag.cost);
+        }
+        for subtotal in &mut range.sources {
+            convert(&mut subtotal.cost);
+        }
+        if let Some(comparison) = &mut range.comparison {
+            convert(&mut comparison.cost);
+            convert(&mut comparison.delta_cost);
+            for model in &mut comparison.models {
+                convert(&mut model.cost);
+                convert(&mut model.previous_cost);
+                convert(&mut model.delta_cost);
+            }
+        }
+    }
+    overview.currency = rate.currency.clone();
+    overview.exchange_rate = Some(rate);
+    overview
 }
 
 fn normalize_optional(value: Option<String>) -> Option<String> {
@@ -175,12 +910,15 @@ fn normalize_optional(value: Option<String>) -> Option<String> {
         .filter(|item| !item.is_empty())
 }
 
-fn get_cached_overview(cache_key: &str) -> Result<Option<CostOverview>, String> {
+pub(crate) fn get_cached_overview(cache_key: &str) -> Result<Option<CostOverview>, String> {
     let cache = COST_CACHE.lock().map_err(|err| err.to_string())?;
     let Some(cached) = cache.get(cache_key) else {
         return Ok(None);
     };
-    if cached.inserted_at.elapsed() > CACHE_TTL {
+    let Some(inserted_at) = cached.inserted_at else {
+        return Ok(None);
+    };
+    if inserted_at.elapsed() > CACHE_TTL {
         return Ok(None);
     }
 
@@ -189,21 +927,135 @@ fn get_cached_overview(cache_key: &str) -> Result<Option<CostOverview>, String>
     Ok(Some(overview))
 }
 
-fn set_cached_overview(cache_key: String, overview: CostOverview) -> Result<(), String> {
+/// Mark the cached overviews of `source` and the combined ones as outdated.
+/// Like overviews restored at launch, they are served once more while a
+/// background refresh recomputes them.
+pub(crate) fn mark_stale(source: &str) {
+    let Ok(mut cache) = COST_CACHE.lock() else {
+        return;
+    };
+    for (key, cached) in cache.iter_mut() {
+        let key_source = key.split('|').next().unwrap_or_default();
+        if key_source == source || key_source == ALL_COST_SOURCE {
+            cached.inserted_at = None;
+        }
+    }
+}
+
+/// An overview persisted by a previous run, or marked stale, that has not
+/// been recomputed yet.
+fn restored_overview(cache_key: &str) -> Option<CostOverview> {
+    let cache = COST_CACHE.lock().ok()?;
+    let cached = cache.get(cache_key)?;
+    if cached.inserted_at.is_some() {
+        return None;
+    }
+    let mut overview = cached.overview.clone();
+    overview.cached = true;
+    Some(overview)
+}
+
+pub(crate) fn set_cached_overview(cache_key: String, overview: CostOverview) -> Result<(), String> {
     let mut cache = COST_CACHE.lock().map_err(|err| err.to_string())?;
     cache.insert(
         cache_key,
         CachedOverview {
-            inserted_at: Instant::now(),
+            inserted_at: Some(Instant::now()),
             overview,
         },
     );
+    drop(cache);
+    schedule_persist();
     Ok(())
 }
 
+/// Write the cache file once no overview has been cached for
+/// [`PERSIST_DEBOUNCE`]; the write itself happens without holding the lock.
+fn schedule_persist() {
+    if PERSIST_PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let spawned = thread::Builder::new()
+        .name("cost-cache-writer".to_string())
+        .spawn(|| {
+            thread::sleep(PERSIST_DEBOUNCE);
+            PERSIST_PENDING.store(false, Ordering::SeqCst);
+            let json = match COST_CACHE.lock() {
+                Ok(cache) => serialize_overviews(&cache),
+                Err(err) => Err(err.to_string()),
+            };
+            match json {
+                Ok(json) => persist_overviews(&json),
+                Err(error) => eprintln!("[Cost] failed to serialize cost cache: {error}"),
+            }
+        });
+    if spawned.is_err() {
+        PERSIST_PENDING.store(false, Ordering::SeqCst);
+    }
+}
+
+fn cost_cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("quotabar").join(COST_CACHE_FILE))
+}
+
+/// Entries that no longer deserialize (e.g. written by an older release) are
+/// dropped individually rather than discarding the whole file.
+fn parse_persisted_overviews(raw: &str) -> HashMap<String, CachedOverview> {
+    let Ok(entries) = serde_json::from_str::<HashMap<String, serde_json::Value>>(raw) else {
+        return HashMap::new();
+    };
+    entries
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let overview = serde_json::from_value::<CostOverview>(value).ok()?;
+            Some((
+                key,
+                CachedOverview {
+                    inserted_at: None,
+                    overview,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn load_persisted_overviews() -> HashMap<String, CachedOverview> {
+    cost_cache_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|raw| parse_persisted_overviews(&raw))
+        .unwrap_or_default()
+}
+
+fn serialize_overviews(cache: &HashMap<String, CachedOverview>) -> Result<Vec<u8>, String> {
+    let entries: HashMap<&String, &CostOverview> = cache
+        .iter()
+        .map(|(key, cached)| (key, &cached.overview))
+        .collect();
+    serde_json::to_vec(&entries).map_err(|err| err.to_string())
+}
+
+/// Write through a temporary file so a crash mid-write never leaves a
+/// truncated cache behind.
+fn persist_overviews(json: &[u8]) {
+    let Some(path) = cost_cache_path() else {
+        return;
+    };
+    let result = (|| -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json).map_err(|err| err.to_string())?;
+        fs::rename(&tmp, &path).map_err(|err| err.to_string())
+    })();
+    if let Err(error) = result {
+        eprintln!("[Cost] failed to persist cost cache: {error}");
+    }
+}
+
 impl CostRangeSummary {
-    fn from_summary(range: &str, label: &str, summary: CostSummary) -> Self {
-        Self {
+    fn from_summary(range: &str, label: &str, summary: CostSummary, prices: &PriceTable) -> Self {
+        let mut summary = Self {
             range: range.to_string(),
             label: label.to_string(),
             since: summary.since.map(|date| date.to_string()),
@@ -217,10 +1069,48 @@ impl CostRangeSummary {
                 .into_iter()
                 .map(CostModelSummary::from)
                 .collect(),
+            projects: Vec::new(),
+            tags: Vec::new(),
+            sources: Vec::new(),
             valid_entries: summary.valid_entries,
             skipped_entries: summary.skipped_entries,
             elapsed_ms: summary.elapsed_ms,
+            comparison: None,
+        };
+        summary.reprice(prices);
+        summary
+    }
+
+    /// Price models the [`PriceTable`] knows (or the user overrides) the same
+    /// way every other view does. Models it does not know, such as Cursor's
+    /// `auto`, keep the `ccstats` price, so no entry changes from priced to
+    /// unpriced. Summaries are always requested in USD.
+    fn reprice(&mut self, prices: &PriceTable) {
+        for model in &mut self.models {
+            if let Some(found) = prices.resolve(&model.model) {
+                let cost = found.pricing.cost_usd(&model.tokens);
+                model.cost = Some(cost);
+                model.cost_usd = Some(cost);
+                model.price_guessed = found.guessed;
+            }
         }
+        if !self.models.is_empty() {
+            let total = self.models.iter().filter_map(|model| model.cost_usd).sum();
+            self.cost = Some(total);
+            self.cost_usd = Some(total);
+        }
+        self.currency = "USD".to_string();
+    }
+}
+
+impl CostTokenBreakdown {
+    pub fn accumulate(&mut self, other: &CostTokenBreakdown) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.total_tokens += other.total_tokens;
     }
 }
 
@@ -244,6 +1134,157 @@ impl From<ModelCostSummary> for CostModelSummary {
             cost: model.cost,
             cost_usd: model.cost_usd,
             tokens: CostTokenBreakdown::from(model.tokens),
+            price_guessed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(model: &str, cost: f64) -> CostModelSummary {
+        CostModelSummary {
+            model: model.to_string(),
+            cost: Some(cost),
+            cost_usd: Some(cost),
+            tokens: CostTokenBreakdown::default(),
+            price_guessed: false,
         }
     }
+
+    fn range(cost: f64, models: Vec<CostModelSummary>) -> CostRangeSummary {
+        CostRangeSummary {
+            range: "week".to_string(),
+            label: "This Week".to_string(),
+            since: Some("2026-05-11".to_string()),
+            until: Some("2026-05-14".to_string()),
+            currency: "USD".to_string(),
+            cost: Some(cost),
+            cost_usd: Some(cost),
+            tokens: CostTokenBreakdown::default(),
+            models,
+            projects: Vec::new(),
+            tags: Vec::new(),
+            sources: Vec::new(),
+            valid_entries: 0,
+            skipped_entries: 0,
+            elapsed_ms: 0.0,
+            comparison: None,
+        }
+    }
+
+    #[test]
+    fn compares_ranges_per_model() {
+        let current = range(30.0, vec![model("opus", 25.0), model("sonnet", 5.0)]);
+        let previous = range(20.0, vec![model("sonnet", 15.0), model("haiku", 5.0)]);
+        let comparison = compare_ranges(&current, previous);
+        assert_eq!(comparison.delta_cost, Some(10.0));
+        assert_eq!(comparison.delta_percent, Some(50.0));
+        let deltas: Vec<(&str, Option<f64>)> = comparison
+            .models
+            .iter()
+            .map(|m| (m.model.as_str(), m.delta_cost))
+            .collect();
+        assert_eq!(
+            deltas,
+            vec![
+                ("opus", Some(25.0)),
+                ("sonnet", Some(-10.0)),
+                ("haiku", Some(-5.0))
+            ]
+        );
+        assert_eq!(comparison.models[0].delta_percent, None);
+    }
+
+    #[test]
+    fn rolls_projects_up_by_tag() {
+        let tokens = |input: i64| CostTokenBreakdown {
+            input_tokens: input,
+            total_tokens: input,
+            ..Default::default()
+        };
+        let mut by_project = HashMap::new();
+        by_project.insert(
+            (
+                "/clients/acme/api".to_string(),
+                "claude-sonnet-4-5".to_string(),
+            ),
+            tokens(1_000_000),
+        );
+        by_project.insert(
+            (
+                "/clients/acme/web".to_string(),
+                "claude-sonnet-4-5".to_string(),
+            ),
+            tokens(2_000_000),
+        );
+        by_project.insert(
+            ("/oss/tool".to_string(), "mystery-model".to_string()),
+            tokens(500),
+        );
+        let tagger = ProjectTagger::new(&[crate::services::config::ProjectTagConfig {
+            path: "/clients/acme".to_string(),
+            tag: "client:acme".to_string(),
+        }]);
+
+        let (projects, tags) = summarize_projects(by_project, &tagger, &PriceTable::default());
+        assert_eq!(projects[0].project, "/clients/acme/web");
+        assert_eq!(projects[0].cost, Some(6.0));
+        assert_eq!(projects[2].tag, None);
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].cost, Some(9.0));
+        assert_eq!(
+            tags[0].projects,
+            vec!["/clients/acme/api", "/clients/acme/web"]
+        );
+    }
+
+    #[test]
+    fn reprices_known_models_and_keeps_ccstats_prices_for_the_rest() {
+        let tokens = CostTokenBreakdown {
+            input_tokens: 1_000_000,
+            total_tokens: 1_000_000,
+            ..Default::default()
+        };
+        let mut summary = range(
+            10.0,
+            vec![
+                CostModelSummary {
+                    tokens: tokens.clone(),
+                    ..model("claude-sonnet-4-5", 4.0)
+                },
+                CostModelSummary {
+                    tokens,
+                    ..model("auto", 6.0)
+                },
+            ],
+        );
+        summary.reprice(&PriceTable::default());
+        assert_eq!(summary.models[0].cost_usd, Some(3.0));
+        assert_eq!(summary.models[1].cost_usd, Some(6.0));
+        assert_eq!(summary.cost_usd, Some(9.0));
+        assert_eq!(summary.cost, Some(9.0));
+    }
+
+    #[test]
+    fn restores_persisted_overviews_and_skips_broken_entries() {
+        let raw = r#"{
+            "claude|USD|local": {
+                "source": "claude",
+                "displayName": "Claude Code",
+                "currency": "USD",
+                "generatedAt": "2026-05-14T08:00:00+00:00",
+                "cached": false,
+                "ranges": []
+            },
+            "codex|USD|local": { "source": "codex" }
+        }"#;
+        let restored = parse_persisted_overviews(raw);
+        assert_eq!(restored.len(), 1);
+        let cached = &restored["claude|USD|local"];
+        assert!(cached.inserted_at.is_none());
+        assert_eq!(cached.overview.generated_at, "2026-05-14T08:00:00+00:00");
+        assert!(parse_persisted_overviews("not json").is_empty());
+    }
 }
@@ -0,0 +1,379 @@
+//! The `all` cost source: Claude Code, Codex and Cursor overviews computed in
+//! parallel and merged into one overview with per-source subtotals.
+//!
+//! Every part is computed in USD and the caller converts the merged result
+//! once, so sources priced by different code paths are never mixed across
+//! currencies.
+
+use crate::services::cost::{
+    self, compare_ranges, CostModelSummary, CostOverview, CostProjectSummary, CostRangeSummary,
+    CostSourceSubtotal, CostTagSummary, CostTokenBreakdown,
+};
+use crate::services::cost_range::{CostRangeRequest, BILLING_CYCLE_RANGE};
+use chrono::Utc;
+use std::collections::HashMap;
+
+pub const ALL_COST_SOURCE: &str = "all";
+
+/// `(source, display name)` of every local source, in display order.
+pub(crate) const PARTS: [(&str, &str); 3] = [
+    ("claude", "Claude Code"),
+    ("codex", "Codex"),
+    ("cursor", "Cursor"),
+];
+
+pub fn is_all_source(source: &str) -> bool {
+    source.trim().eq_ignore_ascii_case(ALL_COST_SOURCE)
+}
+
+fn add_cost(into: &mut Option<f64>, value: Option<f64>) {
+    if let Some(value) = value {
+        *into = Some(into.unwrap_or(0.0) + value);
+    }
+}
+
+fn sort_by_cost<T>(items: &mut [T], cost: impl Fn(&T) -> Option<f64>, name: impl Fn(&T) -> &str) {
+    items.sort_by(|a, b| {
+        cost(b)
+            .unwrap_or(0.0)
+            .total_cmp(&cost(a).unwrap_or(0.0))
+            .then_with(|| name(a).cmp(name(b)))
+    });
+}
+
+fn empty_range(template: &CostRangeSummary) -> CostRangeSummary {
+    CostRangeSummary {
+        range: template.range.clone(),
+        label: template.label.clone(),
+        since: template.since.clone(),
+        until: template.until.clone(),
+        currency: "USD".to_string(),
+        cost: None,
+        cost_usd: None,
+        tokens: CostTokenBreakdown::default(),
+        models: Vec::new(),
+        projects: Vec::new(),
+        tags: Vec::new(),
+        sources: Vec::new(),
+        valid_entries: 0,
+        skipped_entries: 0,
+        elapsed_ms: 0.0,
+        comparison: None,
+    }
+}
+
+/// The previous period of a part, rebuilt from its comparison so merged
+/// comparisons can be recomputed over the combined model list.
+fn previous_range(range: &CostRangeSummary) -> Option<CostRangeSummary> {
+    let comparison = range.comparison.as_ref()?;
+    let mut previous = empty_range(range);
+    previous.since = Some(comparison.since.clone());
+    previous.until = Some(comparison.until.clone());
+    previous.cost = comparison.cost;
+    previous.cost_usd = comparison.cost_usd;
+    previous.tokens = comparison.tokens.clone();
+    previous.models = comparison
+        .models
+        .iter()
+        .filter(|model| model.previous_cost.is_some() || model.previous_total_tokens > 0)
+        .map(|model| CostModelSummary {
+            model: model.model.clone(),
+            cost: model.previous_cost,
+            cost_usd: model.previous_cost,
+            tokens: CostTokenBreakdown {
+                total_tokens: model.previous_total_tokens,
+                ..Default::default()
+            },
+            price_guessed: false,
+        })
+        .collect();
+    Some(previous)
+}
+
+fn merge_range_into(merged: &mut CostRangeSummary, part: &CostRangeSummary) {
+    add_cost(&mut merged.cost, part.cost);
+    add_cost(&mut merged.cost_usd, part.cost_usd);
+    merged.tokens.accumulate(&part.tokens);
+    merged.valid_entries += part.valid_entries;
+    merged.skipped_entries += part.skipped_entries;
+    merged.elapsed_ms = merged.elapsed_ms.max(part.elapsed_ms);
+
+    for model in &part.models {
+        match merged.models.iter_mut().find(|m| m.model == model.model) {
+            Some(existing) => {
+                add_cost(&mut existing.cost, model.cost);
+                add_cost(&mut existing.cost_usd, model.cost_usd);
+                existing.tokens.accumulate(&model.tokens);
+                existing.price_guessed |= model.price_guessed;
+            }
+            None => merged.models.push(model.clone()),
+        }
+    }
+    for project in &part.projects {
+        match merged
+            .projects
+            .iter_mut()
+            .find(|p| p.project == project.project)
+        {
+            Some(existing) => {
+                add_cost(&mut existing.cost, project.cost);
+                add_cost(&mut existing.cost_usd, project.cost_usd);
+                existing.tokens.accumulate(&project.tokens);
+            }
+            None => merged.projects.push(project.clone()),
+        }
+    }
+    for tag in &part.tags {
+        match merged.tags.iter_mut().find(|t| t.tag == tag.tag) {
+            Some(existing) => {
+                add_cost(&mut existing.cost, tag.cost);
+                add_cost(&mut existing.cost_usd, tag.cost_usd);
+                existing.tokens.accumulate(&tag.tokens);
+                for project in &tag.projects {
+                    if !existing.projects.contains(project) {
+                        existing.projects.push(project.clone());
+                    }
+                }
+            }
+            None => merged.tags.push(tag.clone()),
+        }
+    }
+}
+
+/// Merge part overviews (already in USD). Failed parts are listed as
+/// subtotals with their error in every range instead of failing the whole
+/// overview.
+pub(crate) fn merge_overviews(
+    parts: Vec<(&str, &str, Result<CostOverview, String>)>,
+) -> CostOverview {
+    let mut ranges: Vec<CostRangeSummary> = Vec::new();
+    let mut previous: HashMap<String, CostRangeSummary> = HashMap::new();
+    let mut failures: Vec<CostSourceSubtotal> = Vec::new();
+    let mut truncated = false;
+
+    for (source, display_name, result) in parts {
+        let overview = match result {
+            Ok(overview) => overview,
+            Err(error) => {
+                failures.push(CostSourceSubtotal {
+                    source: source.to_string(),
+                    display_name: display_name.to_string(),
+                    cost: None,
+                    cost_usd: None,
+                    tokens: CostTokenBreakdown::default(),
+                    error: Some(error),
+                });
+                continue;
+            }
+        };
+        truncated |= overview.truncated;
+        // Billing cycles start on different days per source, so they are not
+        // summed into one range.
+        for part in overview
+            .ranges
+            .iter()
+            .filter(|part| part.range != BILLING_CYCLE_RANGE)
+        {
+            let index = match ranges.iter().position(|r| r.range == part.range) {
+                Some(index) => index,
+                None => {
+                    ranges.push(empty_range(part));
+                    ranges.len() - 1
+                }
+            };
+            let merged = &mut ranges[index];
+            merge_range_into(merged, part);
+            merged.sources.push(CostSourceSubtotal {
+                source: overview.source.clone(),
+                display_name: overview.display_name.clone(),
+                cost: part.cost,
+                cost_usd: part.cost_usd,
+                tokens: part.tokens.clone(),
+                error: None,
+            });
+            if let Some(part_previous) = previous_range(part) {
+                let entry = previous
+                    .entry(part.range.clone())
+                    .or_insert_with(|| empty_range(&part_previous));
+                merge_range_into(entry, &part_previous);
+            }
+        }
+    }
+
+    for range in &mut ranges {
+        range.sources.extend(failures.iter().cloned());
+        sort_by_cost(
+            &mut range.models,
+            |m: &CostModelSummary| m.cost,
+            |m| &m.model,
+        );
+        sort_by_cost(
+            &mut range.projects,
+            |p: &CostProjectSummary| p.cost,
+            |p| &p.project,
+        );
+        sort_by_cost(&mut range.tags, |t: &CostTagSummary| t.cost, |t| &t.tag);
+        if let Some(previous) = previous.remove(&range.range) {
+            range.comparison = Some(compare_ranges(range, previous));
+        }
+    }
+
+    CostOverview {
+        source: ALL_COST_SOURCE.to_string(),
+        display_name: "All Sources".to_string(),
+        currency: "USD".to_string(),
+        generated_at: Utc::now().to_rfc3339(),
+        cached: false,
+        unpriced_models: cost::unpriced_models(&ranges),
+        ranges,
+        budgets: Vec::new(),
+        exchange_rate: None,
+        truncated,
+        view_key: None,
+    }
+}
+
+/// Build every part on its own thread inside the caller's blocking task.
+/// With a custom `range`, Cursor is reported as unavailable because its
+/// summaries only cover the calendar ranges.
+pub(crate) fn build_combined_overview(
+    timezone: Option<String>,
+    range: Option<CostRangeRequest>,
+    force: bool,
+) -> Result<CostOverview, String> {
+    let parts = std::thread::scope(|scope| {
+        let handles: Vec<_> = PARTS
+            .iter()
+            .map(|&(source, display_name)| {
+                let timezone = timezone.clone();
+                let range = range.clone();
+                let handle = scope.spawn(move || match range {
+                    Some(range) => cost::build_ranged_overview(
+                        source.to_string(),
+                        None,
+                        timezone,
+                        range,
+                        force,
+                    ),
+                    None => cost::build_cost_overview(source.to_string(), None, timezone, force),
+                });
+                (source, display_name, handle)
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|(source, display_name, handle)| {
+                let result = handle
+                    .join()
+                    .unwrap_or_else(|_| Err(format!("{display_name} cost task panicked")));
+                (source, display_name, result)
+            })
+            .collect::<Vec<_>>()
+    });
+    Ok(merge_overviews(parts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::exchange::{ExchangeRate, RATE_ORIGIN_BUILTIN};
+
+    fn range(source_cost: f64, model: &str) -> CostRangeSummary {
+        let mut range = empty_template();
+        range.range = "today".to_string();
+        range.label = "Today".to_string();
+        range.cost = Some(source_cost);
+        range.cost_usd = Some(source_cost);
+        range.valid_entries = 1;
+        range.models.push(CostModelSummary {
+            model: model.to_string(),
+            cost: Some(source_cost),
+            cost_usd: Some(source_cost),
+            tokens: CostTokenBreakdown::default(),
+            price_guessed: false,
+        });
+        range
+    }
+
+    fn empty_template() -> CostRangeSummary {
+        CostRangeSummary {
+            range: String::new(),
+            label: String::new(),
+            since: None,
+            until: None,
+            currency: "USD".to_string(),
+            cost: None,
+            cost_usd: None,
+            tokens: CostTokenBreakdown::default(),
+            models: Vec::new(),
+            projects: Vec::new(),
+            tags: Vec::new(),
+            sources: Vec::new(),
+            valid_entries: 0,
+            skipped_entries: 0,
+            elapsed_ms: 0.0,
+            comparison: None,
+        }
+    }
+
+    fn overview(source: &str, range: CostRangeSummary) -> CostOverview {
+        CostOverview {
+            source: source.to_string(),
+            display_name: source.to_string(),
+            currency: "USD".to_string(),
+            generated_at: String::new(),
+            cached: false,
+            ranges: vec![range],
+            budgets: Vec::new(),
+            unpriced_models: Vec::new(),
+            exchange_rate: None,
+            truncated: false,
+            view_key: None,
+        }
+    }
+
+    #[test]
+    fn merges_sources_with_subtotals_and_failures() {
+        let merged = merge_overviews(vec![
+            (
+                "claude",
+                "Claude Code",
+                Ok(overview("claude", range(4.0, "claude-sonnet-4-5"))),
+            ),
+            ("codex", "Codex", Ok(overview("codex", range(6.0, "gpt-5")))),
+            ("cursor", "Cursor", Err("no Cursor logs".to_string())),
+        ]);
+        assert_eq!(merged.source, ALL_COST_SOURCE);
+        let today = &merged.ranges[0];
+        assert_eq!(today.cost, Some(10.0));
+        assert_eq!(today.valid_entries, 2);
+        assert_eq!(today.models[0].model, "gpt-5");
+        let subtotals: Vec<(&str, Option<f64>, bool)> = today
+            .sources
+            .iter()
+            .map(|s| (s.source.as_str(), s.cost, s.error.is_some()))
+            .collect();
+        assert_eq!(
+            subtotals,
+            vec![
+                ("claude", Some(4.0), false),
+                ("codex", Some(6.0), false),
+                ("cursor", None, true)
+            ]
+        );
+
+        // An explicit rate, so the test never reads the user's rate file.
+        let rate = ExchangeRate {
+            currency: "EUR".to_string(),
+            rate: 0.5,
+            date: "2026-10-01".to_string(),
+            origin: RATE_ORIGIN_BUILTIN.to_string(),
+        };
+        let converted = cost::apply_rate(merged, rate);
+        let today = &converted.ranges[0];
+        assert_eq!(converted.currency, "EUR");
+        assert_eq!(today.cost_usd, Some(10.0));
+        assert_eq!(today.cost, Some(5.0));
+        assert_eq!(today.sources[1].cost, Some(3.0));
+    }
+}
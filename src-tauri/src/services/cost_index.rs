@@ -0,0 +1,849 @@
+//! Long-lived, incrementally updated token index for Claude Code and Codex logs.
+//!
+//! Every log file is read from the byte offset where the previous pass stopped
+//! and its entries are folded into hourly buckets keyed by model, project and
+//! session. Ranges, series and session listings are answered from those
+//! buckets, so a refresh only costs a few `stat` calls plus the newly appended
+//! lines. Prices are applied at query time with the shared pricing table, so
+//! pricing changes need no rescan.
+//!
+//! Directory listings are cached and re-read only when a directory's mtime
+//! changes, and only files written in the last couple of hours are checked on
+//! every pass; everything is re-checked every few minutes. Each file keeps its
+//! own buckets: a file that shrinks or is replaced is re-read on its own, and
+//! the buckets of a deleted file (Claude Code prunes old transcripts) are kept
+//! until they age out. Buckets and de-duplication keys older than
+//! [`RETENTION_DAYS`] are dropped.
+//!
+//! Cursor keeps its usage in a SQLite state database rather than append-only
+//! logs, so it cannot be indexed by offset; the watcher only marks its cached
+//! overview stale when that database changes.
+
+use crate::services::claude_stats::{claude_projects_dirs, parse_entry_timestamp, project_key};
+use crate::services::claude_usage::parse_usage_entry;
+use crate::services::codex::{codex_sessions_dir, is_rollout_file};
+use crate::services::cost::{self, CostTokenBreakdown};
+use crate::services::cursor;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+const HOUR_SECS: i64 = 3600;
+/// How often the watcher checks the log directories.
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(10);
+/// How often every known file and directory is re-checked, not only the
+/// recently active ones.
+const FULL_SCAN_INTERVAL: Duration = Duration::from_secs(300);
+/// Files modified this recently are checked on every pass.
+const ACTIVE_WINDOW: Duration = Duration::from_secs(2 * 3600);
+/// The longest range (366 days) plus its comparison period.
+const RETENTION_DAYS: i64 = 2 * 366;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexedSource {
+    Claude,
+    Codex,
+}
+
+impl IndexedSource {
+    pub const ALL: [IndexedSource; 2] = [IndexedSource::Claude, IndexedSource::Codex];
+
+    pub fn from_name(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "claude" => Some(Self::Claude),
+            "codex" => Some(Self::Codex),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Claude => "claude",
+            Self::Codex => "codex",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BucketKey {
+    /// Hours since the Unix epoch (UTC).
+    pub hour: i64,
+    pub model: String,
+    pub project: String,
+    pub session_id: String,
+}
+
+impl BucketKey {
+    pub fn start(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.hour * HOUR_SECS, 0).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BucketTotals {
+    pub tokens: CostTokenBreakdown,
+    pub entries: i64,
+    pub first_at: Option<DateTime<Utc>>,
+    pub last_at: Option<DateTime<Utc>>,
+}
+
+impl BucketTotals {
+    fn add(&mut self, timestamp: DateTime<Utc>, tokens: &CostTokenBreakdown) {
+        self.tokens.accumulate(tokens);
+        self.entries += 1;
+        self.first_at = Some(
+            self.first_at
+                .map_or(timestamp, |first| first.min(timestamp)),
+        );
+        self.last_at = Some(self.last_at.map_or(timestamp, |last| last.max(timestamp)));
+    }
+
+    fn merge(&mut self, other: &BucketTotals) {
+        self.tokens.accumulate(&other.tokens);
+        self.entries += other.entries;
+        self.first_at = match (self.first_at, other.first_at) {
+            (Some(first), Some(other)) => Some(first.min(other)),
+            (first, other) => first.or(other),
+        };
+        self.last_at = self.last_at.max(other.last_at);
+    }
+}
+
+/// Per-rollout context: Codex records the model and working directory in
+/// `turn_context`/`session_meta` lines ahead of the `token_count` events.
+#[derive(Debug, Clone, Default)]
+struct CodexFileContext {
+    session_id: String,
+    project: String,
+    model: String,
+    last_total_tokens: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct IndexedFile {
+    id: u64,
+    offset: u64,
+    file_size: u64,
+    modified_at: Option<SystemTime>,
+    project: String,
+    codex: CodexFileContext,
+    /// What this file contributed, so it can be re-read or retired alone.
+    buckets: HashMap<BucketKey, BucketTotals>,
+}
+
+impl IndexedFile {
+    fn is_active(&self, now: SystemTime) -> bool {
+        self.modified_at
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_none_or(|age| age < ACTIVE_WINDOW)
+    }
+}
+
+/// A directory's files and subdirectories as of its `modified_at`.
+#[derive(Debug, Clone, Default)]
+struct DirListing {
+    modified_at: Option<SystemTime>,
+    files: Vec<PathBuf>,
+    dirs: Vec<PathBuf>,
+}
+
+/// Where a Claude `message.id:requestId` key was first counted.
+#[derive(Debug, Clone, Copy)]
+struct SeenKey {
+    file: u64,
+    hour: i64,
+}
+
+#[derive(Default)]
+struct SourceIndex {
+    files: HashMap<PathBuf, IndexedFile>,
+    /// Buckets of files that were deleted, kept until they age out.
+    retired: HashMap<BucketKey, BucketTotals>,
+    /// Hashes of Claude `message.id:requestId` keys already counted.
+    seen: HashMap<u64, SeenKey>,
+    dirs: HashMap<PathBuf, DirListing>,
+    next_file_id: u64,
+    refreshed_at: Option<Instant>,
+    full_scan_at: Option<Instant>,
+    /// Retention cutoff hour the buckets were last pruned to.
+    pruned_to: i64,
+}
+
+impl SourceIndex {
+    fn buckets(&self) -> impl Iterator<Item = (&BucketKey, &BucketTotals)> {
+        self.files
+            .values()
+            .flat_map(|file| &file.buckets)
+            .chain(&self.retired)
+    }
+
+    fn new_file(&mut self, project: String) -> IndexedFile {
+        self.next_file_id += 1;
+        IndexedFile {
+            id: self.next_file_id,
+            offset: 0,
+            file_size: 0,
+            modified_at: None,
+            project,
+            codex: CodexFileContext::default(),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Keep the buckets of a deleted file; its de-duplication keys stay too,
+    /// so copies of its messages in other files are still skipped.
+    fn retire(&mut self, path: &Path) {
+        let Some(file) = self.files.remove(path) else {
+            return;
+        };
+        for (key, totals) in file.buckets {
+            self.retired.entry(key).or_default().merge(&totals);
+        }
+    }
+
+    /// Drop everything `file` contributed so it can be read from the start.
+    fn forget(&mut self, file: &mut IndexedFile) {
+        self.seen.retain(|_, seen| seen.file != file.id);
+        file.buckets.clear();
+        file.offset = 0;
+        file.codex = CodexFileContext::default();
+    }
+
+    fn prune(&mut self, cutoff_hour: i64) {
+        if cutoff_hour <= self.pruned_to {
+            return;
+        }
+        self.pruned_to = cutoff_hour;
+        let keep = |key: &BucketKey, _: &mut BucketTotals| key.hour >= cutoff_hour;
+        self.retired.retain(keep);
+        for file in self.files.values_mut() {
+            file.buckets.retain(keep);
+        }
+        self.seen.retain(|_, seen| seen.hour >= cutoff_hour);
+    }
+}
+
+/// Folds one file's lines into its buckets.
+struct FileSink<'a> {
+    file: u64,
+    cutoff_hour: i64,
+    seen: &'a mut HashMap<u64, SeenKey>,
+    buckets: &'a mut HashMap<BucketKey, BucketTotals>,
+}
+
+impl FileSink<'_> {
+    fn record(&mut self, timestamp: DateTime<Utc>, key: BucketKey, tokens: &CostTokenBreakdown) {
+        debug_assert_eq!(key.hour, hour_of(timestamp));
+        self.buckets.entry(key).or_default().add(timestamp, tokens);
+    }
+
+    /// `false` when the key was already counted, here or in another file.
+    fn first_sighting(&mut self, dedupe_key: &str, hour: i64) -> bool {
+        match self.seen.entry(dedupe_hash(dedupe_key)) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(SeenKey {
+                    file: self.file,
+                    hour,
+                });
+                true
+            }
+        }
+    }
+}
+
+static COST_INDEX: OnceLock<Mutex<HashMap<IndexedSource, SourceIndex>>> = OnceLock::new();
+
+fn cost_index() -> &'static Mutex<HashMap<IndexedSource, SourceIndex>> {
+    COST_INDEX.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn hour_of(timestamp: DateTime<Utc>) -> i64 {
+    timestamp.timestamp().div_euclid(HOUR_SECS)
+}
+
+fn retention_cutoff_hour() -> i64 {
+    hour_of(Utc::now() - ChronoDuration::days(RETENTION_DAYS))
+}
+
+fn dedupe_hash(key: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn token_count(value: &serde_json::Value) -> i64 {
+    value.as_i64().unwrap_or(0).max(0)
+}
+
+/// Feed complete lines to `handle` and return how many bytes were consumed.
+/// A trailing line without a newline is still being written and is left for
+/// the next pass.
+fn read_complete_lines<R: BufRead>(mut reader: R, mut handle: impl FnMut(&[u8])) -> u64 {
+    let mut consumed = 0u64;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => {
+                if line.last() != Some(&b'\n') {
+                    break;
+                }
+                consumed += read as u64;
+                handle(&line);
+            }
+        }
+    }
+    consumed
+}
+
+fn ingest_claude_line(sink: &mut FileSink, line: &[u8], project: &str, session: &str) {
+    // Cheap pre-filter: only assistant lines with usage are interesting.
+    if !line.windows(7).any(|window| window == b"\"usage\"") {
+        return;
+    }
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(line) else {
+        return;
+    };
+    let Some((dedupe_key, entry)) = parse_usage_entry(&value, project) else {
+        return;
+    };
+    let hour = hour_of(entry.timestamp);
+    if hour < sink.cutoff_hour {
+        return;
+    }
+    if let Some(dedupe_key) = dedupe_key {
+        if !sink.first_sighting(&dedupe_key, hour) {
+            return;
+        }
+    }
+    let session_id = if entry.session_id.is_empty() {
+        session.to_string()
+    } else {
+        entry.session_id
+    };
+    let key = BucketKey {
+        hour,
+        model: entry.model,
+        project: entry.project,
+        session_id,
+    };
+    sink.record(entry.timestamp, key, &entry.tokens);
+}
+
+/// Codex reports `input_tokens` inclusive of `cached_input_tokens` and
+/// `output_tokens` inclusive of reasoning; split them the way the breakdown
+/// expects.
+fn codex_usage_tokens(usage: &serde_json::Value) -> CostTokenBreakdown {
+    let input = token_count(&usage["input_tokens"]);
+    let cached = token_count(&usage["cached_input_tokens"]).min(input);
+    let output = token_count(&usage["output_tokens"]);
+    CostTokenBreakdown {
+        input_tokens: input - cached,
+        output_tokens: output,
+        reasoning_tokens: token_count(&usage["reasoning_output_tokens"]),
+        cache_creation_tokens: 0,
+        cache_read_tokens: cached,
+        total_tokens: input + output,
+    }
+}
+
+fn ingest_codex_line(sink: &mut FileSink, line: &[u8], context: &mut CodexFileContext) {
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(line) else {
+        return;
+    };
+    let payload = &value["payload"];
+    match value["type"].as_str() {
+        Some("session_meta") => {
+            if let Some(id) = payload["id"].as_str() {
+                context.session_id = id.to_string();
+            }
+            if let Some(cwd) = payload["cwd"].as_str().filter(|cwd| !cwd.is_empty()) {
+                context.project = cwd.to_string();
+            }
+        }
+        Some("turn_context") => {
+            if let Some(model) = payload["model"].as_str().filter(|model| !model.is_empty()) {
+                context.model = model.to_string();
+            }
+            if let Some(cwd) = payload["cwd"].as_str().filter(|cwd| !cwd.is_empty()) {
+                context.project = cwd.to_string();
+            }
+        }
+        Some("event_msg") if payload["type"].as_str() == Some("token_count") => {
+            let info = &payload["info"];
+            let usage = &info["last_token_usage"];
+            if !usage.is_object() {
+                return;
+            }
+            // Codex repeats the last token_count when nothing new was spent
+            // (e.g. a rate-limit refresh); the running total gives it away.
+            let running_total = info["total_token_usage"]["total_tokens"].as_i64();
+            if running_total.is_some() && running_total == context.last_total_tokens {
+                return;
+            }
+            context.last_total_tokens = running_total;
+
+            let Some(timestamp) = parse_entry_timestamp(&value) else {
+                return;
+            };
+            let hour = hour_of(timestamp);
+            if hour < sink.cutoff_hour {
+                return;
+            }
+            let tokens = codex_usage_tokens(usage);
+            if tokens.total_tokens == 0 {
+                return;
+            }
+            let model = if context.model.is_empty() {
+                "unknown".to_string()
+            } else {
+                context.model.clone()
+            };
+            let key = BucketKey {
+                hour,
+                model,
+                project: context.project.clone(),
+                session_id: context.session_id.clone(),
+            };
+            sink.record(timestamp, key, &tokens);
+        }
+        _ => {}
+    }
+}
+
+fn is_claude_log(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "jsonl")
+}
+
+fn read_listing(
+    dir: &Path,
+    modified_at: Option<SystemTime>,
+    keep: fn(&Path) -> bool,
+) -> DirListing {
+    let mut listing = DirListing {
+        modified_at,
+        ..Default::default()
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return listing;
+    };
+    for entry in entries.flatten() {
+        // Symlinks are skipped to avoid loops.
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            listing.dirs.push(path);
+        } else if file_type.is_file() && keep(&path) {
+            listing.files.push(path);
+        }
+    }
+    listing
+}
+
+/// Recursively collect the files under `dir` accepted by `keep`. A directory
+/// is only read again when its mtime changed, or on a `full` pass.
+fn walk(
+    dir: &Path,
+    listings: &mut HashMap<PathBuf, DirListing>,
+    visited: &mut HashSet<PathBuf>,
+    full: bool,
+    keep: fn(&Path) -> bool,
+    out: &mut Vec<PathBuf>,
+) {
+    let Ok(modified_at) = fs::metadata(dir).map(|metadata| metadata.modified().ok()) else {
+        return;
+    };
+    visited.insert(dir.to_path_buf());
+    let unchanged = !full
+        && modified_at.is_some()
+        && listings
+            .get(dir)
+            .is_some_and(|listing| listing.modified_at == modified_at);
+    if !unchanged {
+        listings.insert(dir.to_path_buf(), read_listing(dir, modified_at, keep));
+    }
+    let listing = &listings[dir];
+    out.extend(listing.files.iter().cloned());
+    let subdirs = listing.dirs.clone();
+    for subdir in subdirs {
+        walk(&subdir, listings, visited, full, keep, out);
+    }
+}
+
+fn list_source_files(
+    source: IndexedSource,
+    listings: &mut HashMap<PathBuf, DirListing>,
+    full: bool,
+) -> Vec<(PathBuf, String)> {
+    let mut visited = HashSet::new();
+    let mut out = Vec::new();
+    match source {
+        IndexedSource::Claude => {
+            for root in claude_projects_dirs() {
+                let mut paths = Vec::new();
+                walk(
+                    &root,
+                    listings,
+                    &mut visited,
+                    full,
+                    is_claude_log,
+                    &mut paths,
+                );
+                out.extend(paths.into_iter().map(|path| {
+                    let project = project_key(&root, &path);
+                    (path, project)
+                }));
+            }
+        }
+        IndexedSource::Codex => {
+            if let Some(dir) = codex_sessions_dir() {
+                let mut paths = Vec::new();
+                walk(
+                    &dir,
+                    listings,
+                    &mut visited,
+                    full,
+                    is_rollout_file,
+                    &mut paths,
+                );
+                out.extend(paths.into_iter().map(|path| (path, String::new())));
+            }
+        }
+    }
+    listings.retain(|dir, _| visited.contains(dir));
+    out
+}
+
+fn session_from_path(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Read whatever was appended to `path` since `state.offset`. Returns `false`
+/// when the file could not be opened; it is retried on the next pass.
+fn ingest_file(
+    source: IndexedSource,
+    seen: &mut HashMap<u64, SeenKey>,
+    path: &Path,
+    state: &mut IndexedFile,
+    cutoff_hour: i64,
+) -> bool {
+    let Ok(mut file) = fs::File::open(path) else {
+        return false;
+    };
+    if file.seek(SeekFrom::Start(state.offset)).is_err() {
+        return false;
+    }
+    let session = session_from_path(path);
+    if source == IndexedSource::Codex && state.codex.session_id.is_empty() {
+        state.codex.session_id = session.clone();
+    }
+    let mut sink = FileSink {
+        file: state.id,
+        cutoff_hour,
+        seen,
+        buckets: &mut state.buckets,
+    };
+    let consumed = match source {
+        IndexedSource::Claude => {
+            let project = &state.project;
+            read_complete_lines(BufReader::new(file), |line| {
+                ingest_claude_line(&mut sink, line, project, &session)
+            })
+        }
+        IndexedSource::Codex => {
+            let context = &mut state.codex;
+            read_complete_lines(BufReader::new(file), |line| {
+                ingest_codex_line(&mut sink, line, context)
+            })
+        }
+    };
+    state.offset += consumed;
+    true
+}
+
+/// Bring `index` up to date with `files`. Known files that went quiet are
+/// only checked on a `full` pass. Returns `true` when anything was ingested.
+fn sync_files(
+    source: IndexedSource,
+    index: &mut SourceIndex,
+    files: Vec<(PathBuf, String)>,
+    full: bool,
+) -> bool {
+    let present: HashSet<&PathBuf> = files.iter().map(|(path, _)| path).collect();
+    let vanished: Vec<PathBuf> = index
+        .files
+        .keys()
+        .filter(|path| !present.contains(path))
+        .cloned()
+        .collect();
+    for path in vanished {
+        index.retire(&path);
+    }
+
+    let now = SystemTime::now();
+    let cutoff_hour = retention_cutoff_hour();
+    let mut changed = false;
+    for (path, project) in files {
+        if !full
+            && index
+                .files
+                .get(&path)
+                .is_some_and(|file| !file.is_active(now))
+        {
+            continue;
+        }
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        let file_size = metadata.len();
+        let modified_at = metadata.modified().ok();
+        let mut state = match index.files.remove(&path) {
+            Some(state) if state.file_size == file_size && state.modified_at == modified_at => {
+                index.files.insert(path, state);
+                continue;
+            }
+            Some(mut state) => {
+                let went_back = matches!(
+                    (modified_at, state.modified_at),
+                    (Some(now), Some(before)) if now < before
+                );
+                if file_size < state.offset || went_back {
+                    index.forget(&mut state);
+                    changed = true;
+                }
+                state
+            }
+            None => index.new_file(project),
+        };
+        let offset = state.offset;
+        if ingest_file(source, &mut index.seen, &path, &mut state, cutoff_hour) {
+            state.file_size = file_size;
+            state.modified_at = modified_at;
+        }
+        changed |= state.offset != offset;
+        index.files.insert(path, state);
+    }
+    index.prune(cutoff_hour);
+    changed
+}
+
+/// One pass over `source`; every few minutes it re-checks all files.
+fn refresh_source(source: IndexedSource, index: &mut SourceIndex) {
+    let full = index
+        .full_scan_at
+        .is_none_or(|at| at.elapsed() >= FULL_SCAN_INTERVAL);
+    let files = list_source_files(source, &mut index.dirs, full);
+    let changed = sync_files(source, index, files, full);
+    index.refreshed_at = Some(Instant::now());
+    if full {
+        index.full_scan_at = index.refreshed_at;
+    }
+    if changed {
+        cost::mark_stale(source.as_str());
+    }
+}
+
+/// Bring `source` up to date; cached overviews are marked stale when new data
+/// was ingested.
+pub fn refresh(source: IndexedSource) {
+    if let Ok(mut guard) = cost_index().lock() {
+        refresh_source(source, guard.entry(source).or_default());
+    }
+}
+
+/// Visit every bucket of `source` whose hour overlaps `[since, until)`. The
+/// index is refreshed first when the watcher has not done so recently or when
+/// `force` is set. A key may be visited once per file that contributed to it.
+pub fn for_each_bucket(
+    source: IndexedSource,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    force: bool,
+    mut visit: impl FnMut(&BucketKey, &BucketTotals),
+) -> Result<(), String> {
+    let mut guard = cost_index().lock().map_err(|err| err.to_string())?;
+    let index = guard.entry(source).or_default();
+    let stale = index
+        .refreshed_at
+        .is_none_or(|at| at.elapsed() >= WATCH_INTERVAL);
+    if force || stale {
+        refresh_source(source, index);
+    }
+
+    let first_hour = hour_of(since);
+    let last_hour = hour_of(until - ChronoDuration::nanoseconds(1));
+    for (key, totals) in index.buckets() {
+        if key.hour >= first_hour && key.hour <= last_hour {
+            visit(key, totals);
+        }
+    }
+    Ok(())
+}
+
+fn cursor_state_modified() -> Option<SystemTime> {
+    let path = cursor::state_vscdb_path()?;
+    // Writes usually land in the write-ahead log first.
+    let wal = path.with_extension("vscdb-wal");
+    [path, wal]
+        .iter()
+        .filter_map(|path| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .max()
+}
+
+/// Poll the Claude and Codex log directories and ingest appended lines in the
+/// background so cost queries are answered from an up-to-date index, and
+/// watch Cursor's state database for changes.
+pub fn start_cost_index_watcher() {
+    let spawned = thread::Builder::new()
+        .name("cost-index".to_string())
+        .spawn(|| {
+            let mut cursor_modified = cursor_state_modified();
+            loop {
+                for source in IndexedSource::ALL {
+                    refresh(source);
+                }
+                let modified = cursor_state_modified();
+                if modified != cursor_modified {
+                    cursor_modified = modified;
+                    cost::mark_stale("cursor");
+                }
+                thread::sleep(WATCH_INTERVAL);
+            }
+        });
+    if let Err(error) = spawned {
+        eprintln!("[CostIndex] failed to start the watcher: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn ingest(source: IndexedSource, lines: &str) -> HashMap<BucketKey, BucketTotals> {
+        let mut seen = HashMap::new();
+        let mut buckets = HashMap::new();
+        let mut sink = FileSink {
+            file: 1,
+            cutoff_hour: i64::MIN,
+            seen: &mut seen,
+            buckets: &mut buckets,
+        };
+        let mut context = CodexFileContext::default();
+        read_complete_lines(Cursor::new(lines), |line| match source {
+            IndexedSource::Claude => ingest_claude_line(&mut sink, line, "fallback", "file"),
+            IndexedSource::Codex => ingest_codex_line(&mut sink, line, &mut context),
+        });
+        buckets
+    }
+
+    fn codex_usage_line(at: DateTime<Utc>, total: i64) -> String {
+        format!(
+            r#"{{"timestamp":"{}","type":"event_msg","payload":{{"type":"token_count","info":{{"total_token_usage":{{"total_tokens":{total}}},"last_token_usage":{{"input_tokens":100,"output_tokens":10}}}}}}}}"#,
+            at.to_rfc3339()
+        ) + "\n"
+    }
+
+    fn total_entries(index: &SourceIndex) -> i64 {
+        index.buckets().map(|(_, totals)| totals.entries).sum()
+    }
+
+    #[test]
+    fn syncs_appends_rewrites_and_deleted_files() {
+        let dir = std::env::temp_dir().join(format!("quotabar-cost-index-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rollout-test.jsonl");
+        let files = || vec![(path.clone(), String::new())];
+        let now = Utc::now();
+        let mut index = SourceIndex::default();
+
+        std::fs::write(&path, codex_usage_line(now, 110)).unwrap();
+        assert!(sync_files(IndexedSource::Codex, &mut index, files(), false));
+        assert_eq!(total_entries(&index), 1);
+
+        let mut appended = codex_usage_line(now, 110);
+        appended.push_str(&codex_usage_line(now, 220));
+        std::fs::write(&path, &appended).unwrap();
+        assert!(sync_files(IndexedSource::Codex, &mut index, files(), false));
+        assert_eq!(total_entries(&index), 2);
+        assert!(!sync_files(IndexedSource::Codex, &mut index, files(), true));
+
+        // A shorter file is re-read on its own instead of being added twice.
+        std::fs::write(&path, codex_usage_line(now, 330)).unwrap();
+        assert!(sync_files(IndexedSource::Codex, &mut index, files(), false));
+        assert_eq!(total_entries(&index), 1);
+
+        // Deleted files keep their buckets; old buckets age out.
+        std::fs::remove_file(&path).unwrap();
+        sync_files(IndexedSource::Codex, &mut index, Vec::new(), false);
+        assert!(index.files.is_empty());
+        assert_eq!(total_entries(&index), 1);
+        index.prune(hour_of(now) + 1);
+        assert_eq!(total_entries(&index), 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn buckets_claude_usage_once_per_message() {
+        let line = r#"{"type":"assistant","sessionId":"s1","requestId":"r1","cwd":"/work/app","timestamp":"2026-05-01T09:15:00Z","message":{"id":"m1","model":"claude-sonnet-4-5","usage":{"input_tokens":100,"output_tokens":20}}}"#;
+        let buckets = ingest(IndexedSource::Claude, &format!("{line}\n{line}\n"));
+        assert_eq!(buckets.len(), 1);
+        let (key, totals) = buckets.iter().next().unwrap();
+        assert_eq!(key.project, "/work/app");
+        assert_eq!(key.session_id, "s1");
+        assert_eq!(key.start().to_rfc3339(), "2026-05-01T09:00:00+00:00");
+        assert_eq!(totals.entries, 1);
+        assert_eq!(totals.tokens.total_tokens, 120);
+    }
+
+    #[test]
+    fn splits_codex_cached_input_and_skips_repeated_totals() {
+        let lines = concat!(
+            r#"{"timestamp":"2026-05-01T09:00:00Z","type":"session_meta","payload":{"id":"sess-1","cwd":"/work/api"}}"#,
+            "\n",
+            r#"{"timestamp":"2026-05-01T09:00:01Z","type":"turn_context","payload":{"model":"gpt-5-codex","cwd":"/work/api"}}"#,
+            "\n",
+            r#"{"timestamp":"2026-05-01T09:01:00Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"total_tokens":1500},"last_token_usage":{"input_tokens":1200,"cached_input_tokens":1000,"output_tokens":300,"reasoning_output_tokens":100}}}}"#,
+            "\n",
+            r#"{"timestamp":"2026-05-01T09:01:05Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"total_tokens":1500},"last_token_usage":{"input_tokens":1200,"cached_input_tokens":1000,"output_tokens":300,"reasoning_output_tokens":100}}}}"#,
+            "\n",
+            r#"{"timestamp":"2026-05-01T09:02:00Z","type":"event_msg","payload":{"type":"token_count","info":null,"rate_limits":{}}}"#,
+            "\n",
+        );
+        let buckets = ingest(IndexedSource::Codex, lines);
+        assert_eq!(buckets.len(), 1);
+        let (key, totals) = buckets.iter().next().unwrap();
+        assert_eq!(key.model, "gpt-5-codex");
+        assert_eq!(key.project, "/work/api");
+        assert_eq!(key.session_id, "sess-1");
+        assert_eq!(totals.entries, 1);
+        assert_eq!(totals.tokens.input_tokens, 200);
+        assert_eq!(totals.tokens.cache_read_tokens, 1000);
+        assert_eq!(totals.tokens.output_tokens, 300);
+        assert_eq!(totals.tokens.total_tokens, 1500);
+    }
+
+    #[test]
+    fn leaves_partial_line_for_next_pass() {
+        let consumed = read_complete_lines(Cursor::new("{\"a\":1}\n{\"b\""), |_| {});
+        assert_eq!(consumed, 8);
+    }
+}
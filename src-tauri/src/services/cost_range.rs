@@ -0,0 +1,405 @@
+//! Date ranges for indexed cost summaries: calendar presets, rolling windows,
+//! custom `since`/`until` spans and the period each one is compared against.
+
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, FixedOffset, Local, Months, NaiveDate,
+    TimeZone, Utc,
+};
+use serde::Deserialize;
+
+/// Key of the range covering the current billing cycle of a source.
+pub const BILLING_CYCLE_RANGE: &str = "billingCycle";
+
+/// Optional `range` argument of `get_cost_overview`. Without a preset or
+/// `since` the default Today / This Week / This Month ranges are returned.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostRangeRequest {
+    /// `today`, `week`, `month`, `last7Days`, `last30Days`, `previousWeek`,
+    /// `previousMonth` or `custom`.
+    pub preset: Option<String>,
+    /// Inclusive `YYYY-MM-DD` bounds for `custom` ranges.
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Also summarize the preceding period and report deltas.
+    #[serde(default)]
+    pub compare: bool,
+}
+
+impl CostRangeRequest {
+    /// The default ranges without comparison, which are cached and persisted.
+    pub fn is_default(&self) -> bool {
+        !self.compare && self.preset.is_none() && self.since.is_none() && self.until.is_none()
+    }
+}
+
+/// How the comparison period is derived from a range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PeriodShift {
+    /// The same number of days immediately before.
+    Days,
+    /// The same days of the previous calendar month.
+    Month,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostPeriod {
+    pub key: String,
+    pub label: String,
+    /// Inclusive first and last day.
+    pub since: NaiveDate,
+    pub until: NaiveDate,
+    shift: PeriodShift,
+}
+
+impl CostPeriod {
+    fn new(key: &str, label: &str, since: NaiveDate, until: NaiveDate, shift: PeriodShift) -> Self {
+        Self {
+            key: key.to_string(),
+            label: label.to_string(),
+            since,
+            until,
+            shift,
+        }
+    }
+
+    /// The period this one is compared against: "this week so far" is
+    /// compared with the same weekdays of last week, "this month so far" with
+    /// the same days of last month.
+    pub fn previous(&self) -> (NaiveDate, NaiveDate) {
+        match self.shift {
+            PeriodShift::Days => {
+                let length = (self.until - self.since).num_days() + 1;
+                (
+                    self.since - ChronoDuration::days(length),
+                    self.until - ChronoDuration::days(length),
+                )
+            }
+            PeriodShift::Month => {
+                let since = self
+                    .since
+                    .checked_sub_months(Months::new(1))
+                    .unwrap_or(self.since);
+                let until = if is_last_day_of_month(self.until) {
+                    self.until.with_day(1).unwrap_or(self.until) - ChronoDuration::days(1)
+                } else {
+                    self.until
+                        .checked_sub_months(Months::new(1))
+                        .unwrap_or(self.until)
+                };
+                (since, until)
+            }
+        }
+    }
+}
+
+fn is_last_day_of_month(date: NaiveDate) -> bool {
+    date.succ_opt()
+        .is_none_or(|next| next.month() != date.month())
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - ChronoDuration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+    month_start(date)
+        .checked_add_months(Months::new(1))
+        .map(|next| next - ChronoDuration::days(1))
+        .unwrap_or(date)
+}
+
+fn parse_day(value: &str, field: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid {field} date '{value}', expected YYYY-MM-DD"))
+}
+
+/// Calendar ranges ending today. Weeks start on Monday.
+pub fn default_periods(today: NaiveDate) -> Vec<CostPeriod> {
+    vec![
+        CostPeriod::new("today", "Today", today, today, PeriodShift::Days),
+        CostPeriod::new(
+            "week",
+            "This Week",
+            week_start(today),
+            today,
+            PeriodShift::Days,
+        ),
+        CostPeriod::new(
+            "month",
+            "This Month",
+            month_start(today),
+            today,
+            PeriodShift::Month,
+        ),
+    ]
+}
+
+/// The billing cycle containing `today` that renews on `anchor_day` of each
+/// month. Months shorter than the anchor renew on their last day.
+pub fn billing_cycle_period(today: NaiveDate, anchor_day: u32) -> CostPeriod {
+    let renewal_in = |month: NaiveDate| {
+        let day = anchor_day.clamp(1, last_day_of_month(month).day());
+        month.with_day(day).unwrap_or(month)
+    };
+    let this_month = month_start(today);
+    let mut since = renewal_in(this_month);
+    if since > today {
+        let previous = this_month
+            .checked_sub_months(Months::new(1))
+            .unwrap_or(this_month);
+        since = renewal_in(previous);
+    }
+    CostPeriod::new(
+        BILLING_CYCLE_RANGE,
+        "Billing Cycle",
+        since,
+        today,
+        PeriodShift::Month,
+    )
+}
+
+/// Resolve a request against `today` in the caller's timezone.
+pub fn resolve_periods(
+    request: &CostRangeRequest,
+    today: NaiveDate,
+) -> Result<Vec<CostPeriod>, String> {
+    let preset = match (request.preset.as_deref(), request.since.as_deref()) {
+        (Some(preset), _) => preset.trim(),
+        (None, Some(_)) => "custom",
+        (None, None) => return Ok(default_periods(today)),
+    };
+
+    let period = match preset {
+        "today" => CostPeriod::new("today", "Today", today, today, PeriodShift::Days),
+        "week" => CostPeriod::new(
+            "week",
+            "This Week",
+            week_start(today),
+            today,
+            PeriodShift::Days,
+        ),
+        "month" => CostPeriod::new(
+            "month",
+            "This Month",
+            month_start(today),
+            today,
+            PeriodShift::Month,
+        ),
+        "last7Days" => CostPeriod::new(
+            "last7Days",
+            "Last 7 Days",
+            today - ChronoDuration::days(6),
+            today,
+            PeriodShift::Days,
+        ),
+        "last30Days" => CostPeriod::new(
+            "last30Days",
+            "Last 30 Days",
+            today - ChronoDuration::days(29),
+            today,
+            PeriodShift::Days,
+        ),
+        "previousWeek" => {
+            let since = week_start(today) - ChronoDuration::days(7);
+            CostPeriod::new(
+                "previousWeek",
+                "Previous Week",
+                since,
+                since + ChronoDuration::days(6),
+                PeriodShift::Days,
+            )
+        }
+        "previousMonth" => {
+            let until = month_start(today) - ChronoDuration::days(1);
+            CostPeriod::new(
+                "previousMonth",
+                "Previous Month",
+                month_start(until),
+                until,
+                PeriodShift::Month,
+            )
+        }
+        "custom" => {
+            let since = request
+                .since
+                .as_deref()
+                .ok_or("Custom ranges need a since date")?;
+            let since = parse_day(since, "since")?;
+            let until = match request.until.as_deref() {
+                Some(until) => parse_day(until, "until")?,
+                None => today,
+            };
+            if until < since {
+                return Err(format!("Range ends ({until}) before it starts ({since})"));
+            }
+            CostPeriod::new(
+                "custom",
+                &format!("{since} – {until}"),
+                since,
+                until,
+                PeriodShift::Days,
+            )
+        }
+        other => return Err(format!("Unknown cost range preset '{other}'")),
+    };
+    Ok(vec![period])
+}
+
+/// Timezones the index can bucket by: the system zone, UTC, or a fixed
+/// `±HH:MM` offset. Named IANA zones fall back to `ccstats`.
+///
+/// Buckets are hourly, so zones with a half-hour offset see day boundaries
+/// rounded down to the hour.
+pub(crate) enum RangeTimezone {
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl RangeTimezone {
+    pub(crate) fn parse(value: Option<&str>) -> Option<Self> {
+        let value = value.map(str::trim).unwrap_or("");
+        if value.is_empty() || value.eq_ignore_ascii_case("local") {
+            return Some(Self::Local);
+        }
+        if value.eq_ignore_ascii_case("utc") || value == "Z" {
+            return FixedOffset::east_opt(0).map(Self::Fixed);
+        }
+        value.parse::<FixedOffset>().ok().map(Self::Fixed)
+    }
+
+    pub(crate) fn date_of(&self, instant: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Self::Local => instant.with_timezone(&Local).date_naive(),
+            Self::Fixed(offset) => instant.with_timezone(offset).date_naive(),
+        }
+    }
+
+    /// First instant of `date` in this zone.
+    pub(crate) fn start_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        match self {
+            // A DST gap can swallow midnight; the day then starts an hour later.
+            Self::Local => Local
+                .from_local_datetime(&midnight)
+                .earliest()
+                .or_else(|| {
+                    Local
+                        .from_local_datetime(&(midnight + ChronoDuration::hours(1)))
+                        .earliest()
+                })
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|| midnight.and_utc()),
+            Self::Fixed(offset) => offset
+                .from_local_datetime(&midnight)
+                .single()
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|| midnight.and_utc()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn default_periods_start_on_monday_and_first_of_month() {
+        // 2026-05-14 is a Thursday.
+        let periods = default_periods(day("2026-05-14"));
+        assert_eq!(periods[0].since, day("2026-05-14"));
+        assert_eq!(periods[1].since, day("2026-05-11"));
+        assert_eq!(periods[2].since, day("2026-05-01"));
+    }
+
+    #[test]
+    fn compares_against_matching_previous_periods() {
+        let today = day("2026-03-31");
+        let request = |preset: &str| CostRangeRequest {
+            preset: Some(preset.to_string()),
+            ..Default::default()
+        };
+
+        let month = &resolve_periods(&request("month"), today).unwrap()[0];
+        assert_eq!(month.previous(), (day("2026-02-01"), day("2026-02-28")));
+
+        let previous_month = &resolve_periods(&request("previousMonth"), today).unwrap()[0];
+        assert_eq!(
+            (previous_month.since, previous_month.until),
+            (day("2026-02-01"), day("2026-02-28"))
+        );
+        assert_eq!(
+            previous_month.previous(),
+            (day("2026-01-01"), day("2026-01-31"))
+        );
+
+        let last7 = &resolve_periods(&request("last7Days"), today).unwrap()[0];
+        assert_eq!(last7.previous(), (day("2026-03-18"), day("2026-03-24")));
+    }
+
+    #[test]
+    fn billing_cycles_renew_on_the_anchor_day() {
+        let cycle = billing_cycle_period(day("2026-05-14"), 20);
+        assert_eq!(
+            (cycle.since, cycle.until),
+            (day("2026-04-20"), day("2026-05-14"))
+        );
+        assert_eq!(
+            billing_cycle_period(day("2026-05-20"), 20).since,
+            day("2026-05-20")
+        );
+        // February has no 31st, so the cycle renews on its last day.
+        assert_eq!(
+            billing_cycle_period(day("2026-03-05"), 31).since,
+            day("2026-02-28")
+        );
+        assert_eq!(
+            billing_cycle_period(day("2026-03-31"), 31).since,
+            day("2026-03-31")
+        );
+    }
+
+    #[test]
+    fn validates_custom_ranges() {
+        let today = day("2026-05-14");
+        let custom = CostRangeRequest {
+            since: Some("2026-05-01".to_string()),
+            until: Some("2026-05-10".to_string()),
+            ..Default::default()
+        };
+        let period = &resolve_periods(&custom, today).unwrap()[0];
+        assert_eq!(period.key, "custom");
+        assert_eq!(period.previous(), (day("2026-04-21"), day("2026-04-30")));
+
+        let reversed = CostRangeRequest {
+            since: Some("2026-05-10".to_string()),
+            until: Some("2026-05-01".to_string()),
+            ..Default::default()
+        };
+        assert!(resolve_periods(&reversed, today).is_err());
+        let unknown = CostRangeRequest {
+            preset: Some("fortnight".to_string()),
+            ..Default::default()
+        };
+        assert!(resolve_periods(&unknown, today).is_err());
+    }
+
+    #[test]
+    fn parses_fixed_offset_timezones() {
+        let zone = RangeTimezone::parse(Some("+02:00")).expect("fixed offset");
+        assert_eq!(
+            zone.start_of_day(day("2026-05-14")).to_rfc3339(),
+            "2026-05-13T22:00:00+00:00"
+        );
+        assert!(RangeTimezone::parse(Some("UTC")).is_some());
+        assert!(RangeTimezone::parse(Some("Europe/Berlin")).is_none());
+    }
+}
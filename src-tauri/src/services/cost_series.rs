@@ -0,0 +1,310 @@
+//! Hourly and daily cost series from the incremental cost index, for charts
+//! and daily exports.
+
+use crate::services::cost::{CostModelSummary, CostTokenBreakdown};
+use crate::services::cost_index::{self, IndexedSource};
+use crate::services::cost_range::RangeTimezone;
+use crate::services::pricing::PriceTable;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Upper bound on buckets per request (about three months of hours).
+const MAX_SERIES_POINTS: i64 = 2400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesBucket {
+    Hour,
+    Day,
+}
+
+impl SeriesBucket {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(str::trim).unwrap_or("day") {
+            "hour" => Ok(Self::Hour),
+            "day" | "" => Ok(Self::Day),
+            other => Err(format!("Unknown bucket '{other}', expected hour or day")),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostSeries {
+    pub source: String,
+    pub bucket: String,
+    pub currency: String,
+    pub since: String,
+    pub until: String,
+    pub generated_at: String,
+    pub cost: f64,
+    pub tokens: CostTokenBreakdown,
+    pub points: Vec<CostSeriesPoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostSeriesPoint {
+    /// Bucket start (RFC 3339, UTC). Empty buckets are included so charts stay
+    /// evenly spaced.
+    pub start: String,
+    pub end: String,
+    /// Local day of daily buckets.
+    pub date: Option<String>,
+    pub cost: f64,
+    pub tokens: CostTokenBreakdown,
+    pub entries: i64,
+    pub models: Vec<CostModelSummary>,
+}
+
+/// Accepts `YYYY-MM-DD` (start of that day, or end of it for `until`) or an
+/// RFC 3339 instant.
+pub(crate) fn parse_bound(
+    value: &str,
+    zone: &RangeTimezone,
+    end_of_day: bool,
+) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Ok(instant) = DateTime::parse_from_rfc3339(value) {
+        return Ok(instant.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{value}', expected YYYY-MM-DD or RFC 3339"))?;
+    Ok(if end_of_day {
+        zone.start_of_day(date + ChronoDuration::days(1))
+    } else {
+        zone.start_of_day(date)
+    })
+}
+
+struct Slot {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    date: Option<NaiveDate>,
+}
+
+fn bucket_slots(
+    bucket: SeriesBucket,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    zone: &RangeTimezone,
+) -> Vec<Slot> {
+    let mut slots = Vec::new();
+    match bucket {
+        SeriesBucket::Hour => {
+            let mut hour = since.timestamp().div_euclid(3600);
+            let last = (until - ChronoDuration::nanoseconds(1))
+                .timestamp()
+                .div_euclid(3600);
+            while hour <= last {
+                let start = DateTime::from_timestamp(hour * 3600, 0).unwrap_or_default();
+                slots.push(Slot {
+                    start,
+                    end: start + ChronoDuration::hours(1),
+                    date: None,
+                });
+                hour += 1;
+            }
+        }
+        SeriesBucket::Day => {
+            let mut day = zone.date_of(since);
+            let last = zone.date_of(until - ChronoDuration::nanoseconds(1));
+            while day <= last {
+                let next = day + ChronoDuration::days(1);
+                slots.push(Slot {
+                    start: zone.start_of_day(day),
+                    end: zone.start_of_day(next),
+                    date: Some(day),
+                });
+                day = next;
+            }
+        }
+    }
+    slots
+}
+
+fn estimated_points(bucket: SeriesBucket, since: DateTime<Utc>, until: DateTime<Utc>) -> i64 {
+    let span = until - since;
+    match bucket {
+        SeriesBucket::Hour => span.num_hours() + 1,
+        SeriesBucket::Day => span.num_days() + 1,
+    }
+}
+
+pub fn build_cost_series(
+    source: &str,
+    since: &str,
+    until: Option<&str>,
+    bucket: Option<&str>,
+    timezone: Option<&str>,
+    force: bool,
+) -> Result<CostSeries, String> {
+    let indexed = IndexedSource::from_name(source).ok_or_else(|| {
+        format!("Cost series are only available for Claude Code and Codex, not '{source}'")
+    })?;
+    let bucket = SeriesBucket::parse(bucket)?;
+    let zone = RangeTimezone::parse(timezone).ok_or_else(|| {
+        format!(
+            "Unsupported timezone '{}'; use local, UTC or an offset like +02:00",
+            timezone.unwrap_or_default()
+        )
+    })?;
+    let now = Utc::now();
+    let since = parse_bound(since, &zone, false)?;
+    let until = match until {
+        Some(until) if !until.trim().is_empty() => parse_bound(until, &zone, true)?,
+        _ => now,
+    };
+    if until <= since {
+        return Err("Series end must be after its start".to_string());
+    }
+    if estimated_points(bucket, since, until) > MAX_SERIES_POINTS {
+        return Err(format!(
+            "Range is too long for {} buckets (max {MAX_SERIES_POINTS})",
+            bucket.as_str()
+        ));
+    }
+
+    let slots = bucket_slots(bucket, since, until, &zone);
+    let Some(first) = slots.first() else {
+        return Err("Series range is empty".to_string());
+    };
+    let query_start = first.start;
+    let query_end = slots.last().map_or(until, |slot| slot.end);
+
+    // slot index -> model -> (tokens, entries)
+    let mut per_slot: BTreeMap<usize, HashMap<String, (CostTokenBreakdown, i64)>> = BTreeMap::new();
+    cost_index::for_each_bucket(indexed, query_start, query_end, force, |key, totals| {
+        // Hourly buckets straddling a half-hour day boundary land in the slot
+        // where they start: the first slot ending after the bucket's start.
+        let at = key.start();
+        let slot = slots.partition_point(|slot| slot.end <= at);
+        if slot >= slots.len() {
+            return;
+        }
+        let (tokens, entries) = per_slot
+            .entry(slot)
+            .or_default()
+            .entry(key.model.clone())
+            .or_default();
+        tokens.accumulate(&totals.tokens);
+        *entries += totals.entries;
+    })?;
+
+    let prices = PriceTable::load();
+    let mut total_cost = 0.0;
+    let mut total_tokens = CostTokenBreakdown::default();
+    let points = slots
+        .iter()
+        .enumerate()
+        .map(|(index, slot)| {
+            let mut point = CostSeriesPoint {
+                start: slot.start.to_rfc3339(),
+                end: slot.end.to_rfc3339(),
+                date: slot.date.map(|date| date.to_string()),
+                cost: 0.0,
+                tokens: CostTokenBreakdown::default(),
+                entries: 0,
+                models: Vec::new(),
+            };
+            for (model, (tokens, entries)) in per_slot.remove(&index).unwrap_or_default() {
+                let price = prices.resolve(&model);
+                let cost = price.map(|found| found.pricing.cost_usd(&tokens));
+                point.cost += cost.unwrap_or(0.0);
+                point.tokens.accumulate(&tokens);
+                point.entries += entries;
+                point.models.push(CostModelSummary {
+                    model,
+                    cost,
+                    cost_usd: cost,
+                    tokens,
+                    price_guessed: price.is_some_and(|found| found.guessed),
+                });
+            }
+            point.models.sort_by(|a, b| {
+                b.cost_usd
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.cost_usd.unwrap_or(0.0))
+                    .then_with(|| a.model.cmp(&b.model))
+            });
+            total_cost += point.cost;
+            total_tokens.accumulate(&point.tokens);
+            point
+        })
+        .collect();
+
+    Ok(CostSeries {
+        source: indexed.as_str().to_string(),
+        bucket: bucket.as_str().to_string(),
+        currency: "USD".to_string(),
+        since: since.to_rfc3339(),
+        until: until.to_rfc3339(),
+        generated_at: now.to_rfc3339(),
+        cost: total_cost,
+        tokens: total_tokens,
+        points,
+    })
+}
+
+pub async fn get_cost_series(
+    source: String,
+    since: String,
+    until: Option<String>,
+    bucket: Option<String>,
+    timezone: Option<String>,
+    force: bool,
+) -> Result<CostSeries, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        build_cost_series(
+            &source,
+            &since,
+            until.as_deref(),
+            bucket.as_deref(),
+            timezone.as_deref(),
+            force,
+        )
+    })
+    .await
+    .map_err(|err| format!("Cost series task failed: {err}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daily_slots_follow_the_zone_and_include_both_ends() {
+        let zone = RangeTimezone::parse(Some("+02:00")).unwrap();
+        let since = parse_bound("2026-05-01", &zone, false).unwrap();
+        let until = parse_bound("2026-05-03", &zone, true).unwrap();
+        let slots = bucket_slots(SeriesBucket::Day, since, until, &zone);
+        assert_eq!(slots.len(), 3);
+        assert_eq!(slots[0].start.to_rfc3339(), "2026-04-30T22:00:00+00:00");
+        assert_eq!(slots[2].date.map(|d| d.to_string()).unwrap(), "2026-05-03");
+    }
+
+    #[test]
+    fn hourly_slots_cover_partial_hours() {
+        let zone = RangeTimezone::parse(Some("UTC")).unwrap();
+        let since = parse_bound("2026-05-01T09:30:00Z", &zone, false).unwrap();
+        let until = parse_bound("2026-05-01T12:00:00Z", &zone, true).unwrap();
+        let slots = bucket_slots(SeriesBucket::Hour, since, until, &zone);
+        let starts: Vec<String> = slots.iter().map(|s| s.start.to_rfc3339()).collect();
+        assert_eq!(
+            starts,
+            vec![
+                "2026-05-01T09:00:00+00:00",
+                "2026-05-01T10:00:00+00:00",
+                "2026-05-01T11:00:00+00:00"
+            ]
+        );
+        assert!(SeriesBucket::parse(Some("week")).is_err());
+    }
+}
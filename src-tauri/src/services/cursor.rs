@@ -27,7 +27,7 @@ fn cursor_cache() -> &'static Mutex<Option<CachedCursor>> {
     CURSOR_CACHE.get_or_init(|| Mutex::new(None))
 }
 
-fn state_vscdb_path() -> Option<PathBuf> {
+pub(crate) fn state_vscdb_path() -> Option<PathBuf> {
     dirs::home_dir()
         .map(|home| home.join("Library/Application Support/Cursor/User/globalStorage/state.vscdb"))
 }
@@ -134,6 +134,11 @@ fn get_stale_cached_cursor() -> Option<CursorData> {
     }
 }
 
+/// `startOfMonth` (or `resetAt`) of the last successful usage response.
+pub(crate) fn last_known_cycle_start() -> Option<String> {
+    get_stale_cached_cursor().and_then(|data| data.reset_at)
+}
+
 fn fallback_or_disconnected(error: impl Into<String>) -> CursorData {
     let error = error.into();
     if is_transient_os_error(&error) {
@@ -0,0 +1,310 @@
+//! Custom providers declared in the user config: command plugins
+//! (`plugins`) and declarative HTTP providers (`httpProviders`).
+//!
+//! A background poller runs each enabled provider on its own interval, caches
+//! the result, records it in the quota history and pushes the percentage into
+//! a dedicated tray. A notification is shown when a provider crosses
+//! [`NOTIFY_PERCENT`]. The `get_custom_providers` command serves that cache
+//! and only re-runs providers that are due, or all of them when forced.
+
+use crate::domain::models::{CustomProviderData, CustomProviderWindow};
+use crate::services::config::{self, HttpProviderConfig, PluginConfig};
+use crate::services::http::is_transient_os_error;
+use crate::services::{history, http_provider, notification, plugin, tray};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const POLL_TICK: Duration = Duration::from_secs(5);
+const MIN_INTERVAL_SECS: u64 = 10;
+/// Provider percentage at which a notification is shown, once per crossing.
+const NOTIFY_PERCENT: f64 = 90.0;
+
+struct CachedCustom {
+    data: CustomProviderData,
+    fetched_at: Instant,
+    last_good: Option<CustomProviderData>,
+}
+
+static CUSTOM_CACHE: OnceLock<Mutex<HashMap<String, CachedCustom>>> = OnceLock::new();
+
+fn custom_cache() -> &'static Mutex<HashMap<String, CachedCustom>> {
+    CUSTOM_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Clone)]
+enum ProviderSpec {
+    Command(PluginConfig),
+    Http(HttpProviderConfig),
+}
+
+impl ProviderSpec {
+    fn id(&self) -> &str {
+        match self {
+            Self::Command(plugin) => &plugin.id,
+            Self::Http(provider) => &provider.id,
+        }
+    }
+
+    fn display_name(&self) -> String {
+        match self {
+            Self::Command(plugin) => plugin.display_name(),
+            Self::Http(provider) => provider.display_name(),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Command(_) => plugin::PLUGIN_KIND,
+            Self::Http(_) => http_provider::HTTP_PROVIDER_KIND,
+        }
+    }
+
+    fn tray(&self) -> bool {
+        match self {
+            Self::Command(plugin) => plugin.tray,
+            Self::Http(provider) => provider.tray,
+        }
+    }
+
+    fn poll_interval(&self) -> Duration {
+        let secs = match self {
+            Self::Command(plugin) => plugin.interval_secs,
+            Self::Http(provider) => provider.interval_secs,
+        };
+        Duration::from_secs(secs.max(MIN_INTERVAL_SECS))
+    }
+
+    /// Blocking; call from a worker thread.
+    fn fetch(&self) -> Result<CustomProviderData, String> {
+        match self {
+            Self::Command(plugin) => plugin::run_plugin(plugin),
+            Self::Http(provider) => {
+                tauri::async_runtime::block_on(http_provider::fetch_http_provider(provider))
+            }
+        }
+    }
+}
+
+/// Window percentage as reported, or derived from `used / limit`.
+pub fn window_percentage(
+    percentage: Option<f64>,
+    used: Option<f64>,
+    limit: Option<f64>,
+) -> Option<f64> {
+    percentage
+        .or_else(|| match (used, limit) {
+            (Some(used), Some(limit)) if limit > 0.0 => Some(used / limit * 100.0),
+            _ => None,
+        })
+        .filter(|value| value.is_finite())
+        .map(|value| value.max(0.0))
+}
+
+/// The provider (and tray) value is the most exhausted window.
+pub fn provider_percentage(windows: &[CustomProviderWindow]) -> Option<f64> {
+    windows
+        .iter()
+        .filter_map(|window| window.percentage)
+        .fold(None, |acc: Option<f64>, value| {
+            Some(acc.map_or(value, |current| current.max(value)))
+        })
+}
+
+fn is_due(id: &str, interval: Duration) -> bool {
+    let Ok(cache) = custom_cache().lock() else {
+        return true;
+    };
+    cache
+        .get(id)
+        .is_none_or(|cached| cached.fetched_at.elapsed() >= interval)
+}
+
+fn get_cached(id: &str) -> Option<CustomProviderData> {
+    let cache = custom_cache().lock().ok()?;
+    cache.get(id).map(|cached| cached.data.clone())
+}
+
+fn get_last_good(id: &str) -> Option<CustomProviderData> {
+    let cache = custom_cache().lock().ok()?;
+    cache.get(id).and_then(|cached| cached.last_good.clone())
+}
+
+fn save_cached(id: &str, data: &CustomProviderData) {
+    if let Ok(mut cache) = custom_cache().lock() {
+        let last_good = if data.connected {
+            Some(data.clone())
+        } else {
+            cache.get(id).and_then(|cached| cached.last_good.clone())
+        };
+        cache.insert(
+            id.to_string(),
+            CachedCustom {
+                data: data.clone(),
+                fetched_at: Instant::now(),
+                last_good,
+            },
+        );
+    }
+}
+
+/// On transient OS errors (EMFILE / EAGAIN), return the last successful
+/// result instead of flashing the provider offline.
+fn fallback_or_disconnected(spec: &ProviderSpec, error: String) -> CustomProviderData {
+    if is_transient_os_error(&error) {
+        if let Some(stale) = get_last_good(spec.id()) {
+            return stale;
+        }
+    }
+    CustomProviderData::disconnected(spec.id(), spec.display_name(), spec.kind(), error)
+}
+
+/// Whether `current` crossed [`NOTIFY_PERCENT`] since `previous`, the last
+/// connected result, so an outage in between does not notify again.
+fn crossed_threshold(previous: Option<&CustomProviderData>, current: &CustomProviderData) -> bool {
+    let above = |data: &CustomProviderData| {
+        data.connected && data.percentage.is_some_and(|value| value >= NOTIFY_PERCENT)
+    };
+    above(current) && !previous.is_some_and(above)
+}
+
+fn notify_threshold(app: &AppHandle, spec: &ProviderSpec, data: &CustomProviderData) {
+    let window = data
+        .windows
+        .iter()
+        .filter(|window| window.percentage.is_some())
+        .max_by(|a, b| {
+            a.percentage
+                .unwrap_or(0.0)
+                .total_cmp(&b.percentage.unwrap_or(0.0))
+        });
+    let body = match window {
+        Some(window) => format!(
+            "{} is at {:.0}%",
+            window.label,
+            window.percentage.unwrap_or(0.0)
+        ),
+        None => format!("{:.0}% used", data.percentage.unwrap_or(0.0)),
+    };
+    notification::notify(
+        app,
+        &format!("{} quota running low", spec.display_name()),
+        &body,
+    );
+}
+
+/// Run the provider, then cache, record, notify and update its tray.
+fn refresh_provider(app: &AppHandle, spec: &ProviderSpec) -> CustomProviderData {
+    let previous = get_last_good(spec.id());
+    let data = match spec.fetch() {
+        Ok(data) => {
+            history::record_custom(&data);
+            data
+        }
+        Err(error) => fallback_or_disconnected(spec, error),
+    };
+    save_cached(spec.id(), &data);
+    if crossed_threshold(previous.as_ref(), &data) {
+        notify_threshold(app, spec, &data);
+    }
+    sync_tray(app, spec, &data);
+    data
+}
+
+fn tray_percentage(data: &CustomProviderData) -> Option<u8> {
+    if !data.connected {
+        return None;
+    }
+    data.percentage
+        .map(|value| value.round().clamp(0.0, 100.0) as u8)
+}
+
+fn sync_tray(app: &AppHandle, spec: &ProviderSpec, data: &CustomProviderData) {
+    if let Err(error) = tray::update_custom_tray(
+        app,
+        spec.id(),
+        &spec.display_name(),
+        tray_percentage(data),
+        spec.tray(),
+    ) {
+        eprintln!("[Custom] failed to update tray for {}: {error}", spec.id());
+    }
+}
+
+/// Enabled providers in config order. Ids must be unique across both lists
+/// because they key the cache and the tray; later duplicates are ignored.
+fn enabled_providers() -> Vec<ProviderSpec> {
+    let config = config::load_config();
+    let commands = config
+        .plugins
+        .into_iter()
+        .filter(|plugin| plugin.enabled)
+        .map(ProviderSpec::Command);
+    let http = config
+        .http_providers
+        .into_iter()
+        .filter(|provider| provider.enabled)
+        .map(ProviderSpec::Http);
+
+    let mut seen = HashSet::new();
+    commands
+        .chain(http)
+        .filter(|spec| !spec.id().trim().is_empty() && seen.insert(spec.id().to_string()))
+        .collect()
+}
+
+/// Start the background poller. Config edits are picked up on the next tick,
+/// and trays of providers that were removed or disabled are hidden.
+pub fn start_custom_provider_poller(app: AppHandle) {
+    let spawned = thread::Builder::new()
+        .name("custom-providers".to_string())
+        .spawn(move || {
+            let mut trayed: HashSet<String> = HashSet::new();
+            loop {
+                let providers = enabled_providers();
+                let active: HashSet<String> =
+                    providers.iter().map(|spec| spec.id().to_string()).collect();
+
+                for spec in &providers {
+                    if is_due(spec.id(), spec.poll_interval()) {
+                        refresh_provider(&app, spec);
+                        trayed.insert(spec.id().to_string());
+                    }
+                }
+
+                for id in trayed.difference(&active) {
+                    let _ = tray::update_custom_tray(&app, id, id, None, false);
+                }
+                trayed.retain(|id| active.contains(id));
+
+                thread::sleep(POLL_TICK);
+            }
+        });
+
+    if let Err(error) = spawned {
+        eprintln!("[Custom] failed to start provider poller: {error}");
+    }
+}
+
+pub async fn get_custom_providers(
+    app: AppHandle,
+    force: bool,
+) -> Result<Vec<CustomProviderData>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        enabled_providers()
+            .iter()
+            .map(|spec| {
+                let cached = if force {
+                    None
+                } else {
+                    get_cached(spec.id()).filter(|_| !is_due(spec.id(), spec.poll_interval()))
+                };
+                cached.unwrap_or_else(|| refresh_provider(&app, spec))
+            })
+            .collect()
+    })
+    .await
+    .map_err(|err| format!("Custom provider task failed: {err}"))
+}
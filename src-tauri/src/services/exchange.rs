@@ -0,0 +1,160 @@
+//! Offline exchange rates for showing local cost in other currencies.
+//!
+//! Rates are units of the currency per US dollar. A built-in table dated
+//! [`BUILTIN_RATE_DATE`] covers common currencies, and `exchange-rates.json`
+//! next to `config.json` adds or replaces rates without any network access:
+//!
+//! ```json
+//! { "date": "2026-10-01", "rates": { "EUR": 0.86, "GBP": 0.75 } }
+//! ```
+
+use crate::services::config;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RATES_FILE: &str = "exchange-rates.json";
+
+pub const RATE_ORIGIN_FILE: &str = "file";
+pub const RATE_ORIGIN_BUILTIN: &str = "builtin";
+
+/// Date the built-in rates were taken.
+pub const BUILTIN_RATE_DATE: &str = "2026-10-01";
+
+const BUILTIN_RATES: [(&str, f64); 12] = [
+    ("EUR", 0.86),
+    ("GBP", 0.75),
+    ("JPY", 150.0),
+    ("CNY", 7.12),
+    ("CAD", 1.39),
+    ("AUD", 1.52),
+    ("CHF", 0.80),
+    ("HKD", 7.78),
+    ("SGD", 1.29),
+    ("KRW", 1400.0),
+    ("INR", 88.5),
+    ("TWD", 30.4),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRate {
+    pub currency: String,
+    /// Units of `currency` per US dollar.
+    pub rate: f64,
+    /// `YYYY-MM-DD` the rate was taken.
+    pub date: String,
+    /// `file` or `builtin`.
+    pub origin: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RateTable {
+    #[serde(default)]
+    date: Option<String>,
+    #[serde(default)]
+    rates: HashMap<String, f64>,
+}
+
+pub fn rates_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join(RATES_FILE))
+}
+
+/// The user's rate table. Without a `date` the file's modification day is
+/// used, so every rate still carries a date stamp.
+fn load_table(path: &Path) -> Option<RateTable> {
+    let raw = fs::read_to_string(path).ok()?;
+    let mut table = match serde_json::from_str::<RateTable>(&raw) {
+        Ok(table) => table,
+        Err(error) => {
+            eprintln!("[Exchange] failed to parse {}: {error}", path.display());
+            return None;
+        }
+    };
+    if table
+        .date
+        .as_deref()
+        .is_none_or(|date| date.trim().is_empty())
+    {
+        table.date = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(|modified| {
+                DateTime::<Utc>::from(modified)
+                    .format("%Y-%m-%d")
+                    .to_string()
+            });
+    }
+    Some(table)
+}
+
+fn resolve(code: &str, table: Option<&RateTable>) -> Result<ExchangeRate, String> {
+    let code = code.trim().to_ascii_uppercase();
+    let usable = |rate: f64| rate.is_finite() && rate > 0.0;
+
+    if let Some(table) = table {
+        let rate = table
+            .rates
+            .iter()
+            .find(|(currency, rate)| currency.eq_ignore_ascii_case(&code) && usable(**rate))
+            .map(|(_, rate)| *rate);
+        if let Some(rate) = rate {
+            return Ok(ExchangeRate {
+                currency: code,
+                rate,
+                date: table.date.clone().unwrap_or_default(),
+                origin: RATE_ORIGIN_FILE.to_string(),
+            });
+        }
+    }
+    if code == "USD" {
+        return Ok(ExchangeRate {
+            currency: code,
+            rate: 1.0,
+            date: BUILTIN_RATE_DATE.to_string(),
+            origin: RATE_ORIGIN_BUILTIN.to_string(),
+        });
+    }
+    BUILTIN_RATES
+        .iter()
+        .find(|(currency, _)| *currency == code)
+        .map(|(_, rate)| ExchangeRate {
+            currency: code.clone(),
+            rate: *rate,
+            date: BUILTIN_RATE_DATE.to_string(),
+            origin: RATE_ORIGIN_BUILTIN.to_string(),
+        })
+        .ok_or_else(|| format!("No exchange rate for {code}; add it to {RATES_FILE}"))
+}
+
+/// The rate to convert USD into `code`, preferring the user's table.
+pub fn rate_for(code: &str) -> Result<ExchangeRate, String> {
+    let table = rates_path().and_then(|path| load_table(&path));
+    resolve(code, table.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_rates_win_over_builtin_rates() {
+        let table = RateTable {
+            date: Some("2026-09-30".to_string()),
+            rates: HashMap::from([("eur".to_string(), 0.9), ("GBP".to_string(), 0.0)]),
+        };
+
+        let eur = resolve("EUR", Some(&table)).unwrap();
+        assert_eq!((eur.rate, eur.date.as_str()), (0.9, "2026-09-30"));
+        assert_eq!(eur.origin, RATE_ORIGIN_FILE);
+
+        // Unusable file rates fall back to the built-in table.
+        let gbp = resolve("gbp", Some(&table)).unwrap();
+        assert_eq!(gbp.origin, RATE_ORIGIN_BUILTIN);
+        assert_eq!(gbp.date, BUILTIN_RATE_DATE);
+
+        assert!(resolve("XYZ", Some(&table)).is_err());
+    }
+}
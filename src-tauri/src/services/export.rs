@@ -0,0 +1,394 @@
+//! `export_data`: local cost summaries and quota history written as CSV or
+//! JSON Lines to a user-chosen path.
+//!
+//! Columns are fixed per dataset and always written in the same order, so
+//! downstream scripts can rely on them; JSON Lines objects use the same keys.
+//! Every row starts with [`SCHEMA_VERSION`], bumped whenever the columns
+//! change. Cost rows are one per range and model and carry the cost the
+//! overview itself shows, so exports match the app. Empty cells mean the
+//! value is unknown.
+
+use crate::services::cost::{self, CostOverview};
+use crate::services::cost_combined::{self, PARTS};
+use crate::services::cost_range::{CostRangeRequest, RangeTimezone};
+use crate::services::cost_series;
+use crate::services::history::{self, QuotaSnapshot};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Version of the column layout, written as the first column of every row.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const COST_COLUMNS: [&str; 14] = [
+    "schema_version",
+    "source",
+    "range",
+    "since",
+    "until",
+    "model",
+    "input_tokens",
+    "output_tokens",
+    "reasoning_tokens",
+    "cache_creation_tokens",
+    "cache_read_tokens",
+    "total_tokens",
+    "cost_usd",
+    "price_guessed",
+];
+
+pub const HISTORY_COLUMNS: [&str; 8] = [
+    "schema_version",
+    "recorded_at",
+    "source",
+    "window",
+    "percentage",
+    "used",
+    "limit",
+    "resets_at",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// An explicit format, or the one implied by the file extension (CSV by
+    /// default).
+    pub fn resolve(format: Option<&str>, path: &str) -> Result<Self, String> {
+        match format
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("csv") => Ok(Self::Csv),
+            Some("jsonl") | Some("ndjson") => Ok(Self::Jsonl),
+            Some(other) => Err(format!(
+                "Unknown export format '{other}', expected csv or jsonl"
+            )),
+            None => Ok(
+                match Path::new(path)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(str::to_ascii_lowercase)
+                    .as_deref()
+                {
+                    Some("jsonl") | Some("ndjson") => Self::Jsonl,
+                    _ => Self::Csv,
+                },
+            ),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRequest {
+    /// `cost` or `history`.
+    pub dataset: String,
+    /// `csv` or `jsonl`; inferred from `path` when omitted.
+    pub format: Option<String>,
+    pub path: String,
+    /// Cost: `claude`, `codex`, `cursor` or `all` (default). History:
+    /// `claude` or `codex`, all when omitted.
+    pub source: Option<String>,
+    /// Cost ranges; the default Today / This Week / This Month when omitted.
+    pub range: Option<CostRangeRequest>,
+    /// History bounds, `YYYY-MM-DD` or RFC 3339.
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub path: String,
+    pub dataset: String,
+    pub format: String,
+    pub rows: usize,
+}
+
+fn cost_rows(overview: &CostOverview) -> Vec<Vec<Value>> {
+    overview
+        .ranges
+        .iter()
+        .flat_map(|range| {
+            range.models.iter().map(move |model| {
+                let tokens = &model.tokens;
+                vec![
+                    Value::from(SCHEMA_VERSION),
+                    Value::from(overview.source.as_str()),
+                    Value::from(range.range.as_str()),
+                    Value::from(range.since.clone()),
+                    Value::from(range.until.clone()),
+                    Value::from(model.model.as_str()),
+                    Value::from(tokens.input_tokens),
+                    Value::from(tokens.output_tokens),
+                    Value::from(tokens.reasoning_tokens),
+                    Value::from(tokens.cache_creation_tokens),
+                    Value::from(tokens.cache_read_tokens),
+                    Value::from(tokens.total_tokens),
+                    Value::from(model.cost_usd),
+                    Value::from(model.price_guessed),
+                ]
+            })
+        })
+        .collect()
+}
+
+fn history_row(snapshot: QuotaSnapshot) -> Vec<Value> {
+    vec![
+        Value::from(SCHEMA_VERSION),
+        Value::from(snapshot.recorded_at),
+        Value::from(snapshot.source),
+        Value::from(snapshot.window),
+        Value::from(snapshot.percentage),
+        Value::from(snapshot.used),
+        Value::from(snapshot.limit),
+        Value::from(snapshot.resets_at),
+    ]
+}
+
+fn source_overview(
+    source: &str,
+    range: Option<&CostRangeRequest>,
+    timezone: Option<String>,
+    force: bool,
+) -> Result<CostOverview, String> {
+    match range {
+        Some(range) if !range.is_default() => {
+            cost::build_ranged_overview(source.to_string(), None, timezone, range.clone(), force)
+        }
+        _ => cost::build_cost_overview(source.to_string(), None, timezone, force),
+    }
+}
+
+fn collect_cost_rows(request: &ExportRequest, force: bool) -> Result<Vec<Vec<Value>>, String> {
+    let source = request.source.as_deref().unwrap_or("all");
+    if !cost_combined::is_all_source(source) {
+        let overview = source_overview(
+            source,
+            request.range.as_ref(),
+            request.timezone.clone(),
+            force,
+        )?;
+        return Ok(cost_rows(&overview));
+    }
+
+    // One set of rows per source, so the `source` column stays meaningful.
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for (part, display_name) in PARTS {
+        match source_overview(
+            part,
+            request.range.as_ref(),
+            request.timezone.clone(),
+            force,
+        ) {
+            Ok(overview) => rows.extend(cost_rows(&overview)),
+            Err(error) => {
+                eprintln!("[Export] skipped {display_name}: {error}");
+                errors.push(error);
+            }
+        }
+    }
+    if errors.len() == PARTS.len() {
+        return Err(errors.join("; "));
+    }
+    Ok(rows)
+}
+
+fn collect_history_rows(request: &ExportRequest) -> Result<Vec<Vec<Value>>, String> {
+    let zone = RangeTimezone::parse(request.timezone.as_deref())
+        .ok_or_else(|| "Unsupported timezone for history export".to_string())?;
+    let bound = |value: &Option<String>, end_of_day: bool| {
+        value
+            .as_deref()
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| cost_series::parse_bound(value, &zone, end_of_day))
+            .transpose()
+    };
+    let since = bound(&request.since, false)?;
+    let until = bound(&request.until, true)?;
+    let source = request
+        .source
+        .as_deref()
+        .map(str::trim)
+        .filter(|source| !source.is_empty() && !cost_combined::is_all_source(source));
+    Ok(history::load_history(since, until)?
+        .into_iter()
+        .filter(|snapshot| source.is_none_or(|source| snapshot.source == source))
+        .map(history_row)
+        .collect())
+}
+
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn write_rows<W: Write>(
+    out: &mut W,
+    format: ExportFormat,
+    columns: &[&str],
+    rows: &[Vec<Value>],
+) -> std::io::Result<()> {
+    match format {
+        ExportFormat::Csv => {
+            writeln!(out, "{}", columns.join(","))?;
+            for row in rows {
+                let fields: Vec<String> = row.iter().map(csv_field).collect();
+                writeln!(out, "{}", fields.join(","))?;
+            }
+        }
+        ExportFormat::Jsonl => {
+            // Written by hand to keep keys in column order.
+            for row in rows {
+                let fields: Vec<String> = columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, value)| format!("{}:{value}", Value::from(*column)))
+                    .collect();
+                writeln!(out, "{{{}}}", fields.join(","))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn write_export(request: &ExportRequest, force: bool) -> Result<ExportSummary, String> {
+    let path = request.path.trim();
+    if path.is_empty() {
+        return Err("An export path is required".to_string());
+    }
+    let format = ExportFormat::resolve(request.format.as_deref(), path)?;
+    let dataset = request.dataset.trim().to_ascii_lowercase();
+    let (columns, rows): (&[&str], Vec<Vec<Value>>) = match dataset.as_str() {
+        "cost" => (&COST_COLUMNS, collect_cost_rows(request, force)?),
+        "history" => (&HISTORY_COLUMNS, collect_history_rows(request)?),
+        other => {
+            return Err(format!(
+                "Unknown export dataset '{other}', expected cost or history"
+            ))
+        }
+    };
+
+    let file = fs::File::create(path).map_err(|err| format!("Failed to create {path}: {err}"))?;
+    let mut out = BufWriter::new(file);
+    write_rows(&mut out, format, columns, &rows)
+        .and_then(|_| out.flush())
+        .map_err(|err| format!("Failed to write {path}: {err}"))?;
+
+    Ok(ExportSummary {
+        path: path.to_string(),
+        dataset,
+        format: format.as_str().to_string(),
+        rows: rows.len(),
+    })
+}
+
+pub async fn export_data(request: ExportRequest) -> Result<ExportSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || write_export(&request, false))
+        .await
+        .map_err(|err| format!("Export task failed: {err}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_and_jsonl_keep_column_order() {
+        let rows = vec![vec![
+            Value::from(SCHEMA_VERSION),
+            Value::from("2026-05-01T10:00:00Z"),
+            Value::from("claude"),
+            Value::from("session, 5h"),
+            Value::from(42.5),
+            Value::Null,
+            Value::Null,
+            Value::from("say \"hi\""),
+        ]];
+
+        let mut csv = Vec::new();
+        write_rows(&mut csv, ExportFormat::Csv, &HISTORY_COLUMNS, &rows).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "schema_version,recorded_at,source,window,percentage,used,limit,resets_at\n\
+             1,2026-05-01T10:00:00Z,claude,\"session, 5h\",42.5,,,\"say \"\"hi\"\"\"\n"
+        );
+
+        let mut jsonl = Vec::new();
+        write_rows(&mut jsonl, ExportFormat::Jsonl, &HISTORY_COLUMNS, &rows).unwrap();
+        assert_eq!(
+            String::from_utf8(jsonl).unwrap(),
+            "{\"schema_version\":1,\"recorded_at\":\"2026-05-01T10:00:00Z\",\
+             \"source\":\"claude\",\"window\":\"session, 5h\",\"percentage\":42.5,\
+             \"used\":null,\"limit\":null,\"resets_at\":\"say \\\"hi\\\"\"}\n"
+        );
+    }
+
+    #[test]
+    fn cost_rows_export_the_overview_costs() {
+        let tokens = serde_json::json!({
+            "inputTokens": 1000, "outputTokens": 200, "reasoningTokens": 0,
+            "cacheCreationTokens": 0, "cacheReadTokens": 5000, "totalTokens": 6200
+        });
+        let overview: CostOverview = serde_json::from_value(serde_json::json!({
+            "source": "cursor", "displayName": "Cursor", "currency": "USD",
+            "generatedAt": "2026-05-01T10:00:00Z", "cached": false,
+            "ranges": [{
+                "range": "today", "label": "Today", "since": "2026-05-01",
+                "until": "2026-05-01", "currency": "USD", "cost": 0.42, "costUsd": 0.42,
+                "tokens": tokens, "projects": [], "validEntries": 1,
+                "skippedEntries": 0, "elapsedMs": 1.0,
+                "models": [{
+                    "model": "claude-sonnet-4-5", "cost": 0.42, "costUsd": 0.42,
+                    "tokens": tokens, "priceGuessed": true
+                }]
+            }]
+        }))
+        .unwrap();
+
+        let rows = cost_rows(&overview);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].len(), COST_COLUMNS.len());
+        assert_eq!(rows[0][0], Value::from(SCHEMA_VERSION));
+        assert_eq!(rows[0][11], Value::from(6200));
+        // The overview's own cost, not a re-priced one.
+        assert_eq!(rows[0][12], Value::from(0.42));
+        assert_eq!(rows[0][13], Value::from(true));
+    }
+
+    #[test]
+    fn format_falls_back_to_the_file_extension() {
+        assert_eq!(
+            ExportFormat::resolve(None, "/tmp/cost.jsonl").unwrap(),
+            ExportFormat::Jsonl
+        );
+        assert_eq!(
+            ExportFormat::resolve(None, "/tmp/cost.txt").unwrap(),
+            ExportFormat::Csv
+        );
+        assert!(ExportFormat::resolve(Some("xlsx"), "/tmp/cost.csv").is_err());
+    }
+}
@@ -0,0 +1,388 @@
+//! Quota history: every live Claude, Codex and custom provider quota response
+//! is appended as one snapshot per window to
+//! `<data dir>/quotabar/quota-history.jsonl`.
+//!
+//! Unchanged percentages are recorded at most every ten minutes, and records
+//! older than the retention period are dropped once the file grows large.
+//! Spending anomalies are kept next to it in `cost-anomalies.jsonl`.
+
+use crate::domain::models::{
+    CodexRateLimitWindow, CodexRateLimits, CustomProviderData, QuotaData, UsageInfo,
+};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const HISTORY_FILE: &str = "quota-history.jsonl";
+const ANOMALY_FILE: &str = "cost-anomalies.jsonl";
+const UNCHANGED_RECORD_INTERVAL: Duration = Duration::from_secs(600);
+const RETENTION_DAYS: i64 = 90;
+const COMPACT_THRESHOLD_BYTES: u64 = 4 * 1024 * 1024;
+
+/// `source|window` -> percentage and time of the last recorded snapshot.
+static LAST_RECORDED: OnceLock<Mutex<HashMap<String, (f64, Instant)>>> = OnceLock::new();
+
+fn last_recorded() -> &'static Mutex<HashMap<String, (f64, Instant)>> {
+    LAST_RECORDED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaSnapshot {
+    pub recorded_at: String,
+    /// `claude`, `codex` or `custom:<provider id>`.
+    pub source: String,
+    /// `session`, `weeklyTotal`, `weeklyOpus`, `weeklySonnet`, `weeklyDesign`
+    /// for Claude; `primary` or `secondary` for Codex; the window id, or its
+    /// label, for custom providers.
+    pub window: String,
+    pub percentage: f64,
+    pub used: Option<f64>,
+    pub limit: Option<f64>,
+    pub resets_at: Option<String>,
+}
+
+/// A spending anomaly raised by `anomaly`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostAnomaly {
+    pub detected_at: String,
+    /// `hour` or `session`.
+    pub kind: String,
+    pub source: String,
+    /// Hour start, or first activity of the session.
+    pub start: String,
+    pub end: String,
+    pub session_id: Option<String>,
+    pub cost_usd: f64,
+    /// Median cost of a baseline hour or session.
+    pub baseline_usd: f64,
+    pub ratio: f64,
+    /// Project and model that spent the most.
+    pub project: String,
+    pub model: String,
+}
+
+fn parse_instant(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+fn in_bounds(
+    at: Option<DateTime<Utc>>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> bool {
+    at.is_some_and(|at| {
+        since.is_none_or(|since| at >= since) && until.is_none_or(|until| at < until)
+    })
+}
+
+pub fn history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("quotabar").join(HISTORY_FILE))
+}
+
+pub fn anomaly_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("quotabar").join(ANOMALY_FILE))
+}
+
+fn claude_snapshot(now: &str, window: &str, info: &UsageInfo) -> QuotaSnapshot {
+    QuotaSnapshot {
+        recorded_at: now.to_string(),
+        source: "claude".to_string(),
+        window: window.to_string(),
+        percentage: info.percentage,
+        used: Some(info.used),
+        limit: Some(info.limit),
+        resets_at: info.reset_time.clone(),
+    }
+}
+
+fn codex_snapshot(now: &str, window: &str, info: &CodexRateLimitWindow) -> QuotaSnapshot {
+    QuotaSnapshot {
+        recorded_at: now.to_string(),
+        source: "codex".to_string(),
+        window: window.to_string(),
+        percentage: info.used_percent,
+        used: None,
+        limit: None,
+        resets_at: info
+            .resets_at
+            .and_then(|at| DateTime::from_timestamp(at, 0))
+            .map(|at| at.to_rfc3339()),
+    }
+}
+
+/// Record the windows of a live Claude quota response.
+pub fn record_claude(data: &QuotaData) {
+    if !data.connected {
+        return;
+    }
+    let now = Utc::now().to_rfc3339();
+    let windows = [
+        ("session", &data.session),
+        ("weeklyTotal", &data.weekly_total),
+        ("weeklyOpus", &data.weekly_opus),
+        ("weeklySonnet", &data.weekly_sonnet),
+        ("weeklyDesign", &data.weekly_design),
+    ];
+    record(
+        windows
+            .iter()
+            .filter_map(|(window, info)| {
+                info.as_ref()
+                    .map(|info| claude_snapshot(&now, window, info))
+            })
+            .collect(),
+    );
+}
+
+/// Record the windows of a live Codex rate-limit response.
+pub fn record_codex(limits: &CodexRateLimits) {
+    if !limits.connected {
+        return;
+    }
+    let now = Utc::now().to_rfc3339();
+    let windows = [
+        ("primary", &limits.primary),
+        ("secondary", &limits.secondary),
+    ];
+    record(
+        windows
+            .iter()
+            .filter_map(|(window, info)| {
+                info.as_ref().map(|info| codex_snapshot(&now, window, info))
+            })
+            .collect(),
+    );
+}
+
+/// Record the windows with a percentage of a live custom provider response.
+pub fn record_custom(data: &CustomProviderData) {
+    if !data.connected {
+        return;
+    }
+    let now = Utc::now().to_rfc3339();
+    let source = format!("custom:{}", data.id);
+    record(
+        data.windows
+            .iter()
+            .filter_map(|window| {
+                Some(QuotaSnapshot {
+                    recorded_at: now.clone(),
+                    source: source.clone(),
+                    window: window.id.clone().unwrap_or_else(|| window.label.clone()),
+                    percentage: window.percentage?,
+                    used: window.used,
+                    limit: window.limit,
+                    resets_at: window.reset_time.clone(),
+                })
+            })
+            .collect(),
+    );
+}
+
+fn record(snapshots: Vec<QuotaSnapshot>) {
+    let Ok(mut last) = last_recorded().lock() else {
+        return;
+    };
+    let changed: Vec<QuotaSnapshot> = snapshots
+        .into_iter()
+        .filter(|snapshot| {
+            let key = format!("{}|{}", snapshot.source, snapshot.window);
+            let due = match last.get(&key) {
+                Some((percentage, at)) => {
+                    *percentage != snapshot.percentage || at.elapsed() >= UNCHANGED_RECORD_INTERVAL
+                }
+                None => true,
+            };
+            if due {
+                last.insert(key, (snapshot.percentage, Instant::now()));
+            }
+            due
+        })
+        .collect();
+    drop(last);
+    if changed.is_empty() {
+        return;
+    }
+    let Some(path) = history_path() else {
+        return;
+    };
+    if let Err(error) = append_snapshots(&path, &changed) {
+        eprintln!("[History] failed to record quota snapshots: {error}");
+    }
+}
+
+/// Append one JSON line per record and return the new file size.
+fn append_records<T: Serialize>(path: &Path, records: &[T]) -> Result<u64, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| err.to_string())?;
+    let mut lines = String::new();
+    for record in records {
+        let line = serde_json::to_string(record).map_err(|err| err.to_string())?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+    file.write_all(lines.as_bytes())
+        .map_err(|err| err.to_string())?;
+    Ok(file.metadata().map(|metadata| metadata.len()).unwrap_or(0))
+}
+
+fn append_snapshots(path: &Path, snapshots: &[QuotaSnapshot]) -> Result<(), String> {
+    let size = append_records(path, snapshots)?;
+    if size > COMPACT_THRESHOLD_BYTES {
+        compact(path, Utc::now() - ChronoDuration::days(RETENTION_DAYS))?;
+    }
+    Ok(())
+}
+
+/// Rewrite the file without records older than `cutoff` or unreadable lines.
+fn compact(path: &Path, cutoff: DateTime<Utc>) -> Result<(), String> {
+    let kept = read_snapshots(path, Some(cutoff), None)?;
+    let mut body = String::new();
+    for snapshot in &kept {
+        body.push_str(&serde_json::to_string(snapshot).map_err(|err| err.to_string())?);
+        body.push('\n');
+    }
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, body).map_err(|err| err.to_string())?;
+    fs::rename(&tmp, path).map_err(|err| err.to_string())
+}
+
+/// Records accepted by `keep`, in file order. Lines that fail to parse are
+/// skipped.
+fn read_records<T: DeserializeOwned>(
+    path: &Path,
+    keep: impl Fn(&T) -> bool,
+) -> Result<Vec<T>, String> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(format!("Failed to read {}: {error}", path.display())),
+    };
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        // Invalid UTF-8 only spoils its own line.
+        let Ok(line) = line else {
+            continue;
+        };
+        let Ok(record) = serde_json::from_str::<T>(&line) else {
+            continue;
+        };
+        if keep(&record) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Snapshots recorded in `[since, until)`, oldest first.
+fn read_snapshots(
+    path: &Path,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Result<Vec<QuotaSnapshot>, String> {
+    read_records(path, |snapshot: &QuotaSnapshot| {
+        in_bounds(parse_instant(&snapshot.recorded_at), since, until)
+    })
+}
+
+pub fn load_history(
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Result<Vec<QuotaSnapshot>, String> {
+    match history_path() {
+        Some(path) => read_snapshots(&path, since, until),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Append a detected spending anomaly.
+pub fn record_anomaly(anomaly: &CostAnomaly) {
+    let Some(path) = anomaly_path() else {
+        return;
+    };
+    if let Err(error) = append_records(&path, std::slice::from_ref(anomaly)) {
+        eprintln!("[History] failed to record cost anomaly: {error}");
+    }
+}
+
+/// Anomalies detected in `[since, until)`, oldest first.
+pub fn load_anomalies(
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Result<Vec<CostAnomaly>, String> {
+    match anomaly_path() {
+        Some(path) => read_records(&path, |anomaly: &CostAnomaly| {
+            in_bounds(parse_instant(&anomaly.detected_at), since, until)
+        }),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(recorded_at: &str, percentage: f64) -> QuotaSnapshot {
+        QuotaSnapshot {
+            recorded_at: recorded_at.to_string(),
+            source: "claude".to_string(),
+            window: "session".to_string(),
+            percentage,
+            used: Some(percentage),
+            limit: Some(100.0),
+            resets_at: None,
+        }
+    }
+
+    #[test]
+    fn appends_filters_and_compacts_snapshots() {
+        let path = std::env::temp_dir()
+            .join(format!("quotabar-history-{}", std::process::id()))
+            .join(HISTORY_FILE);
+        let _ = fs::remove_file(&path);
+        append_snapshots(
+            &path,
+            &[
+                snapshot("2026-05-01T10:00:00Z", 10.0),
+                snapshot("2026-05-02T10:00:00Z", 20.0),
+            ],
+        )
+        .unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+        append_snapshots(&path, &[snapshot("2026-05-03T10:00:00Z", 30.0)]).unwrap();
+
+        let since = DateTime::parse_from_rfc3339("2026-05-02T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let recent = read_snapshots(&path, Some(since), None).unwrap();
+        assert_eq!(
+            recent.iter().map(|s| s.percentage).collect::<Vec<_>>(),
+            vec![20.0, 30.0]
+        );
+
+        compact(&path, since).unwrap();
+        assert_eq!(read_snapshots(&path, None, None).unwrap(), recent);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,310 @@
+//! Declarative HTTP providers listed under `httpProviders` in the user config.
+//!
+//! Each definition is one request on the shared client whose JSON response is
+//! mapped into quota windows with JSONPath (see `docs/provider-plugins.md`).
+//! Secrets are interpolated into the URL and header values per request and are
+//! never cached or logged.
+
+use crate::domain::models::{CustomProviderData, CustomProviderWindow};
+use crate::services::config::{HttpProviderConfig, HttpWindowMapping};
+use crate::services::custom_provider::{provider_percentage, window_percentage};
+use crate::services::http::shared_http_client;
+use crate::services::jsonpath::JsonPath;
+use serde_json::Value;
+use std::time::Duration;
+
+pub const HTTP_PROVIDER_KIND: &str = "http";
+/// Epoch values above this are treated as milliseconds.
+const EPOCH_MILLIS_THRESHOLD: f64 = 1e12;
+
+fn read_env_secret(name: &str) -> Result<String, String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| format!("Environment variable {name} is not set"))
+}
+
+fn read_file_secret(path: &str) -> Result<String, String> {
+    let resolved = match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .ok_or_else(|| "Could not resolve home directory".to_string())?
+            .join(rest),
+        None => std::path::PathBuf::from(path),
+    };
+    let content = std::fs::read_to_string(&resolved)
+        .map_err(|e| format!("Failed to read secret file {}: {e}", resolved.display()))?;
+    let secret = content.trim();
+    if secret.is_empty() {
+        return Err(format!("Secret file {} is empty", resolved.display()));
+    }
+    Ok(secret.to_string())
+}
+
+/// `service` or `service/account`, read with `security find-generic-password`.
+#[cfg(target_os = "macos")]
+fn read_keychain_secret(spec: &str) -> Result<String, String> {
+    let (service, account) = match spec.split_once('/') {
+        Some((service, account)) => (service, Some(account)),
+        None => (spec, None),
+    };
+    let mut args = vec!["find-generic-password", "-s", service];
+    if let Some(account) = account {
+        args.extend(["-a", account]);
+    }
+    args.push("-w");
+
+    let output = std::process::Command::new("security")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to run security: {e}"))?;
+    let secret = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || secret.is_empty() {
+        return Err(format!("Keychain item '{spec}' not found"));
+    }
+    Ok(secret)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_keychain_secret(spec: &str) -> Result<String, String> {
+    Err(format!(
+        "Keychain secret '{spec}' is only supported on macOS. Use env: or file: instead."
+    ))
+}
+
+fn resolve_secret(reference: &str) -> Result<String, String> {
+    let (source, arg) = reference
+        .split_once(':')
+        .ok_or_else(|| format!("Secret reference '{reference}' needs a source prefix"))?;
+    match source.trim() {
+        "env" => read_env_secret(arg.trim()),
+        "file" => read_file_secret(arg.trim()),
+        "keychain" => read_keychain_secret(arg.trim()),
+        other => Err(format!("Unknown secret source '{other}'")),
+    }
+}
+
+/// Replace every `${source:arg}` in `template` with the resolved secret.
+fn interpolate(
+    template: &str,
+    resolve: impl Fn(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("Unclosed '${{' in '{template}'"))?;
+        out.push_str(&resolve(&after[..end])?);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn select_first<'a>(doc: &'a Value, expr: &str) -> Result<Option<&'a Value>, String> {
+    Ok(JsonPath::parse(expr)?.first(doc))
+}
+
+fn number_at(doc: &Value, expr: Option<&String>) -> Result<Option<f64>, String> {
+    let Some(expr) = expr else {
+        return Ok(None);
+    };
+    Ok(select_first(doc, expr)?.and_then(|value| {
+        value
+            .as_f64()
+            .or_else(|| value.as_str().and_then(|v| v.trim().parse::<f64>().ok()))
+    }))
+}
+
+/// Reset values may be ISO strings or epoch seconds / milliseconds.
+fn reset_at(doc: &Value, expr: Option<&String>) -> Result<Option<String>, String> {
+    let Some(expr) = expr else {
+        return Ok(None);
+    };
+    Ok(select_first(doc, expr)?.and_then(|value| match value {
+        Value::String(text) if !text.trim().is_empty() => Some(text.trim().to_string()),
+        Value::Number(number) => number.as_f64().and_then(|epoch| {
+            let millis = if epoch > EPOCH_MILLIS_THRESHOLD {
+                epoch
+            } else {
+                epoch * 1000.0
+            };
+            chrono::DateTime::from_timestamp_millis(millis as i64).map(|dt| dt.to_rfc3339())
+        }),
+        _ => None,
+    }))
+}
+
+fn map_window(
+    doc: &Value,
+    index: usize,
+    mapping: &HttpWindowMapping,
+) -> Result<Option<CustomProviderWindow>, String> {
+    let used = number_at(doc, mapping.used.as_ref())?;
+    let limit = number_at(doc, mapping.limit.as_ref())?;
+    let explicit = number_at(doc, mapping.percentage.as_ref())?;
+    if used.is_none() && limit.is_none() && explicit.is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some(CustomProviderWindow {
+        id: mapping.id.clone(),
+        label: mapping
+            .label
+            .clone()
+            .or_else(|| mapping.id.clone())
+            .unwrap_or_else(|| format!("Window {}", index + 1)),
+        used,
+        limit,
+        percentage: window_percentage(explicit, used, limit),
+        reset_time: reset_at(doc, mapping.reset.as_ref())?,
+    }))
+}
+
+/// Map a response document through the provider's JSONPath definitions.
+pub fn map_response(
+    provider: &HttpProviderConfig,
+    doc: &Value,
+) -> Result<CustomProviderData, String> {
+    let mut windows = Vec::new();
+    for (index, mapping) in provider.windows.iter().enumerate() {
+        if let Some(window) = map_window(doc, index, mapping)? {
+            windows.push(window);
+        }
+    }
+    if windows.is_empty() {
+        return Err("Response did not match any window mapping".to_string());
+    }
+
+    let plan_type = match provider.plan_type.as_ref() {
+        Some(expr) => select_first(doc, expr)?
+            .and_then(Value::as_str)
+            .map(ToString::to_string),
+        None => None,
+    };
+
+    Ok(CustomProviderData {
+        id: provider.id.clone(),
+        name: provider.display_name(),
+        kind: HTTP_PROVIDER_KIND.to_string(),
+        connected: true,
+        plan_type,
+        percentage: provider_percentage(&windows),
+        windows,
+        cost: None,
+        updated_at: Some(chrono::Utc::now().to_rfc3339()),
+        error: None,
+    })
+}
+
+pub async fn fetch_http_provider(
+    provider: &HttpProviderConfig,
+) -> Result<CustomProviderData, String> {
+    let name = provider.display_name();
+    let url = interpolate(&provider.url, resolve_secret)?;
+    let method =
+        reqwest::Method::from_bytes(provider.method.trim().to_ascii_uppercase().as_bytes())
+            .map_err(|_| format!("Unsupported HTTP method '{}'", provider.method))?;
+
+    let mut request = shared_http_client()
+        .request(method, &url)
+        .header("Accept", "application/json")
+        .header("User-Agent", "QuotaBar/0.2 (HTTP provider)")
+        .timeout(Duration::from_secs(provider.timeout_secs.max(1)));
+    for (header, value) in &provider.headers {
+        request = request.header(header.as_str(), interpolate(value, resolve_secret)?);
+    }
+    if let Some(body) = &provider.body {
+        request = request.json(body);
+    }
+
+    // The resolved URL may embed secrets, so it is stripped from errors.
+    let response = request
+        .send()
+        .await
+        .map_err(|err| format!("Network error: {}", err.without_url()))?;
+
+    let status = response.status();
+    if provider.auth_failure_status.contains(&status.as_u16()) {
+        return Ok(CustomProviderData::disconnected(
+            provider.id.clone(),
+            name.clone(),
+            HTTP_PROVIDER_KIND,
+            format!("{name} rejected the configured credentials ({status})."),
+        ));
+    }
+    if !status.is_success() {
+        return Err(format!("{name} API error: {status}"));
+    }
+
+    let doc = response
+        .json::<Value>()
+        .await
+        .map_err(|err| format!("Failed to parse {name} response: {}", err.without_url()))?;
+    map_response(provider, &doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn provider(windows: Value) -> HttpProviderConfig {
+        serde_json::from_value(json!({
+            "id": "acme",
+            "url": "https://api.example.com/quota",
+            "planType": "$.plan.name",
+            "windows": windows
+        }))
+        .expect("valid provider config")
+    }
+
+    #[test]
+    fn interpolates_secret_references() {
+        let got = interpolate("Bearer ${env:ACME_TOKEN}!", |reference| {
+            assert_eq!(reference, "env:ACME_TOKEN");
+            Ok("s3cret".to_string())
+        })
+        .unwrap();
+        assert_eq!(got, "Bearer s3cret!");
+        assert!(interpolate("Bearer ${env:ACME", |_| Ok(String::new())).is_err());
+        assert!(resolve_secret("vault:acme").is_err());
+    }
+
+    #[test]
+    fn maps_windows_from_json_paths() {
+        let provider = provider(json!([
+            { "id": "daily", "label": "Daily", "used": "$.usage.daily.used", "limit": "$.usage.daily.limit", "reset": "$.usage.daily.resetsAt" },
+            { "id": "monthly", "percentage": "$.usage.monthly.pct" },
+            { "id": "missing", "used": "$.usage.nope" }
+        ]));
+        let doc = json!({
+            "plan": { "name": "Team" },
+            "usage": {
+                "daily": { "used": "45", "limit": 60, "resetsAt": 1767225600 },
+                "monthly": { "pct": 12.5 }
+            }
+        });
+        let data = map_response(&provider, &doc).expect("mapped");
+        assert!(data.connected);
+        assert_eq!(data.kind, HTTP_PROVIDER_KIND);
+        assert_eq!(data.plan_type.as_deref(), Some("Team"));
+        assert_eq!(data.windows.len(), 2);
+        assert_eq!(data.windows[0].percentage, Some(75.0));
+        assert_eq!(
+            data.windows[0].reset_time.as_deref(),
+            Some("2026-01-01T00:00:00+00:00")
+        );
+        assert_eq!(data.percentage, Some(75.0));
+        assert_eq!(provider.auth_failure_status, vec![401, 403]);
+    }
+
+    #[test]
+    fn unmatched_response_is_an_error() {
+        let provider = provider(json!([{ "used": "$.used", "limit": "$.limit" }]));
+        assert!(map_response(&provider, &json!({ "other": 1 })).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Minimal JSONPath subset for declarative HTTP providers.
+//!
+//! Supported: `$` root, `.name` / `['name']` members, `[n]` indices (negative
+//! counts from the end), `*` / `[*]` wildcards and `..name` recursive descent.
+//! Filters and slices are intentionally left out; providers that need them
+//! should use a command plugin instead.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Member(String),
+    Index(i64),
+    Wildcard,
+    Descendant(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let path = path.trim();
+        let rest = path
+            .strip_prefix('$')
+            .ok_or_else(|| format!("JSONPath must start with '$': {path}"))?;
+        let chars: Vec<char> = rest.chars().collect();
+        let mut segments = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '.' if chars.get(i + 1) == Some(&'.') => {
+                    let (name, next) = read_name(&chars, i + 2);
+                    if name.is_empty() {
+                        return Err(format!("Expected a member name after '..' in {path}"));
+                    }
+                    segments.push(Segment::Descendant(name));
+                    i = next;
+                }
+                '.' => {
+                    if chars.get(i + 1) == Some(&'*') {
+                        segments.push(Segment::Wildcard);
+                        i += 2;
+                        continue;
+                    }
+                    let (name, next) = read_name(&chars, i + 1);
+                    if name.is_empty() {
+                        return Err(format!("Expected a member name after '.' in {path}"));
+                    }
+                    segments.push(Segment::Member(name));
+                    i = next;
+                }
+                '[' => {
+                    let close = chars[i..]
+                        .iter()
+                        .position(|c| *c == ']')
+                        .map(|offset| i + offset)
+                        .ok_or_else(|| format!("Unclosed '[' in {path}"))?;
+                    let inner: String = chars[i + 1..close].iter().collect();
+                    segments.push(parse_bracket(inner.trim(), path)?);
+                    i = close + 1;
+                }
+                other => return Err(format!("Unexpected '{other}' in JSONPath {path}")),
+            }
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// All values matched by the path, in document order.
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for value in current {
+                match segment {
+                    Segment::Member(name) => {
+                        if let Some(child) = value.get(name) {
+                            next.push(child);
+                        }
+                    }
+                    Segment::Index(index) => {
+                        if let Some(items) = value.as_array() {
+                            let resolved = if *index < 0 {
+                                items.len() as i64 + index
+                            } else {
+                                *index
+                            };
+                            if let Some(child) =
+                                usize::try_from(resolved).ok().and_then(|i| items.get(i))
+                            {
+                                next.push(child);
+                            }
+                        }
+                    }
+                    Segment::Wildcard => match value {
+                        Value::Array(items) => next.extend(items.iter()),
+                        Value::Object(map) => next.extend(map.values()),
+                        _ => {}
+                    },
+                    Segment::Descendant(name) => collect_descendants(value, name, &mut next),
+                }
+            }
+            current = next;
+        }
+        current
+    }
+
+    pub fn first<'a>(&self, root: &'a Value) -> Option<&'a Value> {
+        self.select(root).into_iter().next()
+    }
+}
+
+fn read_name(chars: &[char], start: usize) -> (String, usize) {
+    let mut end = start;
+    while end < chars.len() && chars[end] != '.' && chars[end] != '[' {
+        end += 1;
+    }
+    (chars[start..end].iter().collect(), end)
+}
+
+fn parse_bracket(inner: &str, path: &str) -> Result<Segment, String> {
+    if inner == "*" {
+        return Ok(Segment::Wildcard);
+    }
+    for quote in ['\'', '"'] {
+        if let Some(name) = inner
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return Ok(Segment::Member(name.to_string()));
+        }
+    }
+    inner
+        .parse::<i64>()
+        .map(Segment::Index)
+        .map_err(|_| format!("Unsupported bracket expression [{inner}] in {path}"))
+}
+
+fn collect_descendants<'a>(value: &'a Value, name: &str, out: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(child) = map.get(name) {
+                out.push(child);
+            }
+            for child in map.values() {
+                collect_descendants(child, name, out);
+            }
+        }
+        Value::Array(items) => {
+            for child in items {
+                collect_descendants(child, name, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn selects_members_indices_and_wildcards() {
+        let doc = json!({
+            "data": { "quota-info": [{ "used": 3 }, { "used": 7 }] }
+        });
+        let first = JsonPath::parse("$.data['quota-info'][0].used").unwrap();
+        assert_eq!(first.first(&doc), Some(&json!(3)));
+        let last = JsonPath::parse("$.data[\"quota-info\"][-1].used").unwrap();
+        assert_eq!(last.first(&doc), Some(&json!(7)));
+        let all = JsonPath::parse("$.data.quota-info[*].used").unwrap();
+        assert_eq!(all.select(&doc), vec![&json!(3), &json!(7)]);
+    }
+
+    #[test]
+    fn recursive_descent_finds_nested_members() {
+        let doc = json!({ "a": { "limit": 1, "b": [{ "limit": 2 }] } });
+        let path = JsonPath::parse("$..limit").unwrap();
+        assert_eq!(path.select(&doc), vec![&json!(1), &json!(2)]);
+    }
+
+    #[test]
+    fn rejects_unsupported_syntax() {
+        assert!(JsonPath::parse("data.used").is_err());
+        assert!(JsonPath::parse("$.items[?(@.used > 1)]").is_err());
+        assert!(JsonPath::parse("$.items[0").is_err());
+    }
+}
@@ -12,6 +12,11 @@ pub fn open_cursor_dashboard() -> Result<(), String> {
         .map_err(|e| e.to_string())
 }
 
+pub fn open_windsurf_dashboard() -> Result<(), String> {
+    tauri_plugin_opener::open_url("https://windsurf.com/subscription/usage", None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
 pub fn open_antigravity_dashboard() -> Result<(), String> {
     tauri_plugin_opener::open_url("https://antigravity.google.com", None::<&str>)
         .map_err(|e| e.to_string())
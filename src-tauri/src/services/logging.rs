@@ -0,0 +1,35 @@
+//! Append-only log files under `~/Library/Logs/quotabar`.
+//!
+//! Each service writes to its own file so a noisy provider (or a plugin's
+//! stderr) does not drown out the Claude OAuth trail.
+
+use std::fs::OpenOptions;
+use std::io::Write as IoWrite;
+
+pub fn append_log(file_name: &str, msg: &str) {
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    let line = format!("[{timestamp}] {msg}\n");
+
+    print!("{line}");
+
+    let log_dir = dirs::home_dir()
+        .unwrap_or_default()
+        .join("Library/Logs/quotabar");
+    if let Err(e) = std::fs::create_dir_all(&log_dir) {
+        eprintln!("[log] failed to create log dir: {e}");
+        return;
+    }
+
+    match OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_dir.join(file_name))
+    {
+        Ok(mut file) => {
+            if let Err(e) = file.write_all(line.as_bytes()) {
+                eprintln!("[log] failed to write log: {e}");
+            }
+        }
+        Err(e) => eprintln!("[log] failed to open log file: {e}"),
+    }
+}
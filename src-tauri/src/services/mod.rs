@@ -1,10 +1,37 @@
+pub mod anomaly;
 pub mod antigravity;
+pub mod budget;
+pub mod cache_efficiency;
+pub mod calibration;
 pub mod claude;
+pub mod claude_blocks;
+pub mod claude_stats;
+pub mod claude_usage;
 pub mod codex;
+pub mod config;
 pub mod cost;
+pub mod cost_combined;
+pub mod cost_index;
+pub mod cost_range;
+pub mod cost_series;
 pub mod cursor;
+pub mod custom_provider;
+pub mod exchange;
+pub mod export;
+pub mod history;
 pub mod http;
+pub mod http_provider;
+pub mod jsonpath;
 pub mod link;
+pub mod logging;
+pub mod notification;
+pub mod openai;
+pub mod plugin;
+pub mod pricing;
+pub mod projects;
+pub mod sessions;
+pub mod subscription;
 pub mod tray;
 pub mod tray_icon;
 pub mod window;
+pub mod windsurf;
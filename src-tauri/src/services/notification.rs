@@ -0,0 +1,11 @@
+//! Native notifications. They are sent from the backend because the macOS
+//! webview has no usable Web Notification API.
+
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+pub fn notify(app: &AppHandle, title: &str, body: &str) {
+    if let Err(error) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("[Notification] failed to show '{title}': {error}");
+    }
+}
@@ -0,0 +1,467 @@
+//! OpenAI organization billing via the admin `/organization/costs` and
+//! `/organization/usage/completions` endpoints.
+//!
+//! Requires an admin key (`OPENAI_ADMIN_KEY`); regular project keys are
+//! rejected by these endpoints. Buckets are fetched once from the earliest
+//! range start and folded into the same `CostOverview` shape the local
+//! `ccstats` sources produce, so the billed numbers can sit next to Codex's
+//! local-log estimate.
+//!
+//! The costs endpoint only has daily buckets aligned to UTC midnight, so the
+//! ranges follow UTC calendar days; local days would split buckets.
+
+use crate::services::cost::{
+    self, CostModelSummary, CostOverview, CostProjectSummary, CostRangeSummary, CostTokenBreakdown,
+};
+use crate::services::http::shared_http_client;
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub const OPENAI_COST_SOURCE: &str = "openai";
+const OPENAI_ADMIN_KEY_ENV: &str = "OPENAI_ADMIN_KEY";
+const OPENAI_ORGANIZATION_URL: &str = "https://api.openai.com/v1/organization";
+const PAGE_LIMIT: u32 = 31;
+const MAX_PAGES: usize = 12;
+
+struct RangeSpec {
+    key: &'static str,
+    label: &'static str,
+    since: NaiveDate,
+}
+
+fn read_admin_key() -> Option<String> {
+    std::env::var(OPENAI_ADMIN_KEY_ENV)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn range_specs(today: NaiveDate) -> Vec<RangeSpec> {
+    let week_start = today - ChronoDuration::days(today.weekday().num_days_from_monday() as i64);
+    let month_start = today.with_day(1).unwrap_or(today);
+    vec![
+        RangeSpec {
+            key: "today",
+            label: "Today",
+            since: today,
+        },
+        RangeSpec {
+            key: "week",
+            label: "This Week",
+            since: week_start,
+        },
+        RangeSpec {
+            key: "month",
+            label: "This Month",
+            since: month_start,
+        },
+    ]
+}
+
+fn utc_midnight_ts(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+        .timestamp()
+}
+
+struct FetchedBuckets {
+    buckets: Vec<serde_json::Value>,
+    /// More pages remained after [`MAX_PAGES`].
+    truncated: bool,
+}
+
+/// Fetch up to [`MAX_PAGES`] pages of daily buckets for `endpoint` starting
+/// at `start_time`.
+async fn fetch_buckets(
+    admin_key: &str,
+    endpoint: &str,
+    start_time: i64,
+    group_by: &[&str],
+) -> Result<FetchedBuckets, String> {
+    let mut buckets = Vec::new();
+    let mut page: Option<String> = None;
+
+    for _ in 0..MAX_PAGES {
+        let mut query: Vec<(&str, String)> = vec![
+            ("start_time", start_time.to_string()),
+            ("bucket_width", "1d".to_string()),
+            ("limit", PAGE_LIMIT.to_string()),
+        ];
+        for field in group_by {
+            query.push(("group_by", field.to_string()));
+        }
+        if let Some(cursor) = page.as_ref() {
+            query.push(("page", cursor.clone()));
+        }
+
+        let response = shared_http_client()
+            .get(format!("{OPENAI_ORGANIZATION_URL}/{endpoint}"))
+            .query(&query)
+            .header("Authorization", format!("Bearer {admin_key}"))
+            .header("Accept", "application/json")
+            .timeout(Duration::from_secs(15))
+            .send()
+            .await
+            .map_err(|err| format!("Network error: {err}"))?;
+
+        let status = response.status();
+        if status.as_u16() == 401 || status.as_u16() == 403 {
+            return Err(format!(
+                "OpenAI rejected the admin key ({status}). Check {OPENAI_ADMIN_KEY_ENV}."
+            ));
+        }
+        if !status.is_success() {
+            return Err(format!("OpenAI API error: {status}"));
+        }
+
+        let data = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|err| format!("Failed to parse OpenAI response: {err}"))?;
+
+        if let Some(items) = data["data"].as_array() {
+            buckets.extend(items.iter().cloned());
+        }
+
+        match data["next_page"].as_str() {
+            Some(next) if data["has_more"].as_bool().unwrap_or(false) => {
+                page = Some(next.to_string());
+            }
+            _ => {
+                return Ok(FetchedBuckets {
+                    buckets,
+                    truncated: false,
+                })
+            }
+        }
+    }
+
+    eprintln!("[OpenAI] {endpoint} still had more pages after {MAX_PAGES}; results are partial");
+    Ok(FetchedBuckets {
+        buckets,
+        truncated: true,
+    })
+}
+
+/// `line_item` looks like `"gpt-4o-2024-08-06, input"`; the model is the part
+/// before the comma. Non-model line items (e.g. `"web search"`) pass through.
+fn model_from_line_item(line_item: &str) -> String {
+    line_item
+        .split_once(", ")
+        .map(|(model, _)| model)
+        .unwrap_or(line_item)
+        .trim()
+        .to_string()
+}
+
+/// Costs carry `project_name` but usage only `project_id`, so both are keyed
+/// by id and named afterwards.
+fn project_id(result: &serde_json::Value) -> Option<String> {
+    result["project_id"].as_str().map(ToString::to_string)
+}
+
+fn usage_tokens(result: &serde_json::Value) -> Option<CostTokenBreakdown> {
+    let input = result["input_tokens"].as_i64()?;
+    let output = result["output_tokens"].as_i64().unwrap_or(0);
+    let cached = result["input_cached_tokens"].as_i64().unwrap_or(0);
+    let audio_input = result["input_audio_tokens"].as_i64().unwrap_or(0);
+    let audio_output = result["output_audio_tokens"].as_i64().unwrap_or(0);
+
+    let input_tokens = (input - cached).max(0) + audio_input;
+    let output_tokens = output + audio_output;
+    Some(CostTokenBreakdown {
+        input_tokens,
+        output_tokens,
+        reasoning_tokens: 0,
+        cache_creation_tokens: 0,
+        cache_read_tokens: cached,
+        total_tokens: input_tokens + output_tokens + cached,
+    })
+}
+
+#[derive(Default)]
+struct Accumulator {
+    cost: f64,
+    tokens: CostTokenBreakdown,
+}
+
+fn bucket_start(bucket: &serde_json::Value) -> Option<i64> {
+    bucket["start_time"].as_i64()
+}
+
+fn sorted_by_cost(map: HashMap<String, Accumulator>) -> Vec<(String, Accumulator)> {
+    let mut entries: Vec<_> = map.into_iter().collect();
+    entries.sort_by(|a, b| {
+        b.1.cost
+            .partial_cmp(&a.1.cost)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.1.tokens.total_tokens.cmp(&a.1.tokens.total_tokens))
+    });
+    entries
+}
+
+fn summarize_range(
+    spec: &RangeSpec,
+    since_ts: i64,
+    until: NaiveDate,
+    cost_buckets: &[serde_json::Value],
+    usage_buckets: &[serde_json::Value],
+    elapsed_ms: f64,
+) -> CostRangeSummary {
+    let mut total = Accumulator::default();
+    let mut models: HashMap<String, Accumulator> = HashMap::new();
+    let mut projects: HashMap<String, Accumulator> = HashMap::new();
+    let mut project_names: HashMap<String, String> = HashMap::new();
+    let mut valid_entries = 0i64;
+    let mut skipped_entries = 0i64;
+
+    let in_range =
+        |bucket: &&serde_json::Value| bucket_start(bucket).is_some_and(|ts| ts >= since_ts);
+
+    for bucket in cost_buckets.iter().filter(in_range) {
+        for result in bucket["results"].as_array().into_iter().flatten() {
+            let Some(amount) = result["amount"]["value"].as_f64().or_else(|| {
+                result["amount"]["value"]
+                    .as_str()
+                    .and_then(|v| v.parse().ok())
+            }) else {
+                skipped_entries += 1;
+                continue;
+            };
+            valid_entries += 1;
+            total.cost += amount;
+            if let Some(line_item) = result["line_item"].as_str() {
+                models
+                    .entry(model_from_line_item(line_item))
+                    .or_default()
+                    .cost += amount;
+            }
+            if let Some(project) = project_id(result) {
+                if let Some(name) = result["project_name"].as_str() {
+                    project_names.insert(project.clone(), name.to_string());
+                }
+                projects.entry(project).or_default().cost += amount;
+            }
+        }
+    }
+
+    for bucket in usage_buckets.iter().filter(in_range) {
+        for result in bucket["results"].as_array().into_iter().flatten() {
+            let Some(tokens) = usage_tokens(result) else {
+                skipped_entries += 1;
+                continue;
+            };
+            valid_entries += 1;
+            total.tokens.accumulate(&tokens);
+            if let Some(model) = result["model"].as_str() {
+                models
+                    .entry(model.to_string())
+                    .or_default()
+                    .tokens
+                    .accumulate(&tokens);
+            }
+            if let Some(project) = project_id(result) {
+                projects
+                    .entry(project)
+                    .or_default()
+                    .tokens
+                    .accumulate(&tokens);
+            }
+        }
+    }
+
+    CostRangeSummary {
+        range: spec.key.to_string(),
+        label: spec.label.to_string(),
+        since: Some(spec.since.to_string()),
+        until: Some(until.to_string()),
+        currency: "USD".to_string(),
+        cost: Some(total.cost),
+        cost_usd: Some(total.cost),
+        tokens: total.tokens,
+        models: sorted_by_cost(models)
+            .into_iter()
+            .map(|(model, acc)| CostModelSummary {
+                model,
+                cost: Some(acc.cost),
+                cost_usd: Some(acc.cost),
+                tokens: acc.tokens,
+                price_guessed: false,
+            })
+            .collect(),
+        projects: sorted_by_cost(projects)
+            .into_iter()
+            .map(|(project, acc)| CostProjectSummary {
+                project: project_names.remove(&project).unwrap_or(project),
+                tag: None,
+                cost: Some(acc.cost),
+                cost_usd: Some(acc.cost),
+                tokens: acc.tokens,
+            })
+            .collect(),
+        tags: Vec::new(),
+        sources: Vec::new(),
+        valid_entries,
+        skipped_entries,
+        elapsed_ms,
+        comparison: None,
+    }
+}
+
+pub(crate) fn cost_cache_key() -> String {
+    format!("{OPENAI_COST_SOURCE}|USD|utc")
+}
+
+/// Build the billed overview. OpenAI reports amounts in USD and aligns daily
+/// buckets to UTC midnight, so ranges are UTC calendar days.
+pub async fn get_cost_overview(force: bool) -> Result<CostOverview, String> {
+    let cache_key = cost_cache_key();
+    if !force {
+        if let Some(cached) = cost::get_cached_overview(&cache_key)? {
+            return Ok(cached);
+        }
+    }
+
+    let admin_key = read_admin_key().ok_or_else(|| {
+        format!("OpenAI billing not configured. Set {OPENAI_ADMIN_KEY_ENV} to an admin key.")
+    })?;
+
+    let today = Utc::now().date_naive();
+    let specs = range_specs(today);
+    let earliest = specs.iter().map(|spec| spec.since).min().unwrap_or(today);
+    let fetch_start = utc_midnight_ts(earliest);
+
+    let start = Instant::now();
+    let cost_buckets = fetch_buckets(
+        &admin_key,
+        "costs",
+        fetch_start,
+        &["line_item", "project_id"],
+    )
+    .await?;
+    let usage_buckets = fetch_buckets(
+        &admin_key,
+        "usage/completions",
+        fetch_start,
+        &["model", "project_id"],
+    )
+    .await?;
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let truncated = cost_buckets.truncated || usage_buckets.truncated;
+    let (cost_buckets, usage_buckets) = (cost_buckets.buckets, usage_buckets.buckets);
+
+    let ranges = specs
+        .iter()
+        .map(|spec| {
+            let since_ts = utc_midnight_ts(spec.since);
+            summarize_range(
+                spec,
+                since_ts,
+                today,
+                &cost_buckets,
+                &usage_buckets,
+                elapsed_ms,
+            )
+        })
+        .collect();
+
+    let overview = CostOverview {
+        source: OPENAI_COST_SOURCE.to_string(),
+        display_name: "OpenAI API".to_string(),
+        currency: "USD".to_string(),
+        generated_at: Utc::now().to_rfc3339(),
+        cached: false,
+        ranges,
+        budgets: Vec::new(),
+        unpriced_models: Vec::new(),
+        exchange_rate: None,
+        truncated,
+        view_key: None,
+    };
+
+    cost::set_cached_overview(cache_key, overview.clone())?;
+    Ok(overview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> RangeSpec {
+        RangeSpec {
+            key: "month",
+            label: "This Month",
+            since: NaiveDate::from_ymd_opt(2026, 5, 1).unwrap(),
+        }
+    }
+
+    #[test]
+    fn splits_model_from_line_item() {
+        assert_eq!(
+            model_from_line_item("gpt-4o-2024-08-06, input"),
+            "gpt-4o-2024-08-06"
+        );
+        assert_eq!(model_from_line_item("web search"), "web search");
+    }
+
+    #[test]
+    fn folds_cost_and_usage_buckets_into_models_and_projects() {
+        let costs = vec![serde_json::json!({
+            "start_time": 1_777_593_600,
+            "results": [
+                { "amount": { "value": 1.5, "currency": "usd" }, "line_item": "gpt-4o, input", "project_id": "proj_a", "project_name": "Alpha" },
+                { "amount": { "value": 0.5, "currency": "usd" }, "line_item": "gpt-4o, output", "project_id": "proj_b" },
+                { "line_item": "broken" }
+            ]
+        })];
+        let usage = vec![serde_json::json!({
+            "start_time": 1_777_593_600,
+            "results": [
+                { "input_tokens": 1000, "input_cached_tokens": 400, "output_tokens": 200, "model": "gpt-4o", "project_id": "proj_a" }
+            ]
+        })];
+
+        let summary = summarize_range(
+            &spec(),
+            1_777_593_600,
+            NaiveDate::from_ymd_opt(2026, 5, 12).unwrap(),
+            &costs,
+            &usage,
+            0.0,
+        );
+
+        assert_eq!(summary.cost, Some(2.0));
+        assert_eq!(summary.valid_entries, 3);
+        assert_eq!(summary.skipped_entries, 1);
+        assert_eq!(summary.tokens.input_tokens, 600);
+        assert_eq!(summary.tokens.cache_read_tokens, 400);
+        assert_eq!(summary.tokens.total_tokens, 1200);
+        assert_eq!(summary.models.len(), 1);
+        assert_eq!(summary.models[0].model, "gpt-4o");
+        // Usage for `proj_a` lands on the project named by the cost results.
+        assert_eq!(summary.projects.len(), 2);
+        assert_eq!(summary.projects[0].project, "Alpha");
+        assert_eq!(summary.projects[0].cost, Some(1.5));
+        assert_eq!(summary.projects[0].tokens.output_tokens, 200);
+    }
+
+    #[test]
+    fn ignores_buckets_before_range_start() {
+        let costs = vec![serde_json::json!({
+            "start_time": 100,
+            "results": [{ "amount": { "value": 9.0 }, "line_item": "gpt-4o, input" }]
+        })];
+        let summary = summarize_range(
+            &spec(),
+            200,
+            NaiveDate::from_ymd_opt(2026, 5, 12).unwrap(),
+            &costs,
+            &[],
+            0.0,
+        );
+        assert_eq!(summary.cost, Some(0.0));
+        assert!(summary.models.is_empty());
+    }
+}
@@ -0,0 +1,318 @@
+//! Command-based provider plugins.
+//!
+//! A plugin is any executable listed under `plugins` in the user config. It is
+//! run with stdin closed and must print one JSON document on stdout following
+//! the schema in `docs/provider-plugins.md`. Stderr is never shown in the UI;
+//! it is appended to `plugins.log` so authors can debug their scripts.
+
+use crate::domain::models::{CustomProviderCost, CustomProviderData, CustomProviderWindow};
+use crate::services::config::PluginConfig;
+use crate::services::custom_provider::{provider_percentage, window_percentage};
+use crate::services::logging::append_log;
+use serde::Deserialize;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const PLUGIN_KIND: &str = "command";
+const PLUGIN_LOG_FILE: &str = "plugins.log";
+const MAX_OUTPUT_BYTES: u64 = 256 * 1024;
+const MAX_LOGGED_STDERR_BYTES: usize = 4 * 1024;
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long to wait for the pipes to close once the plugin has exited; a
+/// process it left behind may still hold them open.
+const PIPE_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginOutput {
+    #[serde(default)]
+    windows: Vec<PluginWindow>,
+    cost: Option<PluginCost>,
+    plan_type: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginWindow {
+    id: Option<String>,
+    label: Option<String>,
+    used: Option<f64>,
+    limit: Option<f64>,
+    percentage: Option<f64>,
+    reset_time: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginCost {
+    amount: f64,
+    currency: Option<String>,
+    label: Option<String>,
+}
+
+struct CommandOutput {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    success: bool,
+    status: String,
+}
+
+fn log_plugin(plugin: &PluginConfig, msg: &str) {
+    append_log(PLUGIN_LOG_FILE, &format!("[Plugin:{}] {msg}", plugin.id));
+}
+
+fn read_capped<R: Read + Send + 'static>(pipe: Option<R>) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(pipe) = pipe {
+            let _ = pipe.take(MAX_OUTPUT_BYTES).read_to_end(&mut buf);
+        }
+        let _ = sender.send(buf);
+    });
+    receiver
+}
+
+/// Kill the plugin together with anything it spawned. On unix the plugin
+/// leads its own process group, so the group is killed as a whole.
+fn kill_plugin(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+/// Spawn the plugin and wait up to `timeout_secs`, killing it on expiry.
+/// Pipes are drained on helper threads so a chatty plugin cannot deadlock us,
+/// and are given up on after [`PIPE_DRAIN_TIMEOUT`].
+fn run_command(plugin: &PluginConfig) -> Result<CommandOutput, String> {
+    let mut command = Command::new(&plugin.command);
+    command
+        .args(&plugin.args)
+        .envs(&plugin.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to start plugin '{}': {e}", plugin.command))?;
+
+    let stdout = read_capped(child.stdout.take());
+    let stderr = read_capped(child.stderr.take());
+
+    let timeout = Duration::from_secs(plugin.timeout_secs.max(1));
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() >= deadline => {
+                kill_plugin(&mut child);
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => thread::sleep(WAIT_POLL_INTERVAL),
+            Err(e) => {
+                kill_plugin(&mut child);
+                return Err(format!("Failed to wait for plugin: {e}"));
+            }
+        }
+    };
+
+    let stdout = stdout.recv_timeout(PIPE_DRAIN_TIMEOUT).unwrap_or_default();
+    let stderr = stderr.recv_timeout(PIPE_DRAIN_TIMEOUT).unwrap_or_default();
+
+    match status {
+        Some(status) => Ok(CommandOutput {
+            stdout,
+            stderr,
+            success: status.success(),
+            status: status.to_string(),
+        }),
+        None => {
+            if !stderr.is_empty() {
+                log_plugin(
+                    plugin,
+                    &format!("stderr before timeout: {}", stderr_excerpt(&stderr)),
+                );
+            }
+            Err(format!("Plugin timed out after {}s", timeout.as_secs()))
+        }
+    }
+}
+
+fn stderr_excerpt(stderr: &[u8]) -> String {
+    let text = String::from_utf8_lossy(stderr);
+    let trimmed = text.trim();
+    if trimmed.len() > MAX_LOGGED_STDERR_BYTES {
+        let mut end = MAX_LOGGED_STDERR_BYTES;
+        while !trimmed.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...", &trimmed[..end])
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Map validated plugin stdout into the shared custom provider model.
+pub fn parse_plugin_output(
+    id: &str,
+    name: &str,
+    stdout: &[u8],
+) -> Result<CustomProviderData, String> {
+    let output: PluginOutput = serde_json::from_slice(stdout)
+        .map_err(|e| format!("Plugin output is not valid JSON: {e}"))?;
+
+    if let Some(error) = output.error.filter(|e| !e.trim().is_empty()) {
+        return Ok(CustomProviderData::disconnected(
+            id,
+            name,
+            PLUGIN_KIND,
+            error,
+        ));
+    }
+
+    let windows: Vec<CustomProviderWindow> = output
+        .windows
+        .iter()
+        .enumerate()
+        .map(|(index, window)| CustomProviderWindow {
+            id: window.id.clone(),
+            label: window
+                .label
+                .clone()
+                .or_else(|| window.id.clone())
+                .unwrap_or_else(|| format!("Window {}", index + 1)),
+            used: window.used,
+            limit: window.limit,
+            percentage: window_percentage(window.percentage, window.used, window.limit),
+            reset_time: window.reset_time.clone(),
+        })
+        .collect();
+
+    let percentage = provider_percentage(&windows);
+
+    let cost = output.cost.map(|cost| CustomProviderCost {
+        amount: cost.amount,
+        currency: cost.currency.unwrap_or_else(|| "USD".to_string()),
+        label: cost.label,
+    });
+
+    if windows.is_empty() && cost.is_none() {
+        return Err("Plugin output has no windows or cost".to_string());
+    }
+
+    Ok(CustomProviderData {
+        id: id.to_string(),
+        name: name.to_string(),
+        kind: PLUGIN_KIND.to_string(),
+        connected: true,
+        plan_type: output.plan_type,
+        windows,
+        cost,
+        percentage,
+        updated_at: Some(chrono::Utc::now().to_rfc3339()),
+        error: None,
+    })
+}
+
+/// Run one plugin to completion. Blocking; call from a worker thread.
+pub fn run_plugin(plugin: &PluginConfig) -> Result<CustomProviderData, String> {
+    let started = Instant::now();
+    let output = run_command(plugin).inspect_err(|error| log_plugin(plugin, error))?;
+
+    if !output.stderr.is_empty() {
+        log_plugin(
+            plugin,
+            &format!("stderr: {}", stderr_excerpt(&output.stderr)),
+        );
+    }
+    if !output.success {
+        let error = format!("Plugin exited with {}", output.status);
+        log_plugin(plugin, &error);
+        return Err(error);
+    }
+
+    let result = parse_plugin_output(&plugin.id, &plugin.display_name(), &output.stdout)
+        .inspect_err(|error| log_plugin(plugin, error))?;
+    log_plugin(
+        plugin,
+        &format!(
+            "ok: windows={}, percentage={:?}, elapsed={:.0}ms",
+            result.windows.len(),
+            result.percentage,
+            started.elapsed().as_secs_f64() * 1000.0
+        ),
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_windows_and_derives_percentage() {
+        let stdout = br#"{
+            "planType": "team",
+            "windows": [
+                { "id": "daily", "label": "Daily", "used": 30, "limit": 120, "resetTime": "2026-06-01T00:00:00Z" },
+                { "id": "monthly", "percentage": 61.5 }
+            ],
+            "cost": { "amount": 4.25 }
+        }"#;
+        let data = parse_plugin_output("acme", "Acme", stdout).expect("valid output");
+        assert!(data.connected);
+        assert_eq!(data.windows.len(), 2);
+        assert_eq!(data.windows[0].percentage, Some(25.0));
+        assert_eq!(data.windows[1].label, "monthly");
+        assert_eq!(data.percentage, Some(61.5));
+        assert_eq!(data.cost.as_ref().map(|c| c.currency.as_str()), Some("USD"));
+    }
+
+    #[test]
+    fn plugin_reported_error_disconnects() {
+        let data = parse_plugin_output("acme", "Acme", br#"{ "error": "token expired" }"#)
+            .expect("error payloads are valid");
+        assert!(!data.connected);
+        assert_eq!(data.error.as_deref(), Some("token expired"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn timeout_kills_processes_the_plugin_spawned() {
+        // The background `sleep` inherits stdout and would keep the pipe open
+        // if only `sh` were killed.
+        let plugin = PluginConfig {
+            id: "slow".to_string(),
+            name: None,
+            command: "/bin/sh".to_string(),
+            args: vec!["-c".to_string(), "sleep 30 & sleep 30".to_string()],
+            env: Default::default(),
+            interval_secs: 60,
+            timeout_secs: 1,
+            tray: false,
+            enabled: true,
+        };
+        let started = Instant::now();
+        let error = run_command(&plugin).err().expect("plugin times out");
+        assert!(error.contains("timed out"), "{error}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn rejects_empty_or_invalid_output() {
+        assert!(parse_plugin_output("acme", "Acme", b"{}").is_err());
+        assert!(parse_plugin_output("acme", "Acme", b"not json").is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! Built-in per-model token prices (USD per million tokens).
+//!
+//! This is the only price table: every cost QuotaBar shows is priced here,
+//! including summaries from `ccstats`, whose own prices are replaced so the
+//! overview, blocks, series and sessions always agree. Models are matched by
+//! the longest known family prefix, so dated snapshots such as
+//! `claude-sonnet-4-5-20250929` resolve to their family. Any other model that
+//! only matches a family, such as a newer `claude-sonnet-4-7`, is priced as
+//! that family but flagged as guessed.
+//!
+//! `pricingOverrides` in the config file replaces individual token prices
+//! per model or family, and prices models missing from the table. A
+//! [`PriceTable`] reads them once, so load one per computation and pass it
+//! down rather than looking prices up from the config per model.
+
+use crate::services::config::{self, PricingOverrideConfig};
+use crate::services::cost::CostTokenBreakdown;
+
+const PER_MILLION: f64 = 1_000_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+    /// Charged on top of output; zero when reasoning is already billed as
+    /// output, as for every built-in model.
+    pub reasoning: f64,
+}
+
+impl ModelPricing {
+    const fn new(input: f64, output: f64, cache_write: f64, cache_read: f64) -> Self {
+        Self {
+            input,
+            output,
+            cache_write,
+            cache_read,
+            reasoning: 0.0,
+        }
+    }
+
+    /// Reasoning tokens are billed as output; OpenAI already includes them in
+    /// `output_tokens`, so they only cost extra with a `reasoning` override.
+    pub fn cost_usd(&self, tokens: &CostTokenBreakdown) -> f64 {
+        (tokens.input_tokens as f64 * self.input
+            + tokens.output_tokens as f64 * self.output
+            + tokens.reasoning_tokens as f64 * self.reasoning
+            + tokens.cache_creation_tokens as f64 * self.cache_write
+            + tokens.cache_read_tokens as f64 * self.cache_read)
+            / PER_MILLION
+    }
+}
+
+/// `(family prefix, pricing)`; the longest matching prefix wins.
+const BUILTIN_PRICING: &[(&str, ModelPricing)] = &[
+    ("claude-opus-4-6", ModelPricing::new(5.0, 25.0, 6.25, 0.5)),
+    ("claude-opus-4-5", ModelPricing::new(5.0, 25.0, 6.25, 0.5)),
+    ("claude-opus-4-1", ModelPricing::new(15.0, 75.0, 18.75, 1.5)),
+    ("claude-opus-4", ModelPricing::new(15.0, 75.0, 18.75, 1.5)),
+    ("claude-3-opus", ModelPricing::new(15.0, 75.0, 18.75, 1.5)),
+    ("claude-sonnet-4-5", ModelPricing::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-sonnet-4", ModelPricing::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-7-sonnet", ModelPricing::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-5-sonnet", ModelPricing::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-haiku-4-5", ModelPricing::new(1.0, 5.0, 1.25, 0.1)),
+    ("claude-haiku-4", ModelPricing::new(1.0, 5.0, 1.25, 0.1)),
+    ("claude-3-5-haiku", ModelPricing::new(0.8, 4.0, 1.0, 0.08)),
+    ("claude-3-haiku", ModelPricing::new(0.25, 1.25, 0.3, 0.03)),
+    ("gpt-5.1-codex", ModelPricing::new(1.25, 10.0, 0.0, 0.125)),
+    ("gpt-5.1", ModelPricing::new(1.25, 10.0, 0.0, 0.125)),
+    ("gpt-5-codex", ModelPricing::new(1.25, 10.0, 0.0, 0.125)),
+    ("gpt-5-mini", ModelPricing::new(0.25, 2.0, 0.0, 0.025)),
+    ("gpt-5-nano", ModelPricing::new(0.05, 0.4, 0.0, 0.005)),
+    ("gpt-5", ModelPricing::new(1.25, 10.0, 0.0, 0.125)),
+    ("gpt-4.1-mini", ModelPricing::new(0.4, 1.6, 0.0, 0.1)),
+    ("gpt-4.1", ModelPricing::new(2.0, 8.0, 0.0, 0.5)),
+    ("gpt-4o-mini", ModelPricing::new(0.15, 0.6, 0.0, 0.075)),
+    ("gpt-4o", ModelPricing::new(2.5, 10.0, 0.0, 1.25)),
+    ("o4-mini", ModelPricing::new(1.1, 4.4, 0.0, 0.275)),
+    ("o3-mini", ModelPricing::new(1.1, 4.4, 0.0, 0.55)),
+    ("o3", ModelPricing::new(2.0, 8.0, 0.0, 0.5)),
+    ("codex-mini", ModelPricing::new(1.5, 6.0, 0.0, 0.375)),
+    ("gemini-2.5-pro", ModelPricing::new(1.25, 10.0, 0.0, 0.31)),
+    ("gemini-2.5-flash", ModelPricing::new(0.3, 2.5, 0.0, 0.075)),
+];
+
+fn normalize_model(model: &str) -> String {
+    let model = model.trim().to_ascii_lowercase();
+    // Bedrock / Vertex ids: `anthropic.claude-...-v1:0`, `claude-...@20250514`.
+    let model = model.strip_prefix("anthropic.").unwrap_or(&model);
+    model.split('@').next().unwrap_or(model).to_string()
+}
+
+/// The longest built-in family prefix of `normalized`, with its pricing.
+fn builtin_pricing(normalized: &str) -> Option<(&'static str, ModelPricing)> {
+    BUILTIN_PRICING
+        .iter()
+        .filter(|(prefix, _)| normalized.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .copied()
+}
+
+/// Whether `normalized` is the `family` model itself or a dated or versioned
+/// release of it: `claude-sonnet-4-20250514`, `gpt-4.1-2025-04-14`,
+/// `claude-3-5-haiku-20241022-v1:0` or `-latest`. A single-digit part such as
+/// the `-7` in `claude-sonnet-4-7` is a different model.
+fn is_release_of(normalized: &str, family: &str) -> bool {
+    let Some(rest) = normalized.strip_prefix(family) else {
+        return false;
+    };
+    if rest.is_empty() {
+        return true;
+    }
+    let Some(rest) = rest.strip_prefix('-') else {
+        return false;
+    };
+    rest.split('-').all(|part| {
+        let is_date = part.len() >= 2 && part.bytes().all(|b| b.is_ascii_digit());
+        let is_version = part.strip_prefix('v').is_some_and(|version| {
+            !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit() || b == b':')
+        });
+        is_date || is_version || part == "latest"
+    })
+}
+
+/// The override with the longest model prefix matching `normalized`.
+fn find_override<'a>(
+    normalized: &str,
+    overrides: &'a [PricingOverrideConfig],
+) -> Option<&'a PricingOverrideConfig> {
+    overrides
+        .iter()
+        .filter(|entry| {
+            let prefix = normalize_model(&entry.model);
+            !prefix.is_empty() && normalized.starts_with(&prefix)
+        })
+        .max_by_key(|entry| entry.model.trim().len())
+}
+
+/// Token prices the override leaves out keep the built-in price, or zero
+/// for models the table does not know.
+fn apply_override(base: Option<ModelPricing>, entry: &PricingOverrideConfig) -> ModelPricing {
+    let base = base.unwrap_or(ModelPricing::new(0.0, 0.0, 0.0, 0.0));
+    ModelPricing {
+        input: entry.input.unwrap_or(base.input),
+        output: entry.output.unwrap_or(base.output),
+        cache_write: entry.cache_creation.unwrap_or(base.cache_write),
+        cache_read: entry.cache_read.unwrap_or(base.cache_read),
+        reasoning: entry.reasoning.unwrap_or(base.reasoning),
+    }
+}
+
+/// A resolved price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceMatch {
+    pub pricing: ModelPricing,
+    /// Borrowed from a model family the model is not a release of, so the
+    /// cost is an estimate. Never set for user overrides.
+    pub guessed: bool,
+}
+
+/// The built-in table plus the user's `pricingOverrides`.
+#[derive(Debug, Clone, Default)]
+pub struct PriceTable {
+    overrides: Vec<PricingOverrideConfig>,
+}
+
+impl PriceTable {
+    /// Read the overrides from the config file.
+    pub fn load() -> Self {
+        Self::new(config::load_config().pricing_overrides)
+    }
+
+    /// `PriceTable::default()` has the built-in prices only.
+    pub fn new(overrides: Vec<PricingOverrideConfig>) -> Self {
+        Self { overrides }
+    }
+
+    pub fn resolve(&self, model: &str) -> Option<PriceMatch> {
+        let normalized = normalize_model(model);
+        let builtin = builtin_pricing(&normalized);
+        match find_override(&normalized, &self.overrides) {
+            Some(entry) => Some(PriceMatch {
+                pricing: apply_override(builtin.map(|(_, pricing)| pricing), entry),
+                guessed: false,
+            }),
+            None => builtin.map(|(family, pricing)| PriceMatch {
+                pricing,
+                guessed: !is_release_of(&normalized, family),
+            }),
+        }
+    }
+
+    pub fn lookup(&self, model: &str) -> Option<ModelPricing> {
+        self.resolve(model).map(|found| found.pricing)
+    }
+
+    /// Cost of `tokens` for `model`, or `None` when the model is unknown.
+    pub fn cost_usd(&self, model: &str, tokens: &CostTokenBreakdown) -> Option<f64> {
+        self.lookup(model).map(|pricing| pricing.cost_usd(tokens))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_dated_and_provider_prefixed_models() {
+        let prices = PriceTable::default();
+        let lookup = |model: &str| prices.lookup(model);
+        assert_eq!(
+            lookup("claude-sonnet-4-5-20250929").map(|p| p.input),
+            Some(3.0)
+        );
+        assert_eq!(
+            lookup("claude-opus-4-5-20251101").map(|p| p.input),
+            Some(5.0)
+        );
+        assert_eq!(
+            lookup("claude-opus-4-1-20250805").map(|p| p.input),
+            Some(15.0)
+        );
+        assert_eq!(
+            lookup("anthropic.claude-3-5-haiku-20241022-v1:0").map(|p| p.output),
+            Some(4.0)
+        );
+        assert_eq!(lookup("gpt-5-codex").map(|p| p.output), Some(10.0));
+        assert_eq!(lookup("gpt-5-mini").map(|p| p.output), Some(2.0));
+        assert_eq!(lookup("gpt-4.1-2025-04-14").map(|p| p.input), Some(2.0));
+        assert!(lookup("mystery-model").is_none());
+    }
+
+    #[test]
+    fn prices_every_token_class() {
+        let tokens = CostTokenBreakdown {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_creation_tokens: 200_000,
+            cache_read_tokens: 2_000_000,
+            ..Default::default()
+        };
+        let cost = PriceTable::default()
+            .cost_usd("claude-sonnet-4-20250514", &tokens)
+            .unwrap();
+        assert!((cost - (3.0 + 1.5 + 0.75 + 0.6)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn overrides_replace_single_token_prices() {
+        let prices = PriceTable::new(vec![
+            PricingOverrideConfig {
+                model: "claude-sonnet-4".to_string(),
+                input: None,
+                output: None,
+                cache_creation: None,
+                cache_read: Some(0.1),
+                reasoning: None,
+            },
+            PricingOverrideConfig {
+                model: "local-llama".to_string(),
+                input: Some(0.2),
+                output: Some(0.4),
+                cache_creation: None,
+                cache_read: None,
+                reasoning: Some(0.4),
+            },
+        ]);
+        let sonnet = prices.lookup("claude-sonnet-4-5-20250929").unwrap();
+        assert_eq!((sonnet.input, sonnet.cache_read), (3.0, 0.1));
+
+        let llama = prices.lookup("local-llama-70b").unwrap();
+        let tokens = CostTokenBreakdown {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            reasoning_tokens: 500_000,
+            cache_read_tokens: 1_000_000,
+            ..Default::default()
+        };
+        assert!((llama.cost_usd(&tokens) - (0.2 + 0.4 + 0.2)).abs() < 1e-9);
+        assert!(prices.lookup("mystery-model").is_none());
+    }
+
+    #[test]
+    fn flags_family_fallbacks_as_guessed() {
+        let prices = PriceTable::default();
+        let guessed = |model: &str| prices.resolve(model).map(|found| found.guessed);
+        assert_eq!(guessed("claude-sonnet-4-20250514"), Some(false));
+        assert_eq!(guessed("claude-sonnet-4-5-20250929"), Some(false));
+        assert_eq!(
+            guessed("anthropic.claude-3-5-haiku-20241022-v1:0"),
+            Some(false)
+        );
+        assert_eq!(guessed("gpt-4o-2024-08-06"), Some(false));
+        assert_eq!(guessed("claude-sonnet-4-7"), Some(true));
+        assert_eq!(guessed("gpt-5.2"), Some(true));
+        assert_eq!(guessed("mystery-model"), None);
+
+        let overridden = PriceTable::new(vec![PricingOverrideConfig {
+            model: "claude-sonnet-4".to_string(),
+            input: None,
+            output: None,
+            cache_creation: None,
+            cache_read: None,
+            reasoning: None,
+        }]);
+        assert_eq!(
+            overridden
+                .resolve("claude-sonnet-4-7")
+                .map(|found| found.guessed),
+            Some(false)
+        );
+    }
+}
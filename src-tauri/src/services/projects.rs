@@ -0,0 +1,138 @@
+//! Project attribution for local cost: working directories are collapsed to
+//! their repository root and optionally tagged with a team or client from the
+//! `projectTags` section of the config file.
+
+use crate::services::config::{self, ProjectTagConfig};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Label for usage whose log entry carries no working directory.
+pub const UNKNOWN_PROJECT: &str = "unknown";
+
+static REPOSITORY_ROOTS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+fn repository_roots() -> &'static Mutex<HashMap<String, String>> {
+    REPOSITORY_ROOTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The nearest ancestor of `cwd` containing `.git` (a directory, or a file for
+/// worktrees and submodules). Paths outside a repository, or that no longer
+/// exist, are kept as they are. Claude's encoded project directory names are
+/// not absolute paths and are returned unchanged.
+pub fn repository_root(cwd: &str) -> String {
+    let cwd = cwd.trim();
+    if cwd.is_empty() {
+        return UNKNOWN_PROJECT.to_string();
+    }
+    if !Path::new(cwd).is_absolute() {
+        return cwd.to_string();
+    }
+    if let Ok(cache) = repository_roots().lock() {
+        if let Some(root) = cache.get(cwd) {
+            return root.clone();
+        }
+    }
+
+    let root = Path::new(cwd)
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_else(|| cwd.to_string());
+    if let Ok(mut cache) = repository_roots().lock() {
+        cache.insert(cwd.to_string(), root.clone());
+    }
+    root
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~") {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\') => {
+            dirs::home_dir()
+                .map(|home| home.join(rest.trim_start_matches(['/', '\\'])))
+                .unwrap_or_else(|| PathBuf::from(path))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Path-prefix → tag rules; the rule with the most path components wins.
+pub struct ProjectTagger {
+    rules: Vec<(PathBuf, String)>,
+}
+
+impl ProjectTagger {
+    pub fn new(rules: &[ProjectTagConfig]) -> Self {
+        let mut rules: Vec<(PathBuf, String)> = rules
+            .iter()
+            .filter(|rule| !rule.path.trim().is_empty() && !rule.tag.trim().is_empty())
+            .map(|rule| (expand_home(rule.path.trim()), rule.tag.trim().to_string()))
+            .collect();
+        rules.sort_by_key(|(path, _)| std::cmp::Reverse(path.components().count()));
+        Self { rules }
+    }
+
+    pub fn from_config() -> Self {
+        Self::new(&config::load_config().project_tags)
+    }
+
+    pub fn tag_for(&self, project: &str) -> Option<String> {
+        let project = Path::new(project);
+        if !project
+            .components()
+            .any(|component| matches!(component, Component::RootDir | Component::Prefix(_)))
+        {
+            return None;
+        }
+        self.rules
+            .iter()
+            .find(|(prefix, _)| project.starts_with(prefix))
+            .map(|(_, tag)| tag.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(path: &str, tag: &str) -> ProjectTagConfig {
+        ProjectTagConfig {
+            path: path.to_string(),
+            tag: tag.to_string(),
+        }
+    }
+
+    #[test]
+    fn most_specific_tag_wins() {
+        let tagger = ProjectTagger::new(&[
+            rule("/work", "internal"),
+            rule("/work/clients/acme", "client:acme"),
+        ]);
+        assert_eq!(
+            tagger.tag_for("/work/clients/acme/api").as_deref(),
+            Some("client:acme")
+        );
+        assert_eq!(
+            tagger.tag_for("/work/billing-service").as_deref(),
+            Some("internal")
+        );
+        // Prefixes match whole components only.
+        assert_eq!(tagger.tag_for("/workspace/app"), None);
+        assert_eq!(tagger.tag_for("-work-app"), None);
+    }
+
+    #[test]
+    fn finds_repository_root_from_nested_directory() {
+        let base = std::env::temp_dir().join(format!("quotabar-projects-{}", std::process::id()));
+        let nested = base.join("repo/crates/core");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(base.join("repo/.git")).unwrap();
+
+        let root = repository_root(&nested.to_string_lossy());
+        assert_eq!(root, base.join("repo").to_string_lossy());
+        assert_eq!(repository_root(""), UNKNOWN_PROJECT);
+        assert_eq!(repository_root("-Users-dev-app"), "-Users-dev-app");
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
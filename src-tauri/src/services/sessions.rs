@@ -0,0 +1,493 @@
+//! Per-session cost of Claude Code and Codex conversations.
+//!
+//! The incremental cost index already keeps the session id of every hourly
+//! bucket, so sessions are rebuilt by grouping buckets instead of collapsing
+//! them into ranges as `summarize_cost` does. Totals cover the part of each
+//! session inside the requested range.
+
+use crate::services::cost::CostTokenBreakdown;
+use crate::services::cost_index::{self, BucketKey, BucketTotals, IndexedSource};
+use crate::services::cost_range::RangeTimezone;
+use crate::services::cost_series::parse_bound;
+use crate::services::pricing::PriceTable;
+use crate::services::projects::{self, ProjectTagger};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+const DEFAULT_DAYS: i64 = 7;
+const MAX_DAYS: i64 = 366;
+const DEFAULT_LIMIT: usize = 100;
+
+/// Sorting and filtering for [`list_sessions`]; every field is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionQuery {
+    /// `cost` (default), `tokens`, `start`, `end` or `duration`.
+    pub sort: Option<String>,
+    /// `desc` (default) or `asc`.
+    pub order: Option<String>,
+    /// Case-insensitive substring of the working directory, repository or
+    /// project tag.
+    pub project: Option<String>,
+    /// Case-insensitive substring of any model used in the session.
+    pub model: Option<String>,
+    pub min_cost_usd: Option<f64>,
+    /// Maximum sessions returned; 100 when omitted, 0 for all.
+    pub limit: Option<usize>,
+    /// Zone for `YYYY-MM-DD` bounds; local time when omitted.
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionList {
+    pub source: String,
+    pub since: String,
+    pub until: String,
+    pub generated_at: String,
+    /// Sessions matching the filters, before `limit` is applied.
+    pub matched: usize,
+    /// Cost of all matching sessions, before `limit` is applied.
+    pub cost_usd: f64,
+    pub sessions: Vec<SessionSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub source: String,
+    pub session_id: String,
+    pub start: String,
+    pub end: String,
+    pub duration_minutes: i64,
+    /// Working directory with the most tokens in the session.
+    pub project: String,
+    pub repository: String,
+    pub tag: Option<String>,
+    pub entries: i64,
+    pub tokens: CostTokenBreakdown,
+    /// Cost of the priced models; see `unpriced_models`.
+    pub cost_usd: f64,
+    /// Most expensive model first.
+    pub models: Vec<SessionModel>,
+    pub unpriced_models: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionModel {
+    pub model: String,
+    pub entries: i64,
+    pub tokens: CostTokenBreakdown,
+    pub cost_usd: Option<f64>,
+    /// Share of the session's priced cost, `0..=1`.
+    pub cost_share: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionSort {
+    Cost,
+    Tokens,
+    Start,
+    End,
+    Duration,
+}
+
+impl SessionSort {
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("") | Some("cost") => Ok(Self::Cost),
+            Some("tokens") => Ok(Self::Tokens),
+            Some("start") => Ok(Self::Start),
+            Some("end") => Ok(Self::End),
+            Some("duration") => Ok(Self::Duration),
+            Some(other) => Err(format!(
+                "Unsupported sort '{other}'; use cost, tokens, start, end or duration"
+            )),
+        }
+    }
+}
+
+/// Buckets of one session, per model and per working directory.
+#[derive(Debug, Default)]
+struct SessionTotals {
+    first_at: Option<DateTime<Utc>>,
+    last_at: Option<DateTime<Utc>>,
+    models: HashMap<String, BucketTotals>,
+    projects: HashMap<String, i64>,
+}
+
+impl SessionTotals {
+    fn add(&mut self, key: &BucketKey, totals: &BucketTotals) {
+        let first_at = totals.first_at.unwrap_or_else(|| key.start());
+        let last_at = totals.last_at.unwrap_or(first_at);
+        self.first_at = Some(self.first_at.map_or(first_at, |first| first.min(first_at)));
+        self.last_at = Some(self.last_at.map_or(last_at, |last| last.max(last_at)));
+        let model = self.models.entry(key.model.clone()).or_default();
+        model.tokens.accumulate(&totals.tokens);
+        model.entries += totals.entries;
+        *self.projects.entry(key.project.clone()).or_default() += totals.tokens.total_tokens;
+    }
+
+    fn summarize(
+        self,
+        source: IndexedSource,
+        session_id: String,
+        tagger: &ProjectTagger,
+        prices: &PriceTable,
+    ) -> SessionSummary {
+        let first_at = self.first_at.unwrap_or_default();
+        let last_at = self.last_at.unwrap_or(first_at);
+        let project = self
+            .projects
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+            .map(|(project, _)| project)
+            .unwrap_or_default();
+        let repository = projects::repository_root(&project);
+        let tag = tagger
+            .tag_for(&repository)
+            .or_else(|| tagger.tag_for(&project));
+
+        let mut tokens = CostTokenBreakdown::default();
+        let mut entries = 0;
+        let mut cost_usd = 0.0;
+        let mut unpriced_models = Vec::new();
+        let mut models: Vec<SessionModel> = self
+            .models
+            .into_iter()
+            .map(|(model, totals)| {
+                let cost = prices.cost_usd(&model, &totals.tokens);
+                match cost {
+                    Some(cost) => cost_usd += cost,
+                    None => unpriced_models.push(model.clone()),
+                }
+                tokens.accumulate(&totals.tokens);
+                entries += totals.entries;
+                SessionModel {
+                    model,
+                    entries: totals.entries,
+                    tokens: totals.tokens,
+                    cost_usd: cost,
+                    cost_share: None,
+                }
+            })
+            .collect();
+        for model in &mut models {
+            model.cost_share = model
+                .cost_usd
+                .filter(|_| cost_usd > 0.0)
+                .map(|cost| cost / cost_usd);
+        }
+        models.sort_by(|a, b| {
+            b.cost_usd
+                .unwrap_or(0.0)
+                .total_cmp(&a.cost_usd.unwrap_or(0.0))
+                .then_with(|| a.model.cmp(&b.model))
+        });
+        unpriced_models.sort();
+
+        SessionSummary {
+            source: source.as_str().to_string(),
+            session_id,
+            start: first_at.to_rfc3339(),
+            end: last_at.to_rfc3339(),
+            duration_minutes: (last_at - first_at).num_minutes(),
+            project,
+            repository,
+            tag,
+            entries,
+            tokens,
+            cost_usd,
+            models,
+            unpriced_models,
+        }
+    }
+}
+
+fn contains(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(needle)
+}
+
+fn needle(value: Option<&str>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+}
+
+/// Filter, sort and count `sessions`; returns the matching count and cost
+/// before `limit` is applied.
+fn select_sessions(
+    sessions: Vec<SessionSummary>,
+    query: &SessionQuery,
+) -> Result<(Vec<SessionSummary>, usize, f64), String> {
+    let sort = SessionSort::parse(query.sort.as_deref())?;
+    let ascending = match query
+        .order
+        .as_deref()
+        .map(|order| order.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some("desc") => false,
+        Some("asc") => true,
+        Some(other) => return Err(format!("Unsupported order '{other}'; use asc or desc")),
+    };
+    let project = needle(query.project.as_deref());
+    let model = needle(query.model.as_deref());
+    let min_cost = query.min_cost_usd.unwrap_or(0.0);
+
+    let mut sessions: Vec<SessionSummary> = sessions
+        .into_iter()
+        .filter(|session| session.cost_usd >= min_cost)
+        .filter(|session| {
+            project.as_deref().is_none_or(|needle| {
+                contains(&session.project, needle)
+                    || contains(&session.repository, needle)
+                    || session
+                        .tag
+                        .as_deref()
+                        .is_some_and(|tag| contains(tag, needle))
+            })
+        })
+        .filter(|session| {
+            model.as_deref().is_none_or(|needle| {
+                session
+                    .models
+                    .iter()
+                    .any(|entry| contains(&entry.model, needle))
+            })
+        })
+        .collect();
+
+    sessions.sort_by(|a, b| {
+        let ordering = match sort {
+            SessionSort::Cost => a.cost_usd.total_cmp(&b.cost_usd),
+            SessionSort::Tokens => a.tokens.total_tokens.cmp(&b.tokens.total_tokens),
+            // RFC 3339 strings in UTC sort chronologically.
+            SessionSort::Start => a.start.cmp(&b.start),
+            SessionSort::End => a.end.cmp(&b.end),
+            SessionSort::Duration => a.duration_minutes.cmp(&b.duration_minutes),
+        };
+        let ordering = if ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        };
+        match ordering {
+            Ordering::Equal => a.session_id.cmp(&b.session_id),
+            ordering => ordering,
+        }
+    });
+
+    let matched = sessions.len();
+    let cost_usd = sessions.iter().map(|session| session.cost_usd).sum();
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit > 0 {
+        sessions.truncate(limit);
+    }
+    Ok((sessions, matched, cost_usd))
+}
+
+pub fn build_session_list(
+    source: &str,
+    since: Option<&str>,
+    until: Option<&str>,
+    query: &SessionQuery,
+    force: bool,
+) -> Result<SessionList, String> {
+    let sources: Vec<IndexedSource> = if source.trim().eq_ignore_ascii_case("all") {
+        IndexedSource::ALL.to_vec()
+    } else {
+        vec![IndexedSource::from_name(source).ok_or_else(|| {
+            format!("Sessions are only available for Claude Code and Codex, not '{source}'")
+        })?]
+    };
+    let timezone = query.timezone.as_deref();
+    let zone = RangeTimezone::parse(timezone).ok_or_else(|| {
+        format!(
+            "Unsupported timezone '{}'; use local, UTC or an offset like +02:00",
+            timezone.unwrap_or_default()
+        )
+    })?;
+    let now = Utc::now();
+    let bound = |value: Option<&str>, end_of_day: bool| {
+        value
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| parse_bound(value, &zone, end_of_day))
+            .transpose()
+    };
+    let until = bound(until, true)?.unwrap_or(now);
+    let since = bound(since, false)?.unwrap_or_else(|| {
+        zone.start_of_day(zone.date_of(now) - ChronoDuration::days(DEFAULT_DAYS - 1))
+    });
+    if until <= since {
+        return Err("Range end must be after its start".to_string());
+    }
+    if until - since > ChronoDuration::days(MAX_DAYS) {
+        return Err(format!("Range is too long (max {MAX_DAYS} days)"));
+    }
+
+    let tagger = ProjectTagger::from_config();
+    let prices = PriceTable::load();
+    let mut sessions = Vec::new();
+    for indexed in &sources {
+        let mut grouped: HashMap<String, SessionTotals> = HashMap::new();
+        cost_index::for_each_bucket(*indexed, since, until, force, |key, totals| {
+            if !key.session_id.is_empty() {
+                grouped
+                    .entry(key.session_id.clone())
+                    .or_default()
+                    .add(key, totals);
+            }
+        })?;
+        sessions.extend(
+            grouped.into_iter().map(|(session_id, totals)| {
+                totals.summarize(*indexed, session_id, &tagger, &prices)
+            }),
+        );
+    }
+    let (sessions, matched, cost_usd) = select_sessions(sessions, query)?;
+
+    Ok(SessionList {
+        source: match sources.as_slice() {
+            [single] => single.as_str().to_string(),
+            _ => "all".to_string(),
+        },
+        since: since.to_rfc3339(),
+        until: until.to_rfc3339(),
+        generated_at: now.to_rfc3339(),
+        matched,
+        cost_usd,
+        sessions,
+    })
+}
+
+pub async fn list_sessions(
+    source: String,
+    since: Option<String>,
+    until: Option<String>,
+    query: SessionQuery,
+    force: bool,
+) -> Result<SessionList, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        build_session_list(&source, since.as_deref(), until.as_deref(), &query, force)
+    })
+    .await
+    .map_err(|err| format!("Session list task failed: {err}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(hour: i64, model: &str, project: &str, output: i64) -> (BucketKey, BucketTotals) {
+        let key = BucketKey {
+            hour,
+            model: model.to_string(),
+            project: project.to_string(),
+            session_id: "s1".to_string(),
+        };
+        let tokens = CostTokenBreakdown {
+            output_tokens: output,
+            total_tokens: output,
+            ..Default::default()
+        };
+        let start = key.start();
+        let totals = BucketTotals {
+            tokens,
+            entries: 1,
+            first_at: Some(start + ChronoDuration::minutes(10)),
+            last_at: Some(start + ChronoDuration::minutes(40)),
+        };
+        (key, totals)
+    }
+
+    fn summary(id: &str, cost: f64, minutes: i64, project: &str) -> SessionSummary {
+        SessionSummary {
+            source: "claude".to_string(),
+            session_id: id.to_string(),
+            start: "2026-05-01T10:00:00+00:00".to_string(),
+            end: "2026-05-01T11:00:00+00:00".to_string(),
+            duration_minutes: minutes,
+            project: project.to_string(),
+            repository: project.to_string(),
+            tag: None,
+            entries: 1,
+            tokens: CostTokenBreakdown::default(),
+            cost_usd: cost,
+            models: Vec::new(),
+            unpriced_models: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn groups_buckets_into_a_session_with_its_model_mix() {
+        let mut session = SessionTotals::default();
+        for (key, totals) in [
+            bucket(490_000, "claude-sonnet-4-5", "/work/app", 1_000_000),
+            bucket(490_001, "claude-sonnet-4-5", "/work/app", 1_000_000),
+            bucket(490_001, "in-house-model", "/work/lib", 500_000),
+        ] {
+            session.add(&key, &totals);
+        }
+        let summary = session.summarize(
+            IndexedSource::Claude,
+            "s1".to_string(),
+            &ProjectTagger::new(&[]),
+            &PriceTable::default(),
+        );
+
+        assert_eq!(summary.project, "/work/app");
+        assert_eq!(summary.duration_minutes, 90);
+        assert_eq!(summary.entries, 3);
+        assert_eq!(summary.tokens.output_tokens, 2_500_000);
+        assert_eq!(summary.models[0].model, "claude-sonnet-4-5");
+        assert_eq!(summary.models[0].cost_share, Some(1.0));
+        assert_eq!(summary.unpriced_models, vec!["in-house-model".to_string()]);
+        assert!((summary.cost_usd - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn filters_sorts_and_limits_sessions() {
+        let sessions = vec![
+            summary("a", 4.0, 30, "/work/app"),
+            summary("b", 12.0, 10, "/work/App-Server"),
+            summary("c", 0.5, 90, "/work/app"),
+            summary("d", 20.0, 5, "/work/other"),
+        ];
+        let query = SessionQuery {
+            project: Some("app".to_string()),
+            min_cost_usd: Some(1.0),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let (selected, matched, cost) = select_sessions(sessions.clone(), &query).unwrap();
+        assert_eq!((matched, cost), (2, 16.0));
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].session_id, "b");
+
+        let query = SessionQuery {
+            sort: Some("duration".to_string()),
+            order: Some("asc".to_string()),
+            limit: Some(0),
+            ..Default::default()
+        };
+        let (selected, _, _) = select_sessions(sessions, &query).unwrap();
+        let ids: Vec<&str> = selected
+            .iter()
+            .map(|session| session.session_id.as_str())
+            .collect();
+        assert_eq!(ids, ["d", "b", "a", "c"]);
+
+        let query = SessionQuery {
+            sort: Some("price".to_string()),
+            ..Default::default()
+        };
+        assert!(select_sessions(Vec::new(), &query).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+//! Subscription value report: the API-equivalent cost of local Claude Code
+//! and Codex usage per calendar month, compared with the monthly price of the
+//! detected plan.
+//!
+//! Local logs only cover this machine, so the usage is attributed to a single
+//! seat: `seat_value_multiple` compares it with one seat's price and
+//! `value_multiple` with the price of the whole subscription.
+
+use crate::services::config::{self, PlanPriceConfig};
+use crate::services::cost;
+use crate::services::cost_index::IndexedSource;
+use crate::services::cost_range::RangeTimezone;
+use crate::services::pricing::PriceTable;
+use crate::services::{claude, codex};
+use chrono::{Datelike, Duration as ChronoDuration, Months, NaiveDate, Utc};
+use serde::Serialize;
+
+const DEFAULT_MONTHS: u32 = 3;
+const MAX_MONTHS: u32 = 12;
+
+pub const PRICE_ORIGIN_CONFIG: &str = "config";
+pub const PRICE_ORIGIN_CATALOG: &str = "catalog";
+
+/// List prices in USD per seat and month. `planPrices` in the config file
+/// overrides these.
+const PLAN_CATALOG: [(&str, &str, f64); 6] = [
+    ("claude", "pro", 20.0),
+    ("claude", "max_5x", 100.0),
+    ("claude", "max_20x", 200.0),
+    ("codex", "plus", 20.0),
+    ("codex", "pro", 200.0),
+    ("codex", "team", 30.0),
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionValueReport {
+    pub generated_at: String,
+    pub currency: String,
+    pub sources: Vec<SubscriptionValue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionValue {
+    pub source: String,
+    pub display_name: String,
+    pub plan: Option<String>,
+    /// `config` or `catalog`; `None` when no price is known for the plan.
+    pub price_origin: Option<String>,
+    pub price_per_seat: Option<f64>,
+    pub seats: u32,
+    pub monthly_price: Option<f64>,
+    pub months: Vec<SubscriptionMonth>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionMonth {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub since: String,
+    pub until: String,
+    /// The current month, summarized up to today.
+    pub partial: bool,
+    pub api_cost: f64,
+    /// Month-to-date cost extrapolated to the whole month.
+    pub projected_cost: Option<f64>,
+    pub value_multiple: Option<f64>,
+    pub seat_value_multiple: Option<f64>,
+    pub unpriced_entries: i64,
+}
+
+struct PlanPrice {
+    per_seat: f64,
+    seats: u32,
+    origin: &'static str,
+}
+
+/// Config entries for the exact plan win over source-wide config entries,
+/// which win over the built-in catalog.
+fn resolve_price(
+    source: &str,
+    plan: Option<&str>,
+    overrides: &[PlanPriceConfig],
+) -> Option<PlanPrice> {
+    let matches_source =
+        |entry: &&PlanPriceConfig| entry.source.trim().eq_ignore_ascii_case(source);
+    let exact = plan.and_then(|plan| {
+        overrides.iter().filter(matches_source).find(|entry| {
+            entry
+                .plan
+                .as_deref()
+                .is_some_and(|candidate| candidate.trim().eq_ignore_ascii_case(plan))
+        })
+    });
+    let source_wide = || {
+        overrides
+            .iter()
+            .filter(matches_source)
+            .find(|entry| entry.plan.is_none())
+    };
+    if let Some(entry) = exact.or_else(source_wide) {
+        return Some(PlanPrice {
+            per_seat: entry.monthly_price,
+            seats: entry.seats.max(1),
+            origin: PRICE_ORIGIN_CONFIG,
+        });
+    }
+    let plan = plan?;
+    PLAN_CATALOG
+        .iter()
+        .find(|(catalog_source, catalog_plan, _)| {
+            *catalog_source == source && catalog_plan.eq_ignore_ascii_case(plan)
+        })
+        .map(|(_, _, price)| PlanPrice {
+            per_seat: *price,
+            seats: 1,
+            origin: PRICE_ORIGIN_CATALOG,
+        })
+}
+
+/// `(first day, last day summarized, days in month)` of the last `count`
+/// calendar months, oldest first. The current month ends today.
+fn month_periods(today: NaiveDate, count: u32) -> Vec<(NaiveDate, NaiveDate, i64)> {
+    let current = today.with_day(1).unwrap_or(today);
+    (0..count)
+        .rev()
+        .filter_map(|back| {
+            let since = current.checked_sub_months(Months::new(back))?;
+            let next = since.checked_add_months(Months::new(1))?;
+            let days = (next - since).num_days();
+            let until = if back == 0 {
+                today
+            } else {
+                next - ChronoDuration::days(1)
+            };
+            Some((since, until, days))
+        })
+        .collect()
+}
+
+fn ratio(value: f64, price: Option<f64>) -> Option<f64> {
+    price
+        .filter(|price| *price > 0.0)
+        .map(|price| value / price)
+}
+
+fn build_source_value(
+    source: IndexedSource,
+    display_name: &str,
+    plan: Option<String>,
+    overrides: &[PlanPriceConfig],
+    today: NaiveDate,
+    months: u32,
+    force: bool,
+) -> SubscriptionValue {
+    let price = resolve_price(source.as_str(), plan.as_deref(), overrides);
+    let per_seat = price.as_ref().map(|price| price.per_seat);
+    let seats = price.as_ref().map_or(1, |price| price.seats);
+    let monthly_price = per_seat.map(|per_seat| per_seat * f64::from(seats));
+    let mut value = SubscriptionValue {
+        source: source.as_str().to_string(),
+        display_name: display_name.to_string(),
+        plan,
+        price_origin: price.as_ref().map(|price| price.origin.to_string()),
+        price_per_seat: per_seat,
+        seats,
+        monthly_price,
+        months: Vec::new(),
+        error: None,
+    };
+
+    let zone = RangeTimezone::Local;
+    let prices = PriceTable::load();
+    for (since, until, days_in_month) in month_periods(today, months) {
+        let key = since.format("%Y-%m").to_string();
+        let summary = match cost::summarize_indexed_range(
+            source,
+            &key,
+            &key,
+            (since, until),
+            &zone,
+            &prices,
+            force,
+        ) {
+            Ok(summary) => summary,
+            Err(error) => {
+                value.error = Some(error);
+                break;
+            }
+        };
+        let api_cost = summary.cost_usd.unwrap_or(0.0);
+        let partial = until < since + ChronoDuration::days(days_in_month - 1);
+        let projected_cost = partial.then(|| {
+            let elapsed = (until - since).num_days() + 1;
+            api_cost / elapsed as f64 * days_in_month as f64
+        });
+        value.months.push(SubscriptionMonth {
+            month: key,
+            since: since.to_string(),
+            until: until.to_string(),
+            partial,
+            api_cost,
+            projected_cost,
+            value_multiple: ratio(api_cost, monthly_price),
+            seat_value_multiple: ratio(api_cost, per_seat),
+            unpriced_entries: summary.skipped_entries,
+        });
+    }
+    value
+}
+
+pub async fn get_subscription_value(
+    months: Option<u32>,
+    force: bool,
+) -> Result<SubscriptionValueReport, String> {
+    let months = months.unwrap_or(DEFAULT_MONTHS).clamp(1, MAX_MONTHS);
+    let codex_plan = codex::fetch_codex_info()
+        .await
+        .plan_type
+        .or_else(codex::last_known_plan_type)
+        .map(|plan| plan.to_ascii_lowercase());
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let claude_plan = claude::claude_plan();
+        let overrides = config::load_config().plan_prices;
+        let today = chrono::Local::now().date_naive();
+        let sources = vec![
+            build_source_value(
+                IndexedSource::Claude,
+                "Claude Code",
+                claude_plan,
+                &overrides,
+                today,
+                months,
+                force,
+            ),
+            build_source_value(
+                IndexedSource::Codex,
+                "Codex",
+                codex_plan,
+                &overrides,
+                today,
+                months,
+                force,
+            ),
+        ];
+        SubscriptionValueReport {
+            generated_at: Utc::now().to_rfc3339(),
+            currency: "USD".to_string(),
+            sources,
+        }
+    })
+    .await
+    .map_err(|err| format!("Subscription value task failed: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn price(source: &str, plan: Option<&str>, monthly_price: f64, seats: u32) -> PlanPriceConfig {
+        PlanPriceConfig {
+            source: source.to_string(),
+            plan: plan.map(str::to_string),
+            monthly_price,
+            seats,
+        }
+    }
+
+    #[test]
+    fn config_prices_override_the_catalog() {
+        let catalog = resolve_price("claude", Some("max_20x"), &[]).unwrap();
+        assert_eq!(
+            (catalog.per_seat, catalog.origin),
+            (200.0, PRICE_ORIGIN_CATALOG)
+        );
+
+        let overrides = [
+            price("codex", None, 25.0, 4),
+            price("codex", Some("pro"), 180.0, 1),
+        ];
+        let exact = resolve_price("codex", Some("pro"), &overrides).unwrap();
+        assert_eq!((exact.per_seat, exact.seats), (180.0, 1));
+        let source_wide = resolve_price("codex", Some("team"), &overrides).unwrap();
+        assert_eq!((source_wide.per_seat, source_wide.seats), (25.0, 4));
+        assert!(resolve_price("claude", None, &overrides).is_none());
+        assert!(resolve_price("claude", Some("free"), &[]).is_none());
+    }
+
+    #[test]
+    fn months_end_today_and_cover_whole_previous_months() {
+        let periods = month_periods(day("2026-03-10"), 3);
+        assert_eq!(
+            periods,
+            vec![
+                (day("2026-01-01"), day("2026-01-31"), 31),
+                (day("2026-02-01"), day("2026-02-28"), 28),
+                (day("2026-03-01"), day("2026-03-10"), 31),
+            ]
+        );
+        assert_eq!(ratio(300.0, Some(100.0)), Some(3.0));
+        assert_eq!(ratio(300.0, Some(0.0)), None);
+    }
+}
@@ -1,6 +1,7 @@
+use std::collections::HashMap;
 use std::sync::{mpsc, Arc, Mutex};
 
-use super::tray_icon;
+use super::{config, tray_icon};
 use chrono::Local;
 use serde::{Deserialize, Serialize};
 use tauri::{
@@ -9,6 +10,7 @@ use tauri::{
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager, Position, State,
 };
+use tray_icon::TrayIconStyle;
 
 const ICON_SIZE: u32 = 44;
 const TRAY_SERVICE_ACTIVATED_EVENT: &str = "tray-service-activated";
@@ -17,19 +19,52 @@ const TRAY_HIDDEN_TOOLTIP_SUFFIX: &str = "hidden";
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct TraySnapshot {
     percentage: Option<u8>,
+    windows: Option<TrayWindows>,
+    style: TrayIconStyle,
     visible: bool,
 }
 
+/// Used percent of a service's short (session) and long (weekly) quota
+/// windows. With both known the tray draws concentric rings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayWindows {
+    pub session: Option<u8>,
+    pub weekly: Option<u8>,
+}
+
+impl TrayWindows {
+    fn clamped(self) -> Self {
+        Self {
+            session: self.session.map(|value| value.min(100)),
+            weekly: self.weekly.map(|value| value.min(100)),
+        }
+    }
+
+    fn both(self) -> Option<(u8, u8)> {
+        self.session.zip(self.weekly)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct CustomTrayRuntime {
+    generation: u64,
+    snapshot: Option<TraySnapshot>,
+}
+
 #[derive(Default)]
 struct TrayRuntimeState {
     claude_generation: u64,
     codex_generation: u64,
     cursor_generation: u64,
     antigravity_generation: u64,
+    windsurf_generation: u64,
     claude_snapshot: Option<TraySnapshot>,
     codex_snapshot: Option<TraySnapshot>,
     cursor_snapshot: Option<TraySnapshot>,
     antigravity_snapshot: Option<TraySnapshot>,
+    windsurf_snapshot: Option<TraySnapshot>,
+    custom: HashMap<String, CustomTrayRuntime>,
 }
 
 impl TrayRuntimeState {
@@ -51,6 +86,10 @@ impl TrayRuntimeState {
                 self.antigravity_generation = self.antigravity_generation.saturating_add(1);
                 self.antigravity_generation
             }
+            TrayService::Windsurf => {
+                self.windsurf_generation = self.windsurf_generation.saturating_add(1);
+                self.windsurf_generation
+            }
         };
         generation
     }
@@ -61,6 +100,7 @@ impl TrayRuntimeState {
             TrayService::Codex => self.codex_generation,
             TrayService::Cursor => self.cursor_generation,
             TrayService::Antigravity => self.antigravity_generation,
+            TrayService::Windsurf => self.windsurf_generation,
         }
     }
 
@@ -70,6 +110,7 @@ impl TrayRuntimeState {
             TrayService::Codex => self.codex_snapshot,
             TrayService::Cursor => self.cursor_snapshot,
             TrayService::Antigravity => self.antigravity_snapshot,
+            TrayService::Windsurf => self.windsurf_snapshot,
         }
     }
 
@@ -79,6 +120,45 @@ impl TrayRuntimeState {
             TrayService::Codex => self.codex_snapshot = Some(snapshot),
             TrayService::Cursor => self.cursor_snapshot = Some(snapshot),
             TrayService::Antigravity => self.antigravity_snapshot = Some(snapshot),
+            TrayService::Windsurf => self.windsurf_snapshot = Some(snapshot),
+        }
+    }
+
+    fn bump_target_generation(&mut self, target: &TrayTarget) -> u64 {
+        match target {
+            TrayTarget::Service(service) => self.bump_generation(*service),
+            TrayTarget::Custom { id, .. } => {
+                let entry = self.custom.entry(id.clone()).or_default();
+                entry.generation = entry.generation.saturating_add(1);
+                entry.generation
+            }
+        }
+    }
+
+    fn target_generation(&self, target: &TrayTarget) -> u64 {
+        match target {
+            TrayTarget::Service(service) => self.generation(*service),
+            TrayTarget::Custom { id, .. } => self
+                .custom
+                .get(id)
+                .map(|entry| entry.generation)
+                .unwrap_or_default(),
+        }
+    }
+
+    fn target_snapshot(&self, target: &TrayTarget) -> Option<TraySnapshot> {
+        match target {
+            TrayTarget::Service(service) => self.snapshot(*service),
+            TrayTarget::Custom { id, .. } => self.custom.get(id).and_then(|entry| entry.snapshot),
+        }
+    }
+
+    fn set_target_snapshot(&mut self, target: &TrayTarget, snapshot: TraySnapshot) {
+        match target {
+            TrayTarget::Service(service) => self.set_snapshot(*service, snapshot),
+            TrayTarget::Custom { id, .. } => {
+                self.custom.entry(id.clone()).or_default().snapshot = Some(snapshot);
+            }
         }
     }
 }
@@ -95,6 +175,7 @@ pub enum TrayService {
     Codex,
     Cursor,
     Antigravity,
+    Windsurf,
 }
 
 impl TrayService {
@@ -104,6 +185,7 @@ impl TrayService {
             Self::Codex => "Codex",
             Self::Cursor => "Cursor",
             Self::Antigravity => "Antigravity",
+            Self::Windsurf => "Windsurf",
         }
     }
 
@@ -113,6 +195,7 @@ impl TrayService {
             Self::Codex => "codex-tray",
             Self::Cursor => "cursor-tray",
             Self::Antigravity => "antigravity-tray",
+            Self::Windsurf => "windsurf-tray",
         }
     }
 
@@ -122,6 +205,7 @@ impl TrayService {
             Self::Codex => "codex-show",
             Self::Cursor => "cursor-show",
             Self::Antigravity => "antigravity-show",
+            Self::Windsurf => "windsurf-show",
         }
     }
 
@@ -131,6 +215,7 @@ impl TrayService {
             Self::Codex => "codex",
             Self::Cursor => "cursor",
             Self::Antigravity => "antigravity",
+            Self::Windsurf => "windsurf",
         }
     }
 
@@ -140,6 +225,7 @@ impl TrayService {
             Self::Codex => "codex-quit",
             Self::Cursor => "cursor-quit",
             Self::Antigravity => "antigravity-quit",
+            Self::Windsurf => "windsurf-quit",
         }
     }
 
@@ -149,6 +235,65 @@ impl TrayService {
             Self::Codex => tray_icon::TrayIconIdentity::Codex,
             Self::Cursor => tray_icon::TrayIconIdentity::Cursor,
             Self::Antigravity => tray_icon::TrayIconIdentity::Antigravity,
+            Self::Windsurf => tray_icon::TrayIconIdentity::Windsurf,
+        }
+    }
+}
+
+/// A tray entry: one of the built-in services, or a custom provider from the
+/// user config (command plugin) keyed by its config id.
+#[derive(Clone)]
+enum TrayTarget {
+    Service(TrayService),
+    Custom { id: String, label: String },
+}
+
+impl From<TrayService> for TrayTarget {
+    fn from(service: TrayService) -> Self {
+        Self::Service(service)
+    }
+}
+
+impl TrayTarget {
+    fn label(&self) -> String {
+        match self {
+            Self::Service(service) => service.label().to_string(),
+            Self::Custom { label, .. } => label.clone(),
+        }
+    }
+
+    fn tray_id(&self) -> String {
+        match self {
+            Self::Service(service) => service.tray_id().to_string(),
+            Self::Custom { id, .. } => format!("custom-{id}-tray"),
+        }
+    }
+
+    fn show_menu_id(&self) -> String {
+        match self {
+            Self::Service(service) => service.show_menu_id().to_string(),
+            Self::Custom { id, .. } => format!("custom-{id}-show"),
+        }
+    }
+
+    fn quit_menu_id(&self) -> String {
+        match self {
+            Self::Service(service) => service.quit_menu_id().to_string(),
+            Self::Custom { id, .. } => format!("custom-{id}-quit"),
+        }
+    }
+
+    fn icon_identity(&self) -> tray_icon::TrayIconIdentity {
+        match self {
+            Self::Service(service) => service.icon_identity(),
+            Self::Custom { .. } => tray_icon::TrayIconIdentity::Custom,
+        }
+    }
+
+    fn service(&self) -> Option<TrayService> {
+        match self {
+            Self::Service(service) => Some(*service),
+            Self::Custom { .. } => None,
         }
     }
 }
@@ -230,41 +375,98 @@ fn toggle_main_window(app: &AppHandle) {
     }
 }
 
-fn format_tooltip(service: TrayService, percentage: Option<u8>) -> String {
+fn format_tooltip(target: impl Into<TrayTarget>, percentage: Option<u8>) -> String {
+    let label = target.into().label();
     match percentage {
-        Some(value) => format!("{}: {}% used", service.label(), value.min(100)),
-        None => format!("{}: unavailable", service.label()),
+        Some(value) => format!("{label}: {}% used", value.min(100)),
+        None => format!("{label}: unavailable"),
+    }
+}
+
+fn format_windows_tooltip(target: impl Into<TrayTarget>, session: u8, weekly: u8) -> String {
+    format!(
+        "{}: session {}% · weekly {}% used",
+        target.into().label(),
+        session.min(100),
+        weekly.min(100)
+    )
+}
+
+/// The `tray.style` setting from `config.json`.
+fn configured_icon_style() -> TrayIconStyle {
+    let tray = config::load_config().tray;
+    if tray.style.trim().eq_ignore_ascii_case("template") {
+        TrayIconStyle::Template {
+            color_above: tray.color_above,
+        }
+    } else {
+        TrayIconStyle::Color
     }
 }
 
+/// PNG bytes of the tray icon and whether they are a template image.
+fn tray_icon_bytes(
+    target: &TrayTarget,
+    percentage: Option<u8>,
+    windows: Option<TrayWindows>,
+    style: TrayIconStyle,
+) -> (Vec<u8>, bool) {
+    let identity = target.icon_identity();
+    let dual = windows.and_then(TrayWindows::both);
+    let critical = match dual {
+        Some((session, weekly)) => tray_icon::critical_percent(Some(session), Some(weekly)),
+        None => percentage,
+    };
+    let template = style.is_template(critical);
+    let bytes = match (dual, template) {
+        (Some((session, weekly)), false) => {
+            tray_icon::generate_dual_tray_icon(identity, Some(session), Some(weekly), ICON_SIZE)
+        }
+        (Some((session, weekly)), true) => tray_icon::generate_template_dual_tray_icon(
+            identity,
+            Some(session),
+            Some(weekly),
+            ICON_SIZE,
+        ),
+        (None, false) => tray_icon::generate_tray_icon(identity, percentage, ICON_SIZE),
+        (None, true) => tray_icon::generate_template_tray_icon(identity, percentage, ICON_SIZE),
+    };
+    (bytes, template)
+}
+
 fn build_service_tray(app: &AppHandle, service: TrayService) -> tauri::Result<()> {
+    build_tray(app, &TrayTarget::Service(service))
+}
+
+fn build_tray(app: &AppHandle, target: &TrayTarget) -> tauri::Result<()> {
     let show_item =
-        MenuItemBuilder::with_id(service.show_menu_id(), "Show / Hide Window").build(app)?;
-    let quit_item = MenuItemBuilder::with_id(service.quit_menu_id(), "Quit").build(app)?;
+        MenuItemBuilder::with_id(target.show_menu_id(), "Show / Hide Window").build(app)?;
+    let quit_item = MenuItemBuilder::with_id(target.quit_menu_id(), "Quit").build(app)?;
     let menu = MenuBuilder::new(app)
         .items(&[&show_item, &quit_item])
         .build()?;
-    let icon = Image::from_bytes(&tray_icon::generate_tray_icon(
-        service.icon_identity(),
-        None,
-        ICON_SIZE,
-    ))?;
+    let (icon_bytes, is_template) = tray_icon_bytes(target, None, None, configured_icon_style());
+    let icon = Image::from_bytes(&icon_bytes)?;
 
-    let menu_service = service;
-    let click_service = service;
+    let show_menu_id = target.show_menu_id();
+    let quit_menu_id = target.quit_menu_id();
+    let menu_service = target.service();
+    let click_service = target.service();
 
-    let tray = TrayIconBuilder::with_id(service.tray_id())
+    let tray = TrayIconBuilder::with_id(target.tray_id())
         .icon(icon)
-        .icon_as_template(false)
-        .tooltip(service.label())
+        .icon_as_template(is_template)
+        .tooltip(target.label())
         .menu(&menu)
         .show_menu_on_left_click(false)
         .on_menu_event(move |app, event| match event.id().as_ref() {
-            id if id == menu_service.show_menu_id() => {
-                emit_tray_service_activated(app, menu_service);
+            id if id == show_menu_id => {
+                if let Some(service) = menu_service {
+                    emit_tray_service_activated(app, service);
+                }
                 toggle_main_window(app);
             }
-            id if id == menu_service.quit_menu_id() => app.exit(0),
+            id if id == quit_menu_id => app.exit(0),
             _ => {}
         })
         .on_tray_icon_event(move |tray, event| {
@@ -275,7 +477,9 @@ fn build_service_tray(app: &AppHandle, service: TrayService) -> tauri::Result<()
             } = event
             {
                 let app = tray.app_handle();
-                emit_tray_service_activated(app, click_service);
+                if let Some(service) = click_service {
+                    emit_tray_service_activated(app, service);
+                }
                 if let Some(window) = app.get_webview_window("main") {
                     position_window_near_tray(app, tray);
                     let _ = window.show();
@@ -286,13 +490,14 @@ fn build_service_tray(app: &AppHandle, service: TrayService) -> tauri::Result<()
         .build(app)?;
 
     let _ = tray.set_visible(true);
-    let _ = tray.set_icon_as_template(false);
+    let _ = tray.set_icon_as_template(is_template);
     let _ = tray.set_visible(false);
     Ok(())
 }
 
 pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
     build_service_tray(app, TrayService::Antigravity)?;
+    build_service_tray(app, TrayService::Windsurf)?;
     build_service_tray(app, TrayService::Cursor)?;
     build_service_tray(app, TrayService::Codex)?;
     build_service_tray(app, TrayService::Claude)?;
@@ -306,7 +511,7 @@ pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
         });
     }
 
-    println!("[Tray] Ready: claude/codex/cursor/antigravity trays created");
+    println!("[Tray] Ready: claude/codex/cursor/windsurf/antigravity trays created");
     Ok(())
 }
 
@@ -315,21 +520,68 @@ pub async fn update_tray_icon(
     tray_state: State<'_, TrayState>,
     service: TrayService,
     percentage: Option<u8>,
+    windows: Option<TrayWindows>,
     visible: bool,
 ) -> Result<(), String> {
-    let runtime = tray_state.runtime.clone();
+    apply_tray_update(
+        &app,
+        tray_state.runtime.clone(),
+        TrayTarget::Service(service),
+        percentage,
+        windows,
+        visible,
+    )
+}
+
+/// Show, refresh or hide the tray for a custom provider. Trays are created
+/// lazily on first visible update, so unconfigured ids never add icons.
+pub fn update_custom_tray(
+    app: &AppHandle,
+    id: &str,
+    label: &str,
+    percentage: Option<u8>,
+    visible: bool,
+) -> Result<(), String> {
+    let runtime = app.state::<TrayState>().runtime.clone();
+    apply_tray_update(
+        app,
+        runtime,
+        TrayTarget::Custom {
+            id: id.to_string(),
+            label: label.to_string(),
+        },
+        percentage,
+        None,
+        visible,
+    )
+}
+
+fn apply_tray_update(
+    app: &AppHandle,
+    runtime: Arc<Mutex<TrayRuntimeState>>,
+    target: TrayTarget,
+    percentage: Option<u8>,
+    windows: Option<TrayWindows>,
+    visible: bool,
+) -> Result<(), String> {
+    let windows = windows.map(TrayWindows::clamped);
+    // A single known window still drives the single ring.
+    let percentage = percentage.or_else(|| windows.and_then(|w| w.weekly.or(w.session)));
+    let style = configured_icon_style();
     let snapshot = TraySnapshot {
         percentage: percentage.map(|value| value.min(100)),
+        windows,
+        style,
         visible,
     };
     let request_generation = {
         let mut state = runtime
             .lock()
             .map_err(|_| "failed to lock tray runtime state".to_string())?;
-        if state.snapshot(service) == Some(snapshot) {
+        if state.target_snapshot(&target) == Some(snapshot) {
             return Ok(());
         }
-        let generation = state.bump_generation(service);
+        let generation = state.bump_target_generation(&target);
         generation
     };
 
@@ -342,17 +594,17 @@ pub async fn update_tray_icon(
                 let state = runtime
                     .lock()
                     .map_err(|_| "failed to lock tray runtime state".to_string())?;
-                if state.generation(service) != request_generation {
+                if state.target_generation(&target) != request_generation {
                     return Ok(());
                 }
             }
 
             if !visible {
-                if let Some(tray) = app_handle.tray_by_id(service.tray_id()) {
+                if let Some(tray) = app_handle.tray_by_id(&target.tray_id()) {
                     tray.set_visible(false).map_err(|e| e.to_string())?;
                     tray.set_tooltip(Some(format!(
                         "{}: {}",
-                        service.label(),
+                        target.label(),
                         TRAY_HIDDEN_TOOLTIP_SUFFIX
                     )))
                     .map_err(|e| e.to_string())?;
@@ -361,46 +613,42 @@ pub async fn update_tray_icon(
                     let mut state = runtime
                         .lock()
                         .map_err(|_| "failed to lock tray runtime state".to_string())?;
-                    if state.generation(service) == request_generation {
-                        state.set_snapshot(service, snapshot);
+                    if state.target_generation(&target) == request_generation {
+                        state.set_target_snapshot(&target, snapshot);
                     }
                 }
                 return Ok(());
             }
 
-            if app_handle.tray_by_id(service.tray_id()).is_none() {
-                build_service_tray(&app_handle, service).map_err(|e| e.to_string())?;
+            if app_handle.tray_by_id(&target.tray_id()).is_none() {
+                build_tray(&app_handle, &target).map_err(|e| e.to_string())?;
             }
 
-            let Some(tray) = app_handle.tray_by_id(service.tray_id()) else {
-                return Err(format!("missing tray icon for {}", service.label()));
+            let Some(tray) = app_handle.tray_by_id(&target.tray_id()) else {
+                return Err(format!("missing tray icon for {}", target.label()));
             };
 
-            let icon = Image::from_bytes(&tray_icon::generate_tray_icon(
-                service.icon_identity(),
-                percentage,
-                ICON_SIZE,
-            ))
-            .map_err(|e| e.to_string())?;
+            let (icon_bytes, is_template) = tray_icon_bytes(&target, percentage, windows, style);
+            let icon = Image::from_bytes(&icon_bytes).map_err(|e| e.to_string())?;
+            let tooltip = match windows.and_then(TrayWindows::both) {
+                Some((session, weekly)) => format_windows_tooltip(target.clone(), session, weekly),
+                None => format_tooltip(target.clone(), percentage),
+            };
             let updated_at = Local::now().format("%H:%M:%S").to_string();
 
             tray.set_icon(Some(icon)).map_err(|e| e.to_string())?;
-            tray.set_icon_as_template(false)
+            tray.set_icon_as_template(is_template)
+                .map_err(|e| e.to_string())?;
+            tray.set_tooltip(Some(format!("{tooltip}\nUpdated: {updated_at}")))
                 .map_err(|e| e.to_string())?;
-            tray.set_tooltip(Some(format!(
-                "{}\nUpdated: {}",
-                format_tooltip(service, percentage),
-                updated_at
-            )))
-            .map_err(|e| e.to_string())?;
             tray.set_visible(true).map_err(|e| e.to_string())?;
 
             {
                 let mut state = runtime
                     .lock()
                     .map_err(|_| "failed to lock tray runtime state".to_string())?;
-                if state.generation(service) == request_generation {
-                    state.set_snapshot(service, snapshot);
+                if state.target_generation(&target) == request_generation {
+                    state.set_target_snapshot(&target, snapshot);
                 }
             }
             Ok(())
@@ -416,7 +664,10 @@ pub async fn update_tray_icon(
 
 #[cfg(test)]
 mod tests {
-    use super::{format_tooltip, TrayRuntimeState, TrayService, TraySnapshot};
+    use super::{
+        format_tooltip, format_windows_tooltip, TrayIconStyle, TrayRuntimeState, TrayService,
+        TraySnapshot,
+    };
 
     #[test]
     fn tooltip_marks_unavailable() {
@@ -434,11 +685,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tooltip_lists_both_windows() {
+        assert_eq!(
+            format_windows_tooltip(TrayService::Claude, 42, 130),
+            "Claude Code: session 42% · weekly 100% used"
+        );
+    }
+
     #[test]
     fn runtime_snapshot_is_tracked_per_service() {
         let mut state = TrayRuntimeState::default();
         let snapshot = TraySnapshot {
             percentage: Some(100),
+            windows: None,
+            style: TrayIconStyle::Color,
             visible: true,
         };
 
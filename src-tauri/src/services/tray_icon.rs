@@ -9,10 +9,15 @@ const GLYPH_WIDTH: u32 = 3;
 const GLYPH_HEIGHT: u32 = 5;
 const BADGE_BORDER: Rgba<u8> = Rgba([255, 255, 255, 235]);
 const BADGE_TEXT: Rgba<u8> = Rgba([255, 255, 255, 255]);
+/// Template images only keep alpha; the menubar supplies the color.
+const TEMPLATE_INK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const TEMPLATE_TRACK_ALPHA: f32 = 80.0;
 const CLAUDE_BADGE_BYTES: &[u8] = include_bytes!("../../icons/tray-badges/claude.png");
 const CODEX_BADGE_BYTES: &[u8] = include_bytes!("../../icons/tray-badges/codex.png");
 const CURSOR_BADGE_BYTES: &[u8] = include_bytes!("../../icons/tray-badges/cursor.png");
 const ANTIGRAVITY_BADGE_BYTES: &[u8] = include_bytes!("../../icons/tray-badges/antigravity.png");
+const WINDSURF_BADGE_BYTES: &[u8] = include_bytes!("../../icons/tray-badges/windsurf.png");
+const CUSTOM_BADGE_BYTES: &[u8] = include_bytes!("../../icons/tray-badges/custom.png");
 const LARGE_BADGE_OUTER_RADIUS: f32 = 11.2;
 const SMALL_BADGE_OUTER_RADIUS: f32 = 6.4;
 const LARGE_BADGE_BORDER_WIDTH: f32 = 1.2;
@@ -23,6 +28,10 @@ const LARGE_BADGE_INSET: f32 = 0.0;
 const SMALL_BADGE_INSET: f32 = 0.4;
 const LARGE_RING_WIDTH: f32 = 5.8;
 const SMALL_RING_WIDTH: f32 = 3.2;
+const LARGE_DUAL_RING_WIDTH: f32 = 3.4;
+const SMALL_DUAL_RING_WIDTH: f32 = 2.0;
+const LARGE_DUAL_RING_GAP: f32 = 1.2;
+const SMALL_DUAL_RING_GAP: f32 = 0.6;
 const LARGE_RING_OUTER_INSET: f32 = 1.2;
 const SMALL_RING_OUTER_INSET: f32 = 0.6;
 const LARGE_DIGIT_OFFSET_X: i32 = -1;
@@ -43,12 +52,50 @@ const DIGITS: [[u8; 5]; 10] = [
     [0b111, 0b101, 0b111, 0b001, 0b111],
 ];
 
+/// How tray icons are drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrayIconStyle {
+    /// Usage-colored rings with the service's colored badge.
+    Color,
+    /// Monochrome template images that adapt to light and dark menubars,
+    /// switching to the colored icon once usage reaches `color_above`.
+    Template { color_above: u8 },
+}
+
+impl TrayIconStyle {
+    /// Whether an icon whose most used window is at `used_percent` is drawn
+    /// as a template image.
+    pub fn is_template(self, used_percent: Option<u8>) -> bool {
+        match self {
+            Self::Color => false,
+            Self::Template { color_above } => used_percent.is_none_or(|value| value < color_above),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Palette {
+    Color,
+    Template,
+}
+
+impl Palette {
+    fn text(self) -> Rgba<u8> {
+        match self {
+            Self::Color => BADGE_TEXT,
+            Self::Template => TEMPLATE_INK,
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub enum TrayIconIdentity {
     Claude,
     Codex,
     Cursor,
     Antigravity,
+    Windsurf,
+    Custom,
 }
 
 fn draw_glyph(img: &mut RgbaImage, pattern: &[u8; 5], x: i32, y: i32, scale: u32, color: Rgba<u8>) {
@@ -104,6 +151,8 @@ fn badge_background(identity: TrayIconIdentity) -> Rgba<u8> {
         TrayIconIdentity::Codex => Rgba([17, 24, 39, 255]),
         TrayIconIdentity::Cursor => Rgba([55, 65, 81, 255]),
         TrayIconIdentity::Antigravity => Rgba([66, 133, 244, 255]),
+        TrayIconIdentity::Windsurf => Rgba([13, 148, 136, 255]),
+        TrayIconIdentity::Custom => Rgba([100, 116, 139, 255]),
     }
 }
 
@@ -118,6 +167,8 @@ fn badge_source(identity: TrayIconIdentity) -> &'static RgbaImage {
     static CODEX_BADGE: OnceLock<RgbaImage> = OnceLock::new();
     static CURSOR_BADGE: OnceLock<RgbaImage> = OnceLock::new();
     static ANTIGRAVITY_BADGE: OnceLock<RgbaImage> = OnceLock::new();
+    static WINDSURF_BADGE: OnceLock<RgbaImage> = OnceLock::new();
+    static CUSTOM_BADGE: OnceLock<RgbaImage> = OnceLock::new();
 
     match identity {
         TrayIconIdentity::Claude => CLAUDE_BADGE.get_or_init(|| decode_badge(CLAUDE_BADGE_BYTES)),
@@ -126,6 +177,10 @@ fn badge_source(identity: TrayIconIdentity) -> &'static RgbaImage {
         TrayIconIdentity::Antigravity => {
             ANTIGRAVITY_BADGE.get_or_init(|| decode_badge(ANTIGRAVITY_BADGE_BYTES))
         }
+        TrayIconIdentity::Windsurf => {
+            WINDSURF_BADGE.get_or_init(|| decode_badge(WINDSURF_BADGE_BYTES))
+        }
+        TrayIconIdentity::Custom => CUSTOM_BADGE.get_or_init(|| decode_badge(CUSTOM_BADGE_BYTES)),
     }
 }
 
@@ -154,7 +209,7 @@ fn blend_pixel(bottom: Rgba<u8>, top: Rgba<u8>) -> Rgba<u8> {
     ])
 }
 
-fn draw_badge(img: &mut RgbaImage, identity: TrayIconIdentity) {
+fn draw_badge(img: &mut RgbaImage, identity: TrayIconIdentity, palette: Palette) {
     let size = img.width() as f32;
     let is_large = size >= 44.0;
     let outer_radius = if is_large {
@@ -175,7 +230,10 @@ fn draw_badge(img: &mut RgbaImage, identity: TrayIconIdentity) {
     };
     let center_x = size - outer_radius - inset;
     let center_y = size - outer_radius - inset;
-    let fill = badge_background(identity);
+    let fill = match palette {
+        Palette::Color => badge_background(identity),
+        Palette::Template => TEMPLATE_INK,
+    };
 
     for y in 0..img.height() {
         for x in 0..img.width() {
@@ -185,7 +243,12 @@ fn draw_badge(img: &mut RgbaImage, identity: TrayIconIdentity) {
             let outer_mask = smooth_step(outer_radius + 0.5, outer_radius - 0.5, dist);
             let inner_mask = smooth_step(inner_radius + 0.5, inner_radius - 0.5, dist);
 
-            if outer_mask > 0.01 {
+            if palette == Palette::Template && outer_mask > 0.01 {
+                // The border becomes a transparent gap around the badge.
+                let mut pixel = *img.get_pixel(x, y);
+                pixel[3] = (pixel[3] as f32 * (1.0 - outer_mask)) as u8;
+                img.put_pixel(x, y, pixel);
+            } else if outer_mask > 0.01 {
                 img.put_pixel(
                     x,
                     y,
@@ -247,7 +310,16 @@ fn draw_badge(img: &mut RgbaImage, identity: TrayIconIdentity) {
             }
 
             let current = *img.get_pixel(px as u32, py as u32);
-            img.put_pixel(px as u32, py as u32, blend_pixel(current, top));
+            let pixel = match palette {
+                Palette::Color => blend_pixel(current, top),
+                // Punch the white logo out of the ink disc.
+                Palette::Template => {
+                    let mut pixel = current;
+                    pixel[3] = (current[3] as f32 * (1.0 - top[3] as f32 / 255.0)) as u8;
+                    pixel
+                }
+            };
+            img.put_pixel(px as u32, py as u32, pixel);
         }
     }
 }
@@ -261,28 +333,23 @@ fn encode_png(img: &RgbaImage, size: u32) -> Vec<u8> {
     png_bytes
 }
 
-pub fn generate_tray_icon(
-    identity: TrayIconIdentity,
-    used_percent: Option<u8>,
-    size: u32,
-) -> Vec<u8> {
-    let mut img: RgbaImage = ImageBuffer::new(size, size);
+fn draw_ring(
+    img: &mut RgbaImage,
+    pct: Option<u8>,
+    outer_radius: f32,
+    inner_radius: f32,
+    palette: Palette,
+) {
+    let size = img.width();
     let center = size as f32 / 2.0;
-    let is_large = size >= 44;
-    let pct = used_percent.map(|value| value.min(100));
-    let (pr, pg, pb) = pct.map(usage_color).unwrap_or_else(neutral_color);
-    let ring_width = if is_large {
-        LARGE_RING_WIDTH
-    } else {
-        SMALL_RING_WIDTH
+    let (pr, pg, pb) = match palette {
+        Palette::Color => pct.map(usage_color).unwrap_or_else(neutral_color),
+        Palette::Template => (TEMPLATE_INK[0], TEMPLATE_INK[1], TEMPLATE_INK[2]),
+    };
+    let (track, track_alpha) = match palette {
+        Palette::Color => ((130, 130, 130), 100.0),
+        Palette::Template => ((pr, pg, pb), TEMPLATE_TRACK_ALPHA),
     };
-    let outer_radius = center
-        - if is_large {
-            LARGE_RING_OUTER_INSET
-        } else {
-            SMALL_RING_OUTER_INSET
-        };
-    let inner_radius = outer_radius - ring_width;
     let start_angle = -std::f32::consts::FRAC_PI_2;
     let progress_angle =
         pct.map(|value| start_angle + (2.0 * std::f32::consts::PI * (value as f32 / 100.0)));
@@ -311,51 +378,199 @@ pub fn generate_tray_icon(
                 if normalized <= progress {
                     img.put_pixel(x, y, Rgba([pr, pg, pb, alpha]));
                 } else {
-                    img.put_pixel(x, y, Rgba([130, 130, 130, (100.0 * ring_mask) as u8]));
+                    img.put_pixel(
+                        x,
+                        y,
+                        Rgba([track.0, track.1, track.2, (track_alpha * ring_mask) as u8]),
+                    );
                 }
             } else {
                 img.put_pixel(x, y, Rgba([pr, pg, pb, (130.0 * ring_mask) as u8]));
             }
         }
     }
+}
 
-    if let Some(pct) = pct {
-        let scale = if is_large { 3 } else { 1 };
-        let digit_w = GLYPH_WIDTH * scale;
-        let digit_h = GLYPH_HEIGHT * scale;
-        let spacing = if is_large { 2 } else { 1 };
-        let digits: Vec<u8> = pct
-            .to_string()
-            .bytes()
-            .map(|digit| digit.saturating_sub(b'0'))
-            .collect();
-        let total_width =
-            digits.len() as u32 * digit_w + digits.len().saturating_sub(1) as u32 * spacing;
-        let digit_offset_x = if is_large {
-            LARGE_DIGIT_OFFSET_X
-        } else {
-            SMALL_DIGIT_OFFSET_X
-        };
-        let digit_offset_y = if is_large {
-            LARGE_DIGIT_OFFSET_Y
+fn draw_percent(img: &mut RgbaImage, pct: u8, scale: u32, spacing: u32, color: Rgba<u8>) {
+    let size = img.width();
+    let is_large = size >= 44;
+    let digit_w = GLYPH_WIDTH * scale;
+    let digit_h = GLYPH_HEIGHT * scale;
+    let digits: Vec<u8> = pct
+        .to_string()
+        .bytes()
+        .map(|digit| digit.saturating_sub(b'0'))
+        .collect();
+    let total_width =
+        digits.len() as u32 * digit_w + digits.len().saturating_sub(1) as u32 * spacing;
+    let digit_offset_x = if is_large {
+        LARGE_DIGIT_OFFSET_X
+    } else {
+        SMALL_DIGIT_OFFSET_X
+    };
+    let digit_offset_y = if is_large {
+        LARGE_DIGIT_OFFSET_Y
+    } else {
+        SMALL_DIGIT_OFFSET_Y
+    };
+    let start_x = (((size as i32 - total_width as i32) / 2) + digit_offset_x).max(0);
+    let start_y = (((size as i32 - digit_h as i32) / 2) + digit_offset_y).max(0);
+    for (index, digit) in digits.into_iter().enumerate() {
+        let x = start_x + index as i32 * (digit_w + spacing) as i32;
+        draw_digit(img, digit, x, start_y, scale, color);
+    }
+}
+
+fn ring_outer_radius(size: u32) -> f32 {
+    size as f32 / 2.0
+        - if size >= 44 {
+            LARGE_RING_OUTER_INSET
         } else {
-            SMALL_DIGIT_OFFSET_Y
-        };
-        let start_x = (((size as i32 - total_width as i32) / 2) + digit_offset_x).max(0);
-        let start_y = (((size as i32 - digit_h as i32) / 2) + digit_offset_y).max(0);
-        for (index, digit) in digits.into_iter().enumerate() {
-            let x = start_x + index as i32 * (digit_w + spacing) as i32;
-            draw_digit(&mut img, digit, x, start_y, scale, BADGE_TEXT);
+            SMALL_RING_OUTER_INSET
         }
+}
+
+pub fn generate_tray_icon(
+    identity: TrayIconIdentity,
+    used_percent: Option<u8>,
+    size: u32,
+) -> Vec<u8> {
+    render_single(identity, used_percent, size, Palette::Color)
+}
+
+/// Alpha-only variant of [`generate_tray_icon`] for template images.
+pub fn generate_template_tray_icon(
+    identity: TrayIconIdentity,
+    used_percent: Option<u8>,
+    size: u32,
+) -> Vec<u8> {
+    render_single(identity, used_percent, size, Palette::Template)
+}
+
+fn render_single(
+    identity: TrayIconIdentity,
+    used_percent: Option<u8>,
+    size: u32,
+    palette: Palette,
+) -> Vec<u8> {
+    let mut img: RgbaImage = ImageBuffer::new(size, size);
+    let is_large = size >= 44;
+    let pct = used_percent.map(|value| value.min(100));
+    let ring_width = if is_large {
+        LARGE_RING_WIDTH
+    } else {
+        SMALL_RING_WIDTH
+    };
+    let outer_radius = ring_outer_radius(size);
+    draw_ring(
+        &mut img,
+        pct,
+        outer_radius,
+        outer_radius - ring_width,
+        palette,
+    );
+
+    if let Some(pct) = pct {
+        let (scale, spacing) = if is_large { (3, 2) } else { (1, 1) };
+        draw_percent(&mut img, pct, scale, spacing, palette.text());
+    }
+
+    draw_badge(&mut img, identity, palette);
+    encode_png(&img, size)
+}
+
+/// The window closest to its limit, whose digits a dual-ring icon shows.
+pub fn critical_percent(session: Option<u8>, weekly: Option<u8>) -> Option<u8> {
+    session.max(weekly)
+}
+
+/// Concentric rings for a service with a short and a long quota window: the
+/// outer ring is the weekly window, the inner ring the session window, and
+/// the digits show whichever is more used.
+pub fn generate_dual_tray_icon(
+    identity: TrayIconIdentity,
+    session_percent: Option<u8>,
+    weekly_percent: Option<u8>,
+    size: u32,
+) -> Vec<u8> {
+    render_dual(
+        identity,
+        session_percent,
+        weekly_percent,
+        size,
+        Palette::Color,
+    )
+}
+
+/// Alpha-only variant of [`generate_dual_tray_icon`] for template images.
+pub fn generate_template_dual_tray_icon(
+    identity: TrayIconIdentity,
+    session_percent: Option<u8>,
+    weekly_percent: Option<u8>,
+    size: u32,
+) -> Vec<u8> {
+    render_dual(
+        identity,
+        session_percent,
+        weekly_percent,
+        size,
+        Palette::Template,
+    )
+}
+
+fn render_dual(
+    identity: TrayIconIdentity,
+    session_percent: Option<u8>,
+    weekly_percent: Option<u8>,
+    size: u32,
+    palette: Palette,
+) -> Vec<u8> {
+    let mut img: RgbaImage = ImageBuffer::new(size, size);
+    let is_large = size >= 44;
+    let session = session_percent.map(|value| value.min(100));
+    let weekly = weekly_percent.map(|value| value.min(100));
+    let (ring_width, gap) = if is_large {
+        (LARGE_DUAL_RING_WIDTH, LARGE_DUAL_RING_GAP)
+    } else {
+        (SMALL_DUAL_RING_WIDTH, SMALL_DUAL_RING_GAP)
+    };
+    let outer_radius = ring_outer_radius(size);
+    draw_ring(
+        &mut img,
+        weekly,
+        outer_radius,
+        outer_radius - ring_width,
+        palette,
+    );
+    let inner_outer_radius = outer_radius - ring_width - gap;
+    draw_ring(
+        &mut img,
+        session,
+        inner_outer_radius,
+        inner_outer_radius - ring_width,
+        palette,
+    );
+
+    if let Some(pct) = critical_percent(session, weekly) {
+        // Three large digits would overlap the inner ring.
+        let (scale, spacing) = match (is_large, pct) {
+            (false, _) => (1, 1),
+            (true, 100) => (2, 1),
+            (true, _) => (3, 2),
+        };
+        draw_percent(&mut img, pct, scale, spacing, palette.text());
     }
 
-    draw_badge(&mut img, identity);
+    draw_badge(&mut img, identity, palette);
     encode_png(&img, size)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{generate_tray_icon, usage_color, TrayIconIdentity};
+    use super::{
+        critical_percent, generate_dual_tray_icon, generate_template_tray_icon, generate_tray_icon,
+        usage_color, TrayIconIdentity, TrayIconStyle,
+    };
 
     #[test]
     fn generate_icon_returns_png_bytes() {
@@ -386,6 +601,37 @@ mod tests {
         assert_eq!(full, over_limit);
     }
 
+    #[test]
+    fn dual_ring_icon_shows_the_more_critical_window() {
+        assert_eq!(critical_percent(Some(30), Some(80)), Some(80));
+        assert_eq!(critical_percent(Some(95), Some(40)), Some(95));
+        assert_eq!(critical_percent(None, Some(40)), Some(40));
+        assert_eq!(critical_percent(None, None), None);
+
+        let dual = generate_dual_tray_icon(TrayIconIdentity::Codex, Some(30), Some(80), 44);
+        let swapped = generate_dual_tray_icon(TrayIconIdentity::Codex, Some(80), Some(30), 44);
+        let single = generate_tray_icon(TrayIconIdentity::Codex, Some(80), 44);
+        assert_ne!(dual, swapped);
+        assert_ne!(dual, single);
+        assert!(!generate_dual_tray_icon(TrayIconIdentity::Claude, Some(100), None, 22).is_empty());
+    }
+
+    #[test]
+    fn template_icons_are_alpha_only_until_the_color_threshold() {
+        let bytes = generate_template_tray_icon(TrayIconIdentity::Codex, Some(42), 44);
+        let img = image::load_from_memory(&bytes).unwrap().into_rgba8();
+        assert!(img.pixels().any(|pixel| pixel[3] > 0));
+        assert!(img
+            .pixels()
+            .all(|pixel| pixel[0] == 0 && pixel[1] == 0 && pixel[2] == 0));
+
+        let style = TrayIconStyle::Template { color_above: 80 };
+        assert!(style.is_template(None));
+        assert!(style.is_template(Some(79)));
+        assert!(!style.is_template(Some(80)));
+        assert!(!TrayIconStyle::Color.is_template(Some(10)));
+    }
+
     #[test]
     #[ignore]
     fn dump_production_tray_icons() {
@@ -394,6 +640,7 @@ mod tests {
             (TrayIconIdentity::Codex, "codex"),
             (TrayIconIdentity::Cursor, "cursor"),
             (TrayIconIdentity::Antigravity, "antigravity"),
+            (TrayIconIdentity::Windsurf, "windsurf"),
         ] {
             let bytes = generate_tray_icon(id, Some(65), 44);
             std::fs::write(format!("/tmp/tray_{}.png", name), &bytes).unwrap();
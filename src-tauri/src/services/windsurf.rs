@@ -0,0 +1,298 @@
+//! Windsurf (Codeium) credit tracking via the seat management `GetUserStatus` RPC.
+//!
+//! Token resolution order:
+//!   1. WINDSURF_API_KEY env var
+//!   2. ~/Library/Application Support/Windsurf/User/globalStorage/state.vscdb
+//!      byte-scanned for the `windsurfAuthStatus` JSON blob (no SQLite dep).
+
+use crate::domain::models::WindsurfData;
+use crate::services::http::{is_transient_os_error, shared_http_client};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const WINDSURF_TOKEN_ENV_KEY: &str = "WINDSURF_API_KEY";
+const WINDSURF_USER_STATUS_URL: &str =
+    "https://server.codeium.com/exa.seat_management_pb.SeatManagementService/GetUserStatus";
+const QUOTA_CACHE_TTL: Duration = Duration::from_secs(120);
+const AUTH_STATUS_NEEDLE: &[u8] = b"windsurfAuthStatus";
+const API_KEY_NEEDLE: &[u8] = b"\"apiKey\":\"";
+/// How far past the `windsurfAuthStatus` key we look for the `apiKey` field.
+const AUTH_STATUS_SCAN_WINDOW: usize = 4096;
+/// Codeium reports credits in hundredths (50000 == 500 credits).
+const CREDIT_SCALE: f64 = 100.0;
+
+struct CachedWindsurf {
+    data: WindsurfData,
+    cached_at: Instant,
+}
+
+static WINDSURF_CACHE: OnceLock<Mutex<Option<CachedWindsurf>>> = OnceLock::new();
+
+fn windsurf_cache() -> &'static Mutex<Option<CachedWindsurf>> {
+    WINDSURF_CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn state_vscdb_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| {
+        home.join("Library/Application Support/Windsurf/User/globalStorage/state.vscdb")
+    })
+}
+
+fn read_env_token() -> Option<String> {
+    std::env::var(WINDSURF_TOKEN_ENV_KEY)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Scan the SQLite file as raw bytes for the `windsurfAuthStatus` key. Its
+/// value is a JSON blob stored as plain UTF-8 that carries the `apiKey`.
+fn read_token_from_state_vscdb() -> Result<String, String> {
+    let path =
+        state_vscdb_path().ok_or_else(|| "Could not resolve Windsurf storage path".to_string())?;
+    if !path.exists() {
+        return Err(
+            "Windsurf not configured. Open Windsurf and sign in, or set WINDSURF_API_KEY."
+                .to_string(),
+        );
+    }
+
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read state.vscdb: {e}"))?;
+
+    let mut search_from = 0usize;
+    while search_from + AUTH_STATUS_NEEDLE.len() < bytes.len() {
+        let Some(rel) = bytes[search_from..]
+            .windows(AUTH_STATUS_NEEDLE.len())
+            .position(|w| w == AUTH_STATUS_NEEDLE)
+        else {
+            break;
+        };
+        let start = search_from + rel + AUTH_STATUS_NEEDLE.len();
+        let end = (start + AUTH_STATUS_SCAN_WINDOW).min(bytes.len());
+        if let Some(token) = extract_api_key(&bytes[start..end]) {
+            return Ok(token);
+        }
+        search_from = start;
+    }
+
+    Err("windsurfAuthStatus not found in state.vscdb. Re-login to Windsurf or set WINDSURF_API_KEY.".to_string())
+}
+
+/// Find `"apiKey":"<value>"` in the tail and return the value. Codeium keys are
+/// UUIDs or `sk-ws-...` strings, ASCII only.
+fn extract_api_key(tail: &[u8]) -> Option<String> {
+    let rel = tail
+        .windows(API_KEY_NEEDLE.len())
+        .position(|w| w == API_KEY_NEEDLE)?;
+    let start = rel + API_KEY_NEEDLE.len();
+    let len = tail[start..].iter().position(|b| *b == b'"')?;
+    let candidate = std::str::from_utf8(&tail[start..start + len]).ok()?;
+    if candidate.len() < 16 || !candidate.bytes().all(is_key_byte) {
+        return None;
+    }
+    Some(candidate.to_string())
+}
+
+fn is_key_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.')
+}
+
+fn get_windsurf_token() -> Result<String, String> {
+    if let Some(token) = read_env_token() {
+        return Ok(token);
+    }
+    read_token_from_state_vscdb()
+}
+
+fn get_cached_windsurf() -> Option<WindsurfData> {
+    let guard = windsurf_cache().lock().ok()?;
+    let cached = guard.as_ref()?;
+    if cached.cached_at.elapsed() < QUOTA_CACHE_TTL {
+        Some(cached.data.clone())
+    } else {
+        None
+    }
+}
+
+/// Return last cached value regardless of TTL, but only if it represents a
+/// successful connection.
+fn get_stale_cached_windsurf() -> Option<WindsurfData> {
+    let guard = windsurf_cache().lock().ok()?;
+    let cached = guard.as_ref()?;
+    if cached.data.connected {
+        Some(cached.data.clone())
+    } else {
+        None
+    }
+}
+
+fn fallback_or_disconnected(error: impl Into<String>) -> WindsurfData {
+    let error = error.into();
+    if is_transient_os_error(&error) {
+        if let Some(stale) = get_stale_cached_windsurf() {
+            return stale;
+        }
+    }
+    WindsurfData::disconnected(error)
+}
+
+fn save_windsurf_cache(data: &WindsurfData) {
+    if let Ok(mut guard) = windsurf_cache().lock() {
+        *guard = Some(CachedWindsurf {
+            data: data.clone(),
+            cached_at: Instant::now(),
+        });
+    }
+}
+
+pub async fn fetch_windsurf_info() -> WindsurfData {
+    if let Some(cached) = get_cached_windsurf() {
+        return cached;
+    }
+
+    let api_key = match get_windsurf_token() {
+        Ok(t) => t,
+        Err(error) => return fallback_or_disconnected(error),
+    };
+
+    let body = serde_json::json!({
+        "metadata": {
+            "apiKey": api_key,
+            "ideName": "windsurf",
+            "ideVersion": "1.0.0",
+            "extensionName": "windsurf",
+            "extensionVersion": "1.0.0",
+            "locale": "en",
+        }
+    });
+
+    let response = shared_http_client()
+        .post(WINDSURF_USER_STATUS_URL)
+        .json(&body)
+        .header("Accept", "application/json")
+        .header("User-Agent", "QuotaBar/0.2 (Windsurf monitor)")
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await;
+
+    let response = match response {
+        Ok(resp) => resp,
+        Err(err) => return fallback_or_disconnected(format!("Network error: {err}")),
+    };
+
+    let status = response.status();
+    if status.as_u16() == 401 || status.as_u16() == 403 {
+        return WindsurfData::disconnected(
+            "Windsurf session expired. Re-open Windsurf and sign in.",
+        );
+    }
+    if !status.is_success() {
+        return WindsurfData::disconnected(format!("Windsurf API error: {status}"));
+    }
+
+    let data = match response.json::<serde_json::Value>().await {
+        Ok(v) => v,
+        Err(err) => {
+            return WindsurfData::disconnected(format!("Failed to parse Windsurf response: {err}"))
+        }
+    };
+
+    let result = parse_user_status(&data);
+    save_windsurf_cache(&result);
+    result
+}
+
+fn credits(value: &serde_json::Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|v| v.parse::<f64>().ok()))
+        .map(|v| v / CREDIT_SCALE)
+}
+
+fn parse_user_status(data: &serde_json::Value) -> WindsurfData {
+    // The payload shape: { "userStatus": { "email", "planStatus": {
+    //   "planInfo": { "planName", "monthlyPromptCredits", "monthlyFlowCredits" },
+    //   "planEnd", "usedPromptCredits", "usedFlowCredits", ... } } }
+    let user_status = &data["userStatus"];
+    let plan_status = &user_status["planStatus"];
+    let plan_info = &plan_status["planInfo"];
+
+    let prompt_used = credits(&plan_status["usedPromptCredits"]);
+    let prompt_limit = credits(&plan_info["monthlyPromptCredits"])
+        .or_else(|| credits(&plan_status["availablePromptCredits"]));
+    let flow_used = credits(&plan_status["usedFlowCredits"]);
+    let flow_limit = credits(&plan_info["monthlyFlowCredits"])
+        .or_else(|| credits(&plan_status["availableFlowCredits"]));
+
+    let percentage = match (prompt_used, prompt_limit) {
+        (Some(u), Some(l)) if l > 0.0 => Some((u / l) * 100.0),
+        _ => match (flow_used, flow_limit) {
+            (Some(u), Some(l)) if l > 0.0 => Some((u / l) * 100.0),
+            _ => None,
+        },
+    };
+
+    let connected = plan_status.is_object();
+
+    WindsurfData {
+        connected,
+        plan_type: plan_info["planName"].as_str().map(ToString::to_string),
+        email: user_status["email"].as_str().map(ToString::to_string),
+        prompt_used,
+        prompt_limit,
+        flow_used,
+        flow_limit,
+        percentage,
+        reset_at: plan_status["planEnd"].as_str().map(ToString::to_string),
+        error: if connected {
+            None
+        } else {
+            Some("Windsurf API returned no plan status.".to_string())
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_api_key_from_auth_status_blob() {
+        let tail = b"\x00\x12{\"name\":\"dev\",\"apiKey\":\"sk-ws-01-abcdefghijklmnopqrstuvwxyz\",\"allowedCommandModelConfigsProtoBinaryBase64\":[]}";
+        let got = extract_api_key(tail).expect("should parse");
+        assert_eq!(got, "sk-ws-01-abcdefghijklmnopqrstuvwxyz");
+    }
+
+    #[test]
+    fn rejects_short_api_key() {
+        assert!(extract_api_key(b"{\"apiKey\":\"short\"}").is_none());
+    }
+
+    #[test]
+    fn parses_plan_status_credits() {
+        let payload = serde_json::json!({
+            "userStatus": {
+                "email": "dev@example.com",
+                "planStatus": {
+                    "planInfo": {
+                        "planName": "Pro",
+                        "monthlyPromptCredits": 50000,
+                        "monthlyFlowCredits": 150000
+                    },
+                    "planEnd": "2026-06-01T00:00:00Z",
+                    "usedPromptCredits": 12500,
+                    "usedFlowCredits": 3000
+                }
+            }
+        });
+        let data = parse_user_status(&payload);
+        assert!(data.connected);
+        assert_eq!(data.plan_type.as_deref(), Some("Pro"));
+        assert_eq!(data.prompt_used, Some(125.0));
+        assert_eq!(data.prompt_limit, Some(500.0));
+        assert_eq!(data.flow_used, Some(30.0));
+        assert_eq!(data.percentage, Some(25.0));
+        assert_eq!(data.reset_at.as_deref(), Some("2026-06-01T00:00:00Z"));
+    }
+}
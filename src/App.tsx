@@ -7,9 +7,13 @@ import ThemeSelector, { ThemeName } from './components/ThemeSelector';
 import TabSwitcher, { TabName } from './components/TabSwitcher';
 import CodexPanel from './components/CodexPanel';
 import CursorPanel from './components/CursorPanel';
+import WindsurfPanel from './components/WindsurfPanel';
 import AntigravityPanel from './components/AntigravityPanel';
 import TrayToggles, { type TrayToggleEntry } from './components/TrayToggles';
 import CostSummarySection from './components/CostSummarySection';
+import ClaudeStatsSection from './components/ClaudeStatsSection';
+import ClaudeBlocksSection from './components/ClaudeBlocksSection';
+import CustomProvidersSection from './components/CustomProvidersSection';
 import { backend } from './services/backend';
 import { SERVICE_META, SERVICES } from './services/service_meta';
 import {
@@ -18,7 +22,7 @@ import {
   shouldShowTray,
   type TrayServiceName,
 } from './services/tray_visibility';
-import type { QuotaData } from './types/models';
+import type { QuotaData, TrayWindows } from './types/models';
 import './styles.css';
 
 const THEME_STORAGE_KEY = 'claude-quota-theme';
@@ -53,9 +57,14 @@ type ServiceMap<T> = Record<TrayServiceName, T>;
 type TrayEnabledState = ServiceMap<boolean>;
 type TrayIconRequest = {
   percentage: number | null;
+  windows: TrayWindows | null;
   visible: boolean;
 };
 
+function sameTrayWindows(a: TrayWindows | null, b: TrayWindows | null): boolean {
+  return a?.session === b?.session && a?.weekly === b?.weekly;
+}
+
 function defaultServiceMap<T>(value: T): ServiceMap<T> {
   return SERVICES.reduce((acc, svc) => {
     acc[svc] = value;
@@ -138,27 +147,23 @@ function formatResetTime(resetTime?: string): string {
 }
 
 export function getClaudeTrayUsedPercent(quota: QuotaData | null): number | null {
-  if (!quota) return null;
+  const windows = getClaudeTrayWindows(quota);
+  return windows?.weekly ?? windows?.session ?? null;
+}
 
-  if (quota.weeklyTotal) {
-    return quota.weeklyTotal.percentage;
-  }
+export function getClaudeTrayWindows(quota: QuotaData | null): TrayWindows | null {
+  if (!quota) return null;
 
-  const weeklyUsedCandidates = [
+  const weeklyCandidates = [
     quota.weeklyOpus?.percentage,
     quota.weeklySonnet?.percentage,
     quota.weeklyDesign?.percentage,
   ]
     .filter((value): value is number => typeof value === 'number');
-  if (weeklyUsedCandidates.length > 0) {
-    return Math.max(...weeklyUsedCandidates);
-  }
+  const weekly = quota.weeklyTotal?.percentage
+    ?? (weeklyCandidates.length > 0 ? Math.max(...weeklyCandidates) : null);
 
-  if (quota.session) {
-    return quota.session.percentage;
-  }
-
-  return null;
+  return { session: quota.session?.percentage ?? null, weekly };
 }
 
 function isClaudeAuthError(error: string): boolean {
@@ -220,6 +225,7 @@ export default function App() {
   const [activeTab, setActiveTab] = useState<TabName>(getSavedTab);
   const [windowVisible, setWindowVisible] = useState(false);
   const containerRef = useRef<HTMLDivElement>(null);
+  const [codexTrayWindows, setCodexTrayWindows] = useState<TrayWindows | null>(null);
   const lastTrayIconRequestRef = useRef<Partial<Record<TrayServiceName, TrayIconRequest>>>({});
 
   const setServiceConnected = useCallback((service: TrayServiceName, value: boolean) => {
@@ -230,6 +236,10 @@ export default function App() {
     setUsedPercent((prev) => (prev[service] === value ? prev : { ...prev, [service]: value }));
   }, []);
 
+  const updateCodexTrayWindows = useCallback((windows: TrayWindows | null) => {
+    setCodexTrayWindows((prev) => (sameTrayWindows(prev, windows) ? prev : windows));
+  }, []);
+
   const setServiceLoading = useCallback((service: TrayServiceName, value: boolean) => {
     setPanelLoading((prev) => (prev[service] === value ? prev : { ...prev, [service]: value }));
   }, []);
@@ -350,15 +360,20 @@ export default function App() {
     service: TrayServiceName,
     percentage: number | null,
     visible: boolean,
+    windows: TrayWindows | null = null,
   ) => {
     const previous = lastTrayIconRequestRef.current[service];
-    if (previous?.percentage === percentage && previous.visible === visible) {
+    if (
+      previous?.percentage === percentage
+      && previous.visible === visible
+      && sameTrayWindows(previous.windows, windows)
+    ) {
       return;
     }
 
     try {
-      await backend.updateTrayIcon(service, percentage, visible);
-      lastTrayIconRequestRef.current[service] = { percentage, visible };
+      await backend.updateTrayIcon(service, percentage, visible, windows);
+      lastTrayIconRequestRef.current[service] = { percentage, windows, visible };
     } catch (err) {
       console.error(`Failed to update ${service} tray icon:`, err);
     }
@@ -421,9 +436,12 @@ export default function App() {
     for (const svc of SERVICES) {
       const pct = svc === 'claude' ? getClaudeTrayUsedPercent(quota) : usedPercent[svc];
       const isConnected = svc === 'claude' ? quota?.connected ?? false : connected[svc];
-      updateTrayIcon(svc, pct, shouldShowTray(trayEnabled[svc], isConnected));
+      const windows = svc === 'claude'
+        ? getClaudeTrayWindows(quota)
+        : svc === 'codex' ? codexTrayWindows : null;
+      updateTrayIcon(svc, pct, shouldShowTray(trayEnabled[svc], isConnected), windows);
     }
-  }, [quota, connected, usedPercent, trayEnabled, updateTrayIcon]);
+  }, [quota, connected, usedPercent, codexTrayWindows, trayEnabled, updateTrayIcon]);
 
   useEffect(() => {
     syncTrayIcons();
@@ -542,6 +560,9 @@ export default function App() {
         case 'cursor':
           await backend.openCursorDashboard();
           break;
+        case 'windsurf':
+          await backend.openWindsurfDashboard();
+          break;
         case 'antigravity':
           await backend.openAntigravityDashboard();
           break;
@@ -580,6 +601,7 @@ export default function App() {
     claude: quota?.connected ?? false,
     codex: connected.codex,
     cursor: connected.cursor,
+    windsurf: connected.windsurf,
     antigravity: connected.antigravity,
   };
 
@@ -679,9 +701,28 @@ export default function App() {
                     )}
                   </div>
 
+                  {windowVisible && (
+                    <ClaudeBlocksSection refreshKey={claudeCostRefreshNonce} />
+                  )}
+
                   {windowVisible && (
                     <CostSummarySection source="claude" refreshKey={claudeCostRefreshNonce} />
                   )}
+
+                  {windowVisible && (
+                    <CostSummarySection
+                      source="all"
+                      title="ALL LOCAL COST"
+                      refreshKey={claudeCostRefreshNonce}
+                      hideWhenUnavailable
+                    />
+                  )}
+
+                  {windowVisible && <ClaudeStatsSection refreshKey={claudeCostRefreshNonce} />}
+
+                  {windowVisible && (
+                    <CustomProvidersSection refreshKey={claudeCostRefreshNonce} />
+                  )}
                 </div>
               )}
 
@@ -700,6 +741,7 @@ export default function App() {
             <CodexPanel
               onConnectionChange={connectionSetters.codex}
               onUsageChange={usageSetters.codex}
+              onWindowsChange={updateCodexTrayWindows}
               onLoadingChange={loadingSetters.codex}
               manualRefreshNonce={refreshNonces.codex}
               autoRefreshIntervalMs={nonClaudeRefreshIntervalMs}
@@ -718,6 +760,16 @@ export default function App() {
             />
           </div>
 
+          <div style={{ display: activeTab === 'windsurf' ? 'block' : 'none' }}>
+            <WindsurfPanel
+              onConnectionChange={connectionSetters.windsurf}
+              onUsageChange={usageSetters.windsurf}
+              onLoadingChange={loadingSetters.windsurf}
+              manualRefreshNonce={refreshNonces.windsurf}
+              autoRefreshIntervalMs={nonClaudeRefreshIntervalMs}
+            />
+          </div>
+
           <div style={{ display: activeTab === 'antigravity' ? 'block' : 'none' }}>
             <AntigravityPanel
               onConnectionChange={connectionSetters.antigravity}
@@ -0,0 +1,88 @@
+import { useEffect, useState } from 'react';
+import { backend } from '../services/backend';
+import type { ClaudeBlock } from '../types/models';
+
+interface ClaudeBlocksSectionProps {
+  refreshKey?: number;
+}
+
+function formatUsd(value: number): string {
+  return `$${value.toFixed(value < 1 ? 3 : 2)}`;
+}
+
+function formatClock(value?: string): string {
+  if (!value) return '';
+  const date = new Date(value);
+  if (Number.isNaN(date.getTime())) return '';
+  return date.toLocaleTimeString('en-US', { hour: '2-digit', minute: '2-digit' });
+}
+
+function projectName(project: string): string {
+  const parts = project.split(/[\\/]/).filter(Boolean);
+  return parts[parts.length - 1] ?? project;
+}
+
+export default function ClaudeBlocksSection({ refreshKey = 0 }: ClaudeBlocksSectionProps) {
+  const [block, setBlock] = useState<ClaudeBlock | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    backend
+      .getClaudeBlocks(24, refreshKey > 0)
+      .then((report) => {
+        if (!cancelled) {
+          setBlock(report.blocks.find((candidate) => candidate.isActive) ?? null);
+        }
+      })
+      .catch(() => {
+        if (!cancelled) setBlock(null);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [refreshKey]);
+
+  if (!block) {
+    return null;
+  }
+
+  const limitAt = formatClock(block.projectedLimitAt);
+
+  return (
+    <div className="section">
+      <div className="section-title">
+        CURRENT BLOCK
+        <span className="plan-tag">
+          {formatClock(block.startTime)}–{formatClock(block.endTime)}
+        </span>
+      </div>
+      <div className="codex-card">
+        <div className="codex-row">
+          <span className="codex-label">Estimated cost</span>
+          <span className="codex-value">
+            {formatUsd(block.costUsd)}
+            {block.projectedCostUsd != null && ` → ${formatUsd(block.projectedCostUsd)}`}
+          </span>
+        </div>
+        <div className="codex-row">
+          <span className="codex-label">Active</span>
+          <span className="codex-value">{block.activeMinutes} min</span>
+        </div>
+        {limitAt && (
+          <div className="codex-row">
+            <span className="codex-label">Limit at this pace</span>
+            <span className="codex-value">{limitAt}</span>
+          </div>
+        )}
+        {block.projects.slice(0, 3).map((project) => (
+          <div className="codex-row" key={project.project}>
+            <span className="codex-label" title={project.project}>
+              {projectName(project.project)}
+            </span>
+            <span className="codex-value">{formatUsd(project.costUsd)}</span>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,79 @@
+import { useEffect, useState } from 'react';
+import { backend } from '../services/backend';
+import type { ClaudeStats } from '../types/models';
+
+interface ClaudeStatsSectionProps {
+  refreshKey?: number;
+}
+
+function shortModelName(model: string): string {
+  return model.replace(/^claude-/, '').replace(/-\d{8}$/, '');
+}
+
+export default function ClaudeStatsSection({ refreshKey = 0 }: ClaudeStatsSectionProps) {
+  const [stats, setStats] = useState<ClaudeStats | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    backend
+      .getClaudeStats()
+      .then((data) => {
+        if (!cancelled) setStats(data);
+      })
+      .catch(() => {
+        if (!cancelled) setStats(null);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [refreshKey]);
+
+  if (!stats || stats.totalSessions === 0) {
+    return null;
+  }
+
+  const topModels = stats.messagesByModel.filter((model) => model.today > 0).slice(0, 3);
+  const topTools = stats.toolCalls.filter((tool) => tool.today > 0).slice(0, 3);
+
+  return (
+    <div className="section">
+      <div className="section-title">LOCAL STATS</div>
+      <div className="codex-card">
+        <div className="codex-row">
+          <span className="codex-label">Today</span>
+          <span className="codex-value">
+            {stats.todaySessions} sessions · {stats.activeProjects} projects
+          </span>
+        </div>
+        <div className="codex-row">
+          <span className="codex-label">Tool calls today</span>
+          <span className="codex-value">{stats.toolCallsToday}</span>
+        </div>
+        {topTools.map((tool) => (
+          <div className="codex-row" key={`tool-${tool.name}`}>
+            <span className="codex-label">{tool.name}</span>
+            <span className="codex-value">{tool.today}</span>
+          </div>
+        ))}
+        {topModels.map((model) => (
+          <div className="codex-row" key={`model-${model.name}`}>
+            <span className="codex-label">{shortModelName(model.name)}</span>
+            <span className="codex-value">{model.today} messages</span>
+          </div>
+        ))}
+        <div className="codex-row">
+          <span className="codex-label">Total</span>
+          <span className="codex-value">
+            {stats.totalSessions} sessions · {stats.totalProjects} projects
+          </span>
+        </div>
+        {stats.lastActivity && (
+          <div className="codex-row">
+            <span className="codex-label">Last activity</span>
+            <span className="codex-value">{stats.lastActivity} UTC</span>
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -1,12 +1,13 @@
 import { useEffect, useState, useCallback } from 'react';
 import { backend } from '../services/backend';
 import CostSummarySection from './CostSummarySection';
-import type { CodexData, CodexRateLimits, CodexStats } from '../types/models';
+import type { CodexData, CodexRateLimits, CodexStats, TrayWindows } from '../types/models';
 import { formatPlanType, getProgressStyle } from '../utils/quota_format';
 
 interface CodexPanelProps {
   onConnectionChange?: (connected: boolean) => void;
   onUsageChange?: (usedPercent: number | null) => void;
+  onWindowsChange?: (windows: TrayWindows | null) => void;
   autoRefreshIntervalMs?: number;
   manualRefreshNonce?: number;
   onLoadingChange?: (loading: boolean) => void;
@@ -75,6 +76,7 @@ function getTrayUsedPercent(limits: CodexRateLimits): number | null {
 export default function CodexPanel({
   onConnectionChange,
   onUsageChange,
+  onWindowsChange,
   autoRefreshIntervalMs = 60 * 1000,
   manualRefreshNonce = 0,
   onLoadingChange,
@@ -113,14 +115,20 @@ export default function CodexPanel({
 
       // Use weekly usage for tray when available (secondary window).
       onUsageChange?.(getTrayUsedPercent(limits));
+      // Primary is the short (session) window, secondary the weekly one.
+      onWindowsChange?.({
+        session: limits.primary?.usedPercent ?? null,
+        weekly: limits.secondary?.usedPercent ?? null,
+      });
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to fetch Codex data');
       onConnectionChange?.(false);
       onUsageChange?.(null);
+      onWindowsChange?.(null);
     } finally {
       setLoading(false);
     }
-  }, [onConnectionChange, onUsageChange]);
+  }, [onConnectionChange, onUsageChange, onWindowsChange]);
 
   useEffect(() => {
     fetchData();
@@ -176,6 +184,18 @@ export default function CodexPanel({
               <div className="section-title">
                 USAGE
                 <span className="plan-tag">Codex {formatPlanType(planType)}</span>
+                {rateLimits?.source === 'local' && rateLimits.updatedAt && (
+                  <span
+                    className="plan-tag"
+                    title="Read from local Codex session logs while the usage API is unavailable"
+                  >
+                    Local snapshot ·{' '}
+                    {new Date(rateLimits.updatedAt).toLocaleTimeString([], {
+                      hour: '2-digit',
+                      minute: '2-digit',
+                    })}
+                  </span>
+                )}
               </div>
 
               {rateLimits?.primary && (
@@ -272,6 +292,15 @@ export default function CodexPanel({
             <CostSummarySection source="codex" refreshKey={manualRefreshNonce} />
           )}
 
+          {showCostSummary && (
+            <CostSummarySection
+              source="openai"
+              title="BILLED COST"
+              refreshKey={manualRefreshNonce}
+              hideWhenUnavailable
+            />
+          )}
+
           {/* ChatGPT Link */}
           <button className="open-dashboard-btn" onClick={handleOpenDashboard}>
             Open Dashboard
@@ -0,0 +1,65 @@
+import { useEffect, useState } from 'react';
+import { backend } from '../services/backend';
+import type { CostSeries, CostSource } from '../types/models';
+
+interface CostSparklineProps {
+  source: CostSource;
+  days?: number;
+  refreshKey?: number;
+}
+
+const WIDTH = 240;
+const HEIGHT = 28;
+
+function isoDay(date: Date): string {
+  const month = `${date.getMonth() + 1}`.padStart(2, '0');
+  const day = `${date.getDate()}`.padStart(2, '0');
+  return `${date.getFullYear()}-${month}-${day}`;
+}
+
+export default function CostSparkline({ source, days = 14, refreshKey = 0 }: CostSparklineProps) {
+  const [series, setSeries] = useState<CostSeries | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    const since = new Date();
+    since.setDate(since.getDate() - (days - 1));
+    backend
+      .getCostSeries(source, isoDay(since), null, 'day')
+      .then((data) => {
+        if (!cancelled) setSeries(data);
+      })
+      .catch(() => {
+        if (!cancelled) setSeries(null);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [source, days, refreshKey]);
+
+  if (!series || series.points.length < 2 || series.cost <= 0) {
+    return null;
+  }
+
+  const max = Math.max(...series.points.map((point) => point.cost));
+  const step = WIDTH / (series.points.length - 1);
+  const path = series.points
+    .map((point, index) => {
+      const x = index * step;
+      const y = HEIGHT - 2 - (max > 0 ? (point.cost / max) * (HEIGHT - 4) : 0);
+      return `${index === 0 ? 'M' : 'L'}${x.toFixed(1)},${y.toFixed(1)}`;
+    })
+    .join(' ');
+
+  return (
+    <svg
+      className="cost-sparkline"
+      viewBox={`0 0 ${WIDTH} ${HEIGHT}`}
+      preserveAspectRatio="none"
+      role="img"
+      aria-label={`Daily cost for the last ${days} days`}
+    >
+      <path d={path} />
+    </svg>
+  );
+}
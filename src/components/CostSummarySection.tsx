@@ -1,10 +1,37 @@
 import { useEffect, useMemo, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { backend } from '../services/backend';
-import type { CostOverview, CostRangeSummary, CostSource } from '../types/models';
+import CostSparkline from './CostSparkline';
+import SubscriptionValueRow from './SubscriptionValueRow';
+import type {
+  CostOverview,
+  CostRangePreset,
+  CostRangeRequest,
+  CostRangeSummary,
+  CostSource,
+} from '../types/models';
+
+// Emitted once a background refresh replaces an overview restored from disk.
+const COST_OVERVIEW_UPDATED_EVENT = 'cost-overview-updated';
+
+// Sources answered from the local cost index, which supports custom ranges.
+// `all` merges them with Cursor, which only reports the default ranges.
+const RANGED_SOURCES = new Set<CostSource>(['claude', 'codex', 'all']);
+const SERIES_SOURCES = new Set<CostSource>(['claude', 'codex']);
+
+const RANGE_OPTIONS: { value: CostRangePreset | 'default'; label: string }[] = [
+  { value: 'default', label: 'Today / Week / Month' },
+  { value: 'last7Days', label: 'Last 7 days' },
+  { value: 'last30Days', label: 'Last 30 days' },
+  { value: 'previousWeek', label: 'Previous week' },
+  { value: 'previousMonth', label: 'Previous month' },
+];
 
 interface CostSummarySectionProps {
   source: CostSource;
   refreshKey?: number;
+  title?: string;
+  hideWhenUnavailable?: boolean;
 }
 
 function formatMoney(value: number | null | undefined, currency: string): string {
@@ -42,6 +69,23 @@ function formatUpdatedAt(value: string): string {
   }
 }
 
+function formatDelta(value: number | null | undefined, currency: string): string {
+  if (value == null || !Number.isFinite(value)) return 'n/a';
+  const sign = value > 0 ? '+' : value < 0 ? '−' : '';
+  return `${sign}${formatMoney(Math.abs(value), currency)}`;
+}
+
+function formatPercent(value: number | null | undefined): string {
+  if (value == null || !Number.isFinite(value)) return 'new';
+  const sign = value > 0 ? '+' : '';
+  return `${sign}${Math.round(value)}%`;
+}
+
+function projectName(project: string): string {
+  const parts = project.split(/[\\/]/).filter(Boolean);
+  return parts[parts.length - 1] ?? project;
+}
+
 function pickPrimaryRange(overview: CostOverview | null): CostRangeSummary | null {
   if (!overview) return null;
   return (
@@ -52,19 +96,30 @@ function pickPrimaryRange(overview: CostOverview | null): CostRangeSummary | nul
   );
 }
 
-export default function CostSummarySection({ source, refreshKey = 0 }: CostSummarySectionProps) {
+export default function CostSummarySection({
+  source,
+  refreshKey = 0,
+  title = 'LOCAL COST',
+  hideWhenUnavailable = false,
+}: CostSummarySectionProps) {
   const [overview, setOverview] = useState<CostOverview | null>(null);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [preset, setPreset] = useState<CostRangePreset | 'default'>('default');
+  const supportsRanges = RANGED_SOURCES.has(source);
+  const viewKey = overview?.viewKey ?? null;
 
   useEffect(() => {
     let cancelled = false;
 
+    const range: CostRangeRequest | null =
+      supportsRanges && preset !== 'default' ? { preset, compare: true } : null;
+
     const loadCost = async (force: boolean) => {
       try {
         setLoading(true);
         setError(null);
-        const data = await backend.getCostOverview(source, force);
+        const data = await backend.getCostOverview(source, force, range);
         if (!cancelled) {
           setOverview(data);
         }
@@ -84,18 +139,88 @@ export default function CostSummarySection({ source, refreshKey = 0 }: CostSumma
     return () => {
       cancelled = true;
     };
-  }, [source, refreshKey]);
+  }, [source, refreshKey, preset, supportsRanges]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let mounted = true;
+
+    listen<CostOverview>(COST_OVERVIEW_UPDATED_EVENT, (event) => {
+      // Background refreshes only cover the default ranges; the view key also
+      // tells apart overviews in another currency or timezone.
+      if (
+        preset === 'default' &&
+        event.payload?.source === source &&
+        viewKey != null &&
+        event.payload.viewKey === viewKey
+      ) {
+        setOverview(event.payload);
+        setError(null);
+      }
+    })
+      .then((stopListening) => {
+        if (mounted) {
+          unlisten = stopListening;
+          return;
+        }
+        stopListening();
+      })
+      .catch((err) => {
+        console.error('Failed to subscribe cost overview updates:', err);
+      });
+
+    return () => {
+      mounted = false;
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [source, preset, viewKey]);
 
   const primaryRange = useMemo(() => pickPrimaryRange(overview), [overview]);
   const topModels = primaryRange?.models.slice(0, 3) ?? [];
+  const topProjects = primaryRange?.projects.slice(0, 3) ?? [];
+  const subtotals = primaryRange?.sources ?? [];
+  const budgets = overview?.budgets ?? [];
+  const unpriced = (overview?.unpricedModels ?? []).filter((model) => !model.guessed);
+  const guessed = (overview?.unpricedModels ?? []).filter((model) => model.guessed);
+  const comparison = primaryRange?.comparison ?? null;
+  const topMovers = comparison?.models.slice(0, 3) ?? [];
   const currency = overview?.currency ?? 'USD';
 
+  if (hideWhenUnavailable && error && !overview) {
+    return null;
+  }
+
   return (
     <div className="section cost-section">
       <div className="section-title">
-        LOCAL COST
+        {title}
         {overview && (
-          <span className="plan-tag">{overview.cached ? 'Cached' : currency}</span>
+          <span
+            className="plan-tag"
+            title={
+              overview.exchangeRate
+                ? `1 USD = ${overview.exchangeRate.rate} ${overview.exchangeRate.currency} (${overview.exchangeRate.date})`
+                : undefined
+            }
+          >
+            {overview.cached ? 'Cached' : currency}
+          </span>
+        )}
+        {supportsRanges && (
+          <select
+            className="cost-range-select"
+            value={preset}
+            onChange={(event) => setPreset(event.target.value as CostRangePreset | 'default')}
+            aria-label="Cost range"
+          >
+            {RANGE_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                {option.label}
+              </option>
+            ))}
+          </select>
         )}
       </div>
 
@@ -170,11 +295,113 @@ export default function CostSummarySection({ source, refreshKey = 0 }: CostSumma
             </>
           )}
 
+          {budgets.length > 0 && (
+            <div className="cost-budgets">
+              {budgets.map((budget) => (
+                <div
+                  className={`cost-model-row cost-budget ${budget.level}`}
+                  key={`${budget.source}-${budget.period}`}
+                >
+                  <span className="cost-model-name">
+                    {budget.period.charAt(0).toUpperCase() + budget.period.slice(1)} budget
+                  </span>
+                  <span className="cost-model-tokens">{Math.round(budget.percent)}%</span>
+                  <span className="cost-model-cost">
+                    {formatMoney(budget.spent, 'USD')} / {formatMoney(budget.amount, 'USD')}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+
+          {primaryRange && subtotals.length > 0 && (
+            <div className="cost-models">
+              {subtotals.map((subtotal) => (
+                <div
+                  className="cost-model-row"
+                  key={subtotal.source}
+                  title={subtotal.error ?? undefined}
+                >
+                  <span className="cost-model-name">{subtotal.displayName}</span>
+                  <span className="cost-model-tokens">
+                    {subtotal.error
+                      ? 'unavailable'
+                      : formatCompactNumber(subtotal.tokens.totalTokens)}
+                  </span>
+                  <span className="cost-model-cost">
+                    {formatMoney(subtotal.cost, primaryRange.currency)}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+
+          {primaryRange && topProjects.length > 0 && (
+            <div className="cost-models">
+              {topProjects.map((project) => (
+                <div className="cost-model-row" key={project.project} title={project.project}>
+                  <span className="cost-model-name">{projectName(project.project)}</span>
+                  <span className="cost-model-tokens">{project.tag ?? ''}</span>
+                  <span className="cost-model-cost">
+                    {formatMoney(project.cost, primaryRange.currency)}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+
+          {SERIES_SOURCES.has(source) && <CostSparkline source={source} refreshKey={refreshKey} />}
+
+          {SERIES_SOURCES.has(source) && (
+            <SubscriptionValueRow source={source} refreshKey={refreshKey} />
+          )}
+
+          {primaryRange && comparison && (
+            <div className="cost-comparison">
+              <div className="cost-model-row">
+                <span className="cost-model-name">
+                  vs {comparison.since} – {comparison.until}
+                </span>
+                <span className="cost-model-tokens">{formatPercent(comparison.deltaPercent)}</span>
+                <span className="cost-model-cost">
+                  {formatDelta(comparison.deltaCost, primaryRange.currency)}
+                </span>
+              </div>
+              {topMovers.map((model) => (
+                <div className="cost-model-row" key={model.model}>
+                  <span className="cost-model-name">{model.model}</span>
+                  <span className="cost-model-tokens">{formatPercent(model.deltaPercent)}</span>
+                  <span className="cost-model-cost">
+                    {formatDelta(model.deltaCost, primaryRange.currency)}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+
           <div className="cost-footer">
             <span>{primaryRange?.label ?? overview.displayName}</span>
             <span>{formatUpdatedAt(overview.generatedAt)}</span>
           </div>
 
+          {unpriced.length > 0 && (
+            <div className="cost-inline-error compact">
+              Unpriced: {unpriced.map((model) => model.model).join(', ')}
+            </div>
+          )}
+
+          {guessed.length > 0 && (
+            <div className="cost-inline-error compact">
+              Estimated price: {guessed.map((model) => model.model).join(', ')}
+            </div>
+          )}
+
+          {overview.truncated && (
+            <div className="cost-inline-error compact">
+              Partial data: the provider returned more pages than were fetched.
+            </div>
+          )}
+
           {error && <div className="cost-inline-error compact">{error}</div>}
         </div>
       )}
@@ -0,0 +1,106 @@
+import { useEffect, useState } from 'react';
+import { backend } from '../services/backend';
+import type { CustomProviderData, CustomProviderWindow } from '../types/models';
+import { formatPlanType, getProgressStyle } from '../utils/quota_format';
+
+interface CustomProvidersSectionProps {
+  refreshKey?: number;
+  autoRefreshIntervalMs?: number;
+}
+
+function formatAmount(value: number): string {
+  return Number.isInteger(value) ? String(value) : value.toFixed(1);
+}
+
+function formatWindowValue(window: CustomProviderWindow): string {
+  if (window.used != null && window.limit != null) {
+    return `${formatAmount(window.used)} / ${formatAmount(window.limit)}`;
+  }
+  if (window.percentage != null) return `${Math.round(window.percentage)}%`;
+  return '—';
+}
+
+function formatCost(provider: CustomProviderData): string | null {
+  if (!provider.cost) return null;
+  const amount = provider.cost.amount.toFixed(2);
+  return provider.cost.currency === 'USD' ? `$${amount}` : `${amount} ${provider.cost.currency}`;
+}
+
+export default function CustomProvidersSection({
+  refreshKey = 0,
+  autoRefreshIntervalMs = 60 * 1000,
+}: CustomProvidersSectionProps) {
+  const [providers, setProviders] = useState<CustomProviderData[]>([]);
+
+  useEffect(() => {
+    let cancelled = false;
+    const load = (force: boolean) => {
+      backend
+        .getCustomProviders(force)
+        .then((data) => {
+          if (!cancelled) setProviders(data);
+        })
+        .catch(() => {
+          if (!cancelled) setProviders([]);
+        });
+    };
+    load(refreshKey > 0);
+    const interval = setInterval(() => load(false), autoRefreshIntervalMs);
+    return () => {
+      cancelled = true;
+      clearInterval(interval);
+    };
+  }, [refreshKey, autoRefreshIntervalMs]);
+
+  if (providers.length === 0) {
+    return null;
+  }
+
+  return (
+    <>
+      {providers.map((provider) => {
+        const cost = formatCost(provider);
+        return (
+          <div className="section" key={provider.id}>
+            <div className="section-title">
+              {provider.name.toUpperCase()}
+              {provider.planType && (
+                <span className="plan-tag">{formatPlanType(provider.planType)}</span>
+              )}
+            </div>
+
+            {!provider.connected && (
+              <div className="cost-inline-error compact">
+                {provider.error ?? 'Provider unavailable'}
+              </div>
+            )}
+
+            {provider.connected &&
+              provider.windows.map((window, index) => (
+                <div className="quota-card" key={window.id ?? `${window.label}-${index}`}>
+                  <div className="quota-header">
+                    <span className="quota-label">{window.label}</span>
+                    <span className="quota-value">{formatWindowValue(window)}</span>
+                  </div>
+                  {window.percentage != null && (
+                    <div className="progress-bar">
+                      <div className="progress-fill" style={getProgressStyle(window.percentage)} />
+                    </div>
+                  )}
+                </div>
+              ))}
+
+            {provider.connected && cost && (
+              <div className="codex-card">
+                <div className="codex-row">
+                  <span className="codex-label">{provider.cost?.label ?? 'Cost'}</span>
+                  <span className="codex-value">{cost}</span>
+                </div>
+              </div>
+            )}
+          </div>
+        );
+      })}
+    </>
+  );
+}
@@ -0,0 +1,51 @@
+import { useEffect, useState } from 'react';
+import { backend } from '../services/backend';
+import type { CostSource, SubscriptionValue } from '../types/models';
+
+interface SubscriptionValueRowProps {
+  source: CostSource;
+  refreshKey?: number;
+}
+
+function formatUsd(value: number): string {
+  return new Intl.NumberFormat('en-US', {
+    style: 'currency',
+    currency: 'USD',
+    maximumFractionDigits: 0,
+  }).format(value);
+}
+
+// API-equivalent cost of this month's local usage against the plan price.
+export default function SubscriptionValueRow({ source, refreshKey = 0 }: SubscriptionValueRowProps) {
+  const [value, setValue] = useState<SubscriptionValue | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    backend
+      .getSubscriptionValue(1)
+      .then((report) => {
+        if (!cancelled) setValue(report.sources.find((item) => item.source === source) ?? null);
+      })
+      .catch(() => {
+        if (!cancelled) setValue(null);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [source, refreshKey]);
+
+  const month = value?.months[value.months.length - 1];
+  if (!value || !month || month.seatValueMultiple == null) {
+    return null;
+  }
+
+  return (
+    <div className="cost-model-row subscription-value" title={value.plan ?? undefined}>
+      <span className="cost-model-name">Plan value this month</span>
+      <span className="cost-model-tokens">{month.seatValueMultiple.toFixed(1)}×</span>
+      <span className="cost-model-cost">
+        {formatUsd(month.apiCost)} / {formatUsd(value.pricePerSeat ?? 0)}
+      </span>
+    </div>
+  );
+}
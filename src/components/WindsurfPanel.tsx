@@ -0,0 +1,186 @@
+import { useEffect, useState, useCallback } from 'react';
+import { backend } from '../services/backend';
+import type { WindsurfData } from '../types/models';
+import { formatPlanType, getProgressStyle } from '../utils/quota_format';
+
+interface WindsurfPanelProps {
+  onConnectionChange?: (connected: boolean) => void;
+  onUsageChange?: (usedPercent: number | null) => void;
+  autoRefreshIntervalMs?: number;
+  manualRefreshNonce?: number;
+  onLoadingChange?: (loading: boolean) => void;
+}
+
+function formatResetDate(resetAt?: string): string {
+  if (!resetAt) return '';
+  try {
+    const date = new Date(resetAt);
+    if (Number.isNaN(date.getTime())) return '';
+    const now = new Date();
+    const diff = date.getTime() - now.getTime();
+    if (diff <= 0) return 'Resets soon';
+    const days = Math.round(diff / (1000 * 60 * 60 * 24));
+    if (days >= 2) return `Resets in ${days}d`;
+    const hours = Math.round(diff / (1000 * 60 * 60));
+    return `Resets in ${hours}h`;
+  } catch {
+    return '';
+  }
+}
+
+function formatCredits(value: number): string {
+  return Number.isInteger(value) ? String(value) : value.toFixed(1);
+}
+
+function creditPercent(used?: number, limit?: number): number | null {
+  if (used == null || limit == null || limit <= 0) return null;
+  return (used / limit) * 100;
+}
+
+export default function WindsurfPanel({
+  onConnectionChange,
+  onUsageChange,
+  autoRefreshIntervalMs = 60 * 1000,
+  manualRefreshNonce = 0,
+  onLoadingChange,
+}: WindsurfPanelProps) {
+  const [windsurfData, setWindsurfData] = useState<WindsurfData | null>(null);
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchData = useCallback(async () => {
+    try {
+      setLoading(true);
+      setError(null);
+      const data = await backend.getWindsurfInfo();
+      setWindsurfData(data);
+      if (data.error) {
+        setError(data.error);
+      }
+      onConnectionChange?.(data.connected);
+      onUsageChange?.(data.percentage ?? null);
+    } catch (err) {
+      const message = err instanceof Error ? err.message : 'Failed to fetch Windsurf data';
+      setError(message);
+      onConnectionChange?.(false);
+      onUsageChange?.(null);
+    } finally {
+      setLoading(false);
+    }
+  }, [onConnectionChange, onUsageChange]);
+
+  useEffect(() => {
+    fetchData();
+    const interval = setInterval(fetchData, autoRefreshIntervalMs);
+    return () => clearInterval(interval);
+  }, [fetchData, autoRefreshIntervalMs]);
+
+  useEffect(() => {
+    onLoadingChange?.(loading);
+  }, [loading, onLoadingChange]);
+
+  useEffect(() => {
+    if (manualRefreshNonce > 0) {
+      fetchData();
+    }
+  }, [manualRefreshNonce, fetchData]);
+
+  const handleOpenDashboard = async () => {
+    try {
+      setError(null);
+      await backend.openWindsurfDashboard();
+    } catch (err) {
+      const message = err instanceof Error ? err.message : 'Failed to open Windsurf dashboard';
+      setError(message);
+    }
+  };
+
+  if (loading && !windsurfData) {
+    return (
+      <div className="codex-panel">
+        <div className="loading-state">Loading Windsurf info...</div>
+      </div>
+    );
+  }
+
+  const resetLabel = formatResetDate(windsurfData?.resetAt);
+  const promptPercent = creditPercent(windsurfData?.promptUsed, windsurfData?.promptLimit);
+  const flowPercent = creditPercent(windsurfData?.flowUsed, windsurfData?.flowLimit);
+
+  return (
+    <div className="codex-panel">
+      {error && (
+        <div className="error-banner">
+          <span className="error-icon">!</span>
+          <span className="error-text">{error}</span>
+        </div>
+      )}
+
+      {windsurfData?.connected && (
+        <div className="codex-content">
+          <div className="section">
+            <div className="section-title">
+              CREDITS
+              <span className="plan-tag">
+                Windsurf {formatPlanType(windsurfData.planType, 'Unknown')}
+              </span>
+            </div>
+
+            {windsurfData.promptUsed != null && windsurfData.promptLimit != null && (
+              <div className="quota-card">
+                <div className="quota-header">
+                  <span className="quota-label">Prompt credits</span>
+                  <span className="quota-value">
+                    {formatCredits(windsurfData.promptUsed)} / {formatCredits(windsurfData.promptLimit)}
+                  </span>
+                </div>
+                {promptPercent != null && (
+                  <div className="progress-bar">
+                    <div className="progress-fill" style={getProgressStyle(promptPercent)} />
+                  </div>
+                )}
+                {resetLabel && <div className="reset-time">{resetLabel}</div>}
+              </div>
+            )}
+
+            {windsurfData.flowUsed != null && windsurfData.flowLimit != null && (
+              <div className="quota-card">
+                <div className="quota-header">
+                  <span className="quota-label">Flow action credits</span>
+                  <span className="quota-value">
+                    {formatCredits(windsurfData.flowUsed)} / {formatCredits(windsurfData.flowLimit)}
+                  </span>
+                </div>
+                {flowPercent != null && (
+                  <div className="progress-bar">
+                    <div className="progress-fill" style={getProgressStyle(flowPercent)} />
+                  </div>
+                )}
+              </div>
+            )}
+
+            {windsurfData.email && (
+              <div className="quota-card credits-card">
+                <div className="quota-header">
+                  <span className="quota-label">Account</span>
+                  <span className="quota-value email">{windsurfData.email}</span>
+                </div>
+              </div>
+            )}
+          </div>
+
+          <button className="open-dashboard-btn" onClick={handleOpenDashboard}>
+            Open Dashboard
+          </button>
+        </div>
+      )}
+
+      {!windsurfData?.connected && !error && (
+        <div className="empty-state">
+          <p>Windsurf not connected</p>
+          <p className="hint">Open Windsurf and sign in, or set WINDSURF_API_KEY</p>
+        </div>
+      )}
+    </div>
+  );
+}
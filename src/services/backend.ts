@@ -1,22 +1,46 @@
 import { invoke } from '@tauri-apps/api/core';
 import type {
   AntigravityData,
+  CacheEfficiencyReport,
+  ClaudeBlocksReport,
+  ClaudeStats,
+  CostAnomaly,
   CodexData,
   CodexRateLimits,
   CodexStats,
   CostOverview,
+  CostRangeRequest,
+  CostSeries,
+  CostSeriesBucket,
   CostSource,
   CursorData,
+  CustomProviderData,
+  ExportRequest,
+  ExportSummary,
+  QuotaCalibrationReport,
   QuotaData,
+  SessionList,
+  SessionQuery,
+  SubscriptionValueReport,
+  TrayWindows,
+  WindsurfData,
 } from '../types/models';
 
-type TrayService = 'claude' | 'codex' | 'cursor' | 'antigravity';
+type TrayService = 'claude' | 'codex' | 'cursor' | 'windsurf' | 'antigravity';
 
 export const backend = {
   getQuota() {
     return invoke<QuotaData>('get_quota');
   },
 
+  getClaudeStats() {
+    return invoke<ClaudeStats>('get_claude_stats');
+  },
+
+  getClaudeBlocks(hours = 24, force = false) {
+    return invoke<ClaudeBlocksReport>('get_claude_blocks', { hours, force });
+  },
+
   getCodexInfo() {
     return invoke<CodexData>('get_codex_info');
   },
@@ -33,19 +57,87 @@ export const backend = {
     return invoke<CursorData>('get_cursor_info');
   },
 
+  getWindsurfInfo() {
+    return invoke<WindsurfData>('get_windsurf_info');
+  },
+
   getAntigravityInfo() {
     return invoke<AntigravityData>('get_antigravity_info');
   },
 
-  getCostOverview(source: CostSource, force = false) {
+  getCustomProviders(force = false) {
+    return invoke<CustomProviderData[]>('get_custom_providers', { force });
+  },
+
+  getCostOverview(source: CostSource, force = false, range: CostRangeRequest | null = null) {
     return invoke<CostOverview>('get_cost_overview', {
       source,
-      currency: 'USD',
+      currency: null,
       timezone: null,
+      range,
       force,
     });
   },
 
+  getCostSeries(
+    source: CostSource,
+    since: string,
+    until: string | null = null,
+    bucket: CostSeriesBucket = 'day',
+    force = false,
+  ) {
+    return invoke<CostSeries>('get_cost_series', {
+      source,
+      since,
+      until,
+      bucket,
+      timezone: null,
+      force,
+    });
+  },
+
+  getSubscriptionValue(months: number | null = null, force = false) {
+    return invoke<SubscriptionValueReport>('get_subscription_value', { months, force });
+  },
+
+  getQuotaCalibration(days: number | null = null, force = false) {
+    return invoke<QuotaCalibrationReport>('get_quota_calibration', { days, force });
+  },
+
+  getCostAnomalies(since: string | null = null, until: string | null = null) {
+    return invoke<CostAnomaly[]>('get_cost_anomalies', { since, until });
+  },
+
+  getCacheEfficiency(
+    source: 'claude' | 'codex',
+    since: string | null = null,
+    until: string | null = null,
+    timezone: string | null = null,
+    force = false,
+  ) {
+    return invoke<CacheEfficiencyReport>('get_cache_efficiency', {
+      source,
+      since,
+      until,
+      timezone,
+      force,
+    });
+  },
+
+  listSessions(
+    source: 'claude' | 'codex' | 'all',
+    since: string | null = null,
+    until: string | null = null,
+    query: SessionQuery | null = null,
+    force = false,
+  ) {
+    return invoke<SessionList>('list_sessions', { source, since, until, query, force });
+  },
+
+  exportData(request: ExportRequest) {
+    return invoke<ExportSummary>('export_data', { request });
+  },
+
   openClaudeDashboard() {
     return invoke<void>('open_claude_dashboard');
   },
@@ -58,14 +150,25 @@ export const backend = {
     return invoke<void>('open_cursor_dashboard');
   },
 
+  openWindsurfDashboard() {
+    return invoke<void>('open_windsurf_dashboard');
+  },
+
   openAntigravityDashboard() {
     return invoke<void>('open_antigravity_dashboard');
   },
 
-  updateTrayIcon(service: TrayService, percentage: number | null, visible: boolean) {
+  updateTrayIcon(
+    service: TrayService,
+    percentage: number | null,
+    visible: boolean,
+    windows: TrayWindows | null = null,
+  ) {
+    const round = (value?: number | null) => (value == null ? null : Math.round(value));
     return invoke<void>('update_tray_icon', {
       service,
-      percentage: percentage == null ? null : Math.round(percentage),
+      percentage: round(percentage),
+      windows: windows && { session: round(windows.session), weekly: round(windows.weekly) },
       visible,
     });
   },
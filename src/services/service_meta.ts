@@ -11,7 +11,7 @@ export interface ServiceMeta {
   disconnectedHint: string;
 }
 
-export const SERVICES: TrayServiceName[] = ['claude', 'codex', 'cursor', 'antigravity'];
+export const SERVICES: TrayServiceName[] = ['claude', 'codex', 'cursor', 'windsurf', 'antigravity'];
 
 export const SERVICE_META: Record<TrayServiceName, ServiceMeta> = {
   claude: {
@@ -41,6 +41,15 @@ export const SERVICE_META: Record<TrayServiceName, ServiceMeta> = {
     accent: '#ef4444',
     disconnectedHint: 'Requires Cursor sign-in or CURSOR_SESSION_TOKEN',
   },
+  windsurf: {
+    id: 'windsurf',
+    label: 'Windsurf',
+    shortLabel: 'Windsurf',
+    initials: 'Ws',
+    trayLabel: 'Windsurf Tray',
+    accent: '#0d9488',
+    disconnectedHint: 'Requires Windsurf sign-in or WINDSURF_API_KEY',
+  },
   antigravity: {
     id: 'antigravity',
     label: 'Antigravity',
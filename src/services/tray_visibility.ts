@@ -1,9 +1,10 @@
-export type TrayServiceName = 'claude' | 'codex' | 'cursor' | 'antigravity';
+export type TrayServiceName = 'claude' | 'codex' | 'cursor' | 'windsurf' | 'antigravity';
 
 const TRAY_STORAGE_KEYS: Record<TrayServiceName, string> = {
   claude: 'claude-tray-enabled',
   codex: 'codex-tray-enabled',
   cursor: 'cursor-tray-enabled',
+  windsurf: 'windsurf-tray-enabled',
   antigravity: 'antigravity-tray-enabled',
 };
 
@@ -11,6 +12,7 @@ const TRAY_DEFAULT_ENABLED: Record<TrayServiceName, boolean> = {
   claude: true,
   codex: true,
   cursor: true,
+  windsurf: false,
   antigravity: false,
 };
 
@@ -30,6 +30,24 @@ export interface CodexStats {
   lastActivity?: string;
 }
 
+export interface ClaudeNamedCount {
+  name: string;
+  total: number;
+  today: number;
+}
+
+export interface ClaudeStats {
+  totalSessions: number;
+  todaySessions: number;
+  totalProjects: number;
+  activeProjects: number;
+  lastActivity?: string;
+  toolCallsTotal: number;
+  toolCallsToday: number;
+  toolCalls: ClaudeNamedCount[];
+  messagesByModel: ClaudeNamedCount[];
+}
+
 export interface CodexRateLimitWindow {
   usedPercent: number;
   windowMinutes?: number;
@@ -42,12 +60,20 @@ export interface CodexCredits {
   balance?: string;
 }
 
+/** Used percent of a service's session and weekly windows for the tray. */
+export interface TrayWindows {
+  session?: number | null;
+  weekly?: number | null;
+}
+
 export interface CodexRateLimits {
   connected: boolean;
   planType?: string;
   primary?: CodexRateLimitWindow;
   secondary?: CodexRateLimitWindow;
   credits?: CodexCredits;
+  source?: 'api' | 'local';
+  updatedAt?: string;
   error?: string;
 }
 
@@ -63,13 +89,54 @@ export interface CursorData {
   error?: string;
 }
 
+export interface WindsurfData {
+  connected: boolean;
+  planType?: string;
+  email?: string;
+  promptUsed?: number;
+  promptLimit?: number;
+  flowUsed?: number;
+  flowLimit?: number;
+  percentage?: number;
+  resetAt?: string;
+  error?: string;
+}
+
+export interface CustomProviderWindow {
+  id?: string;
+  label: string;
+  used?: number;
+  limit?: number;
+  percentage?: number;
+  resetTime?: string;
+}
+
+export interface CustomProviderCost {
+  amount: number;
+  currency: string;
+  label?: string;
+}
+
+export interface CustomProviderData {
+  id: string;
+  name: string;
+  kind: string;
+  connected: boolean;
+  planType?: string;
+  windows: CustomProviderWindow[];
+  cost?: CustomProviderCost;
+  percentage?: number;
+  updatedAt?: string;
+  error?: string;
+}
+
 export interface AntigravityData {
   connected: boolean;
   status: string;
   error?: string;
 }
 
-export type CostSource = 'claude' | 'codex' | 'cursor';
+export type CostSource = 'claude' | 'codex' | 'cursor' | 'openai' | 'all';
 
 export interface CostTokenBreakdown {
   inputTokens: number;
@@ -87,6 +154,31 @@ export interface CostModelSummary {
   tokens: CostTokenBreakdown;
 }
 
+export interface CostProjectSummary {
+  project: string;
+  tag?: string | null;
+  cost?: number | null;
+  costUsd?: number | null;
+  tokens: CostTokenBreakdown;
+}
+
+export interface CostTagSummary {
+  tag: string;
+  cost?: number | null;
+  costUsd?: number | null;
+  tokens: CostTokenBreakdown;
+  projects: string[];
+}
+
+export interface CostSourceSubtotal {
+  source: string;
+  displayName: string;
+  cost?: number | null;
+  costUsd?: number | null;
+  tokens: CostTokenBreakdown;
+  error?: string | null;
+}
+
 export interface CostRangeSummary {
   range: string;
   label: string;
@@ -97,9 +189,75 @@ export interface CostRangeSummary {
   costUsd?: number | null;
   tokens: CostTokenBreakdown;
   models: CostModelSummary[];
+  projects: CostProjectSummary[];
+  tags?: CostTagSummary[];
+  sources?: CostSourceSubtotal[];
   validEntries: number;
   skippedEntries: number;
   elapsedMs: number;
+  comparison?: CostRangeComparison | null;
+}
+
+export interface CostModelDelta {
+  model: string;
+  cost?: number | null;
+  previousCost?: number | null;
+  deltaCost?: number | null;
+  deltaPercent?: number | null;
+  totalTokens: number;
+  previousTotalTokens: number;
+}
+
+export interface CostRangeComparison {
+  since: string;
+  until: string;
+  cost?: number | null;
+  costUsd?: number | null;
+  tokens: CostTokenBreakdown;
+  deltaCost?: number | null;
+  deltaPercent?: number | null;
+  models: CostModelDelta[];
+}
+
+export type CostRangePreset =
+  | 'today'
+  | 'week'
+  | 'month'
+  | 'last7Days'
+  | 'last30Days'
+  | 'previousWeek'
+  | 'previousMonth'
+  | 'custom';
+
+export interface CostRangeRequest {
+  preset?: CostRangePreset;
+  since?: string;
+  until?: string;
+  compare?: boolean;
+}
+
+export type CostSeriesBucket = 'hour' | 'day';
+
+export interface CostSeriesPoint {
+  start: string;
+  end: string;
+  date?: string | null;
+  cost: number;
+  tokens: CostTokenBreakdown;
+  entries: number;
+  models: CostModelSummary[];
+}
+
+export interface CostSeries {
+  source: string;
+  bucket: CostSeriesBucket;
+  currency: string;
+  since: string;
+  until: string;
+  generatedAt: string;
+  cost: number;
+  tokens: CostTokenBreakdown;
+  points: CostSeriesPoint[];
 }
 
 export interface CostOverview {
@@ -109,4 +267,242 @@ export interface CostOverview {
   generatedAt: string;
   cached: boolean;
   ranges: CostRangeSummary[];
+  budgets?: BudgetStatus[];
+  unpricedModels?: UnpricedModel[];
+  exchangeRate?: ExchangeRate | null;
+  /** The provider had more data than was fetched. */
+  truncated?: boolean;
+  /** Source, currency and timezone of a default-range overview. */
+  viewKey?: string | null;
+}
+
+export interface ExchangeRate {
+  currency: string;
+  rate: number;
+  date: string;
+  origin: 'file' | 'builtin';
+}
+
+export interface UnpricedModel {
+  model: string;
+  totalTokens: number;
+  /** Priced as a related model family; the cost is an estimate. */
+  guessed?: boolean;
+}
+
+export interface SubscriptionMonth {
+  month: string;
+  since: string;
+  until: string;
+  partial: boolean;
+  apiCost: number;
+  projectedCost?: number | null;
+  valueMultiple?: number | null;
+  seatValueMultiple?: number | null;
+  unpricedEntries: number;
+}
+
+export interface SubscriptionValue {
+  source: string;
+  displayName: string;
+  plan?: string | null;
+  priceOrigin?: 'config' | 'catalog' | null;
+  pricePerSeat?: number | null;
+  seats: number;
+  monthlyPrice?: number | null;
+  months: SubscriptionMonth[];
+  error?: string | null;
+}
+
+export interface SubscriptionValueReport {
+  generatedAt: string;
+  currency: string;
+  sources: SubscriptionValue[];
+}
+
+export interface EstimateBand {
+  estimate: number;
+  low: number;
+  high: number;
+}
+
+export interface WindowCalibration {
+  source: 'claude' | 'codex';
+  window: string;
+  samples: number;
+  observedPercent: number;
+  tokensPerPercent?: EstimateBand | null;
+  costUsdPerPercent?: EstimateBand | null;
+  currentPercentage?: number | null;
+  resetsAt?: string | null;
+  remainingTokens?: EstimateBand | null;
+  remainingCostUsd?: EstimateBand | null;
+  confidence: 'none' | 'low' | 'medium' | 'high';
+}
+
+export interface QuotaCalibrationReport {
+  generatedAt: string;
+  days: number;
+  windows: WindowCalibration[];
+}
+
+export interface CostAnomaly {
+  detectedAt: string;
+  kind: 'hour' | 'session';
+  source: 'claude' | 'codex';
+  start: string;
+  end: string;
+  sessionId?: string | null;
+  costUsd: number;
+  baselineUsd: number;
+  ratio: number;
+  project: string;
+  model: string;
+}
+
+export interface CacheMetrics {
+  promptTokens: number;
+  inputTokens: number;
+  cacheCreationTokens: number;
+  cacheReadTokens: number;
+  hitRatio?: number | null;
+  promptCostUsd: number;
+  uncachedCostUsd: number;
+  savingsUsd: number;
+  writePremiumUsd: number;
+  readSavingsUsd: number;
+  readsPerWrite?: number | null;
+  breakEvenReads?: number | null;
+}
+
+export interface CacheGroup extends CacheMetrics {
+  name: string;
+}
+
+export interface CacheTrendPoint extends CacheMetrics {
+  date: string;
+}
+
+export interface CacheEfficiencyReport {
+  source: 'claude' | 'codex';
+  since: string;
+  until: string;
+  generatedAt: string;
+  total: CacheMetrics;
+  models: CacheGroup[];
+  projects: CacheGroup[];
+  trend: CacheTrendPoint[];
+  unpricedModels: string[];
+}
+
+export interface SessionQuery {
+  sort?: 'cost' | 'tokens' | 'start' | 'end' | 'duration' | null;
+  order?: 'asc' | 'desc' | null;
+  project?: string | null;
+  model?: string | null;
+  minCostUsd?: number | null;
+  limit?: number | null;
+  timezone?: string | null;
+}
+
+export interface SessionModel {
+  model: string;
+  entries: number;
+  tokens: CostTokenBreakdown;
+  costUsd?: number | null;
+  costShare?: number | null;
+}
+
+export interface SessionSummary {
+  source: 'claude' | 'codex';
+  sessionId: string;
+  start: string;
+  end: string;
+  durationMinutes: number;
+  project: string;
+  repository: string;
+  tag?: string | null;
+  entries: number;
+  tokens: CostTokenBreakdown;
+  costUsd: number;
+  models: SessionModel[];
+  unpricedModels: string[];
+}
+
+export interface SessionList {
+  source: 'claude' | 'codex' | 'all';
+  since: string;
+  until: string;
+  generatedAt: string;
+  matched: number;
+  costUsd: number;
+  sessions: SessionSummary[];
+}
+
+export type ExportDataset = 'cost' | 'history';
+
+export interface ExportRequest {
+  dataset: ExportDataset;
+  format?: 'csv' | 'jsonl' | null;
+  path: string;
+  source?: CostSource | null;
+  range?: CostRangeRequest | null;
+  since?: string | null;
+  until?: string | null;
+  timezone?: string | null;
+}
+
+export interface ExportSummary {
+  path: string;
+  dataset: ExportDataset;
+  format: 'csv' | 'jsonl';
+  rows: number;
+}
+
+export type BudgetLevel = 'ok' | 'warning' | 'exceeded';
+
+export interface BudgetStatus {
+  source: string;
+  period: 'daily' | 'weekly' | 'monthly' | string;
+  range: string;
+  since?: string | null;
+  amount: number;
+  spent: number;
+  percent: number;
+  threshold?: number | null;
+  level: BudgetLevel;
+  tray: boolean;
+}
+
+export interface ClaudeBlockProject {
+  project: string;
+  entries: number;
+  totalTokens: number;
+  costUsd: number;
+}
+
+export interface ClaudeBlock {
+  startTime: string;
+  endTime: string;
+  firstActivity: string;
+  lastActivity: string;
+  activeMinutes: number;
+  isActive: boolean;
+  anchored: boolean;
+  entries: number;
+  tokens: CostTokenBreakdown;
+  costUsd: number;
+  models: string[];
+  unpricedModels: string[];
+  projects: ClaudeBlockProject[];
+  projectedCostUsd?: number;
+  projectedTokens?: number;
+  projectedLimitAt?: string;
+}
+
+export interface ClaudeBlocksReport {
+  generatedAt: string;
+  sessionResetTime?: string;
+  sessionPercentage?: number;
+  blocks: ClaudeBlock[];
 }
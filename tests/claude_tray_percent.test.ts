@@ -5,6 +5,7 @@ import {
   BACKOFF_REFRESH_INTERVAL_MS,
   getClaudeRefreshIntervalMs,
   getClaudeTrayUsedPercent,
+  getClaudeTrayWindows,
 } from '../src/App';
 import type { QuotaData, UsageInfo } from '../src/types/models';
 
@@ -47,6 +48,25 @@ describe('getClaudeTrayUsedPercent', () => {
   });
 });
 
+describe('getClaudeTrayWindows', () => {
+  test('reports session and weekly windows for the dual-ring tray', () => {
+    expect(getClaudeTrayWindows({
+      connected: true,
+      session: usage(64),
+      weeklyOpus: usage(20),
+      weeklySonnet: usage(35),
+    })).toEqual({ session: 64, weekly: 35 });
+  });
+
+  test('leaves missing windows empty', () => {
+    expect(getClaudeTrayWindows({ connected: true, weeklyTotal: usage(50) })).toEqual({
+      session: null,
+      weekly: 50,
+    });
+    expect(getClaudeTrayWindows(null)).toBeNull();
+  });
+});
+
 describe('getClaudeRefreshIntervalMs', () => {
   test('uses normal polling when Claude quota succeeds', () => {
     expect(getClaudeRefreshIntervalMs(null)).toBe(AUTO_REFRESH_INTERVAL_MS);